@@ -0,0 +1,671 @@
+//! Morphology analysis: fractal dimension estimators and shape statistics.
+//!
+//! Radial quantities are measured from the centroid of the seed particles
+//! (age 0), so clusters grown from off-center seeds are measured from where
+//! they started. Each dimension comes from a least-squares fit of a log-log
+//! curve over a configurable range, with a confidence interval from the
+//! standard error of the slope:
+//!
+//! - mass-radius: particles within r of the seed centroid, `M(r) ~ r^D`
+//! - radius of gyration: Rg over the growth history, `N ~ Rg^D`
+//! - two-point correlation: pair density at distance r, `C(r) ~ r^(D - d)`
+//! - sandbox: mean mass within r of a particle, `<M(r)> ~ r^D`
+//!
+//! The last two average over particles in the inner half of the cluster, so
+//! their shells stay inside it up to half the cluster radius.
+//!
+//! Lacunarity (gliding box) and the angular mass distribution describe the
+//! gaps and the symmetry of the cluster and have no fit. 3D runs measure the
+//! voxels, except for those two, which use the x-y projection. Cluster-cluster
+//! aggregation has no growth order, so it gets no radius of gyration.
+
+use crate::settings::GrowthModel;
+use crate::simulation::DlaSimulation;
+use serde::Serialize;
+use std::f32::consts::{PI, TAU};
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+/// Highest angular harmonic reported (6 picks out six-fold symmetry)
+const ANGULAR_HARMONICS: usize = 6;
+
+/// Options of a morphology analysis
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnalysisSettings {
+    /// Radii the radial estimators are fitted over, in cells
+    /// (None = from 2 cells to half the cluster radius)
+    pub radius_range: Option<(f32, f32)>,
+    /// Particle counts the radius of gyration is fitted over
+    /// (None = from a hundredth of the particles, at least 20, to all)
+    pub mass_range: Option<(usize, usize)>,
+    /// Confidence level of the dimension intervals (0.5-0.999)
+    pub confidence: f32,
+    /// Points sampled on each log-scaled curve
+    pub samples: usize,
+    /// Most reference particles of the correlation function and the sandbox method
+    pub reference_points: usize,
+    /// Bins of the angular mass distribution
+    pub angular_bins: usize,
+}
+
+impl Default for AnalysisSettings {
+    fn default() -> Self {
+        Self {
+            radius_range: None,
+            mass_range: None,
+            confidence: 0.95,
+            samples: 24,
+            reference_points: 500,
+            angular_bins: 36,
+        }
+    }
+}
+
+/// Dimension from the slope of a log-log fit
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct DimensionFit {
+    pub dimension: f32,
+    pub std_error: f32,
+    /// Confidence interval of the dimension at the configured level
+    pub low: f32,
+    pub high: f32,
+    pub r_squared: f32,
+    /// Range of the curve's x values that was fitted
+    pub range: (f32, f32),
+    /// Samples inside the range
+    pub points: usize,
+}
+
+impl DimensionFit {
+    /// Half-width of the confidence interval
+    pub fn margin(&self) -> f32 {
+        (self.high - self.low) / 2.0
+    }
+}
+
+/// A sampled curve and the dimension fitted to it
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Estimate {
+    /// (x, y) samples: (r, M) mass-radius, (N, Rg) gyration, (r, C) correlation, `(r, <M>)` sandbox
+    pub samples: Vec<(f32, f32)>,
+    /// None when fewer than three samples fall in the fit range
+    pub fit: Option<DimensionFit>,
+}
+
+impl Estimate {
+    pub fn dimension(&self) -> Option<f32> {
+        self.fit.map(|fit| fit.dimension)
+    }
+}
+
+/// Particles per angle around the seed centroid
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct AngularMass {
+    /// Particle counts of equal angle bins, starting at -180 degrees
+    pub bins: Vec<usize>,
+    /// Strength of the k-fold symmetry for k = 1..=6, |mean of e^(ik angle)|
+    /// (0 = none, 1 = all particles on k rays)
+    pub harmonics: Vec<f32>,
+}
+
+/// Results of every estimator for one cluster
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Morphology {
+    pub particles: usize,
+    /// 2 for planar runs, 3 for voxel runs
+    pub embedding: usize,
+    /// Seed centroid the radial estimators are measured from
+    pub center: [f32; 3],
+    /// Farthest particle from the center
+    pub radius: f32,
+    pub confidence: f32,
+    pub mass_radius: Estimate,
+    /// None for clusters without a growth order (cluster-cluster aggregation)
+    pub gyration: Option<Estimate>,
+    pub correlation: Estimate,
+    pub sandbox: Estimate,
+    /// (box size, lacunarity) of the gliding-box method
+    pub lacunarity: Vec<(f32, f32)>,
+    pub angular: AngularMass,
+}
+
+impl Morphology {
+    /// Analyze the current cluster (voxels in 3D runs, discs off-lattice)
+    pub fn from_simulation(sim: &DlaSimulation, settings: &AnalysisSettings) -> Self {
+        let (mut points, embedding) = cluster_points(sim);
+        // Growth order, for the radius of gyration
+        points.sort_by(|a, b| a.1.cmp(&b.1).then(a.0[1].total_cmp(&b.0[1])).then(a.0[0].total_cmp(&b.0[0])));
+        let seeds = points.iter().take_while(|(_, age)| *age == 0).count();
+        let points: Vec<[f32; 3]> = points.into_iter().map(|(p, _)| p).collect();
+        let mut morphology = Self::from_points(&points, seeds, embedding, settings);
+        // DLCA ages count merges, not the order particles joined the cluster
+        if sim.settings.active_growth_model() == GrowthModel::Dlca {
+            morphology.gyration = None;
+        }
+        morphology
+    }
+
+    /// Analyze particles given in growth order, the first `seeds` of them
+    /// being the seed (with no seed, radii are measured from the centroid)
+    pub fn from_points(points: &[[f32; 3]], seeds: usize, embedding: usize, settings: &AnalysisSettings) -> Self {
+        let mut morphology = Self {
+            particles: points.len(),
+            embedding,
+            confidence: settings.confidence,
+            ..Self::default()
+        };
+        if points.is_empty() {
+            return morphology;
+        }
+
+        let seeds = if seeds == 0 { points.len() } else { seeds.min(points.len()) };
+        let center = centroid(&points[..seeds]);
+        let radius = points.iter().map(|p| distance(p, &center)).fold(0.0, f32::max);
+        morphology.center = center;
+        morphology.radius = radius;
+
+        // Log-spaced radii from one cell to the cluster radius
+        let samples = settings.samples.max(4);
+        let r_max = radius.max(2.0);
+        let radii: Vec<f32> = (0..samples)
+            .map(|i| r_max.powf(i as f32 / (samples - 1) as f32))
+            .collect();
+        let (r_low, r_high) = settings.radius_range.unwrap_or((2.0, radius / 2.0));
+        let confidence = settings.confidence;
+
+        // Mass-radius from the seed centroid
+        let counts = cumulative(&radial_histogram(points, &center, &radii));
+        let samples_mr: Vec<(f32, f32)> = radii.iter().zip(&counts).map(|(&r, &m)| (r, m as f32)).collect();
+        morphology.mass_radius = Estimate {
+            fit: fit_dimension(&samples_mr, (r_low, r_high), confidence, |x, y| (x.ln(), y.ln()), 0.0),
+            samples: samples_mr,
+        };
+
+        morphology.gyration = Some(gyration(points, samples, settings.mass_range, confidence));
+
+        // Reference particles in the inner half of the cluster, spread evenly
+        // over it, so shells up to half the radius stay inside the cluster
+        let inner: Vec<&[f32; 3]> = points.iter().filter(|p| distance(p, &center) <= radius / 2.0).collect();
+        let stride = inner.len().div_ceil(settings.reference_points.max(1)).max(1);
+        let references: Vec<&[f32; 3]> = inner.into_iter().step_by(stride).collect();
+
+        // Pair counts in shells around every reference, shared by the
+        // correlation and sandbox estimates
+        let mut shells = vec![0u64; radii.len()];
+        for reference in &references {
+            for (shell, count) in shells.iter_mut().zip(radial_histogram(points, reference, &radii)) {
+                *shell += count;
+            }
+        }
+
+        // Two-point correlation: pair density in each shell
+        let ball = |r: f32| if embedding == 3 { 4.0 / 3.0 * PI * r.powi(3) } else { PI * r * r };
+        let samples_c: Vec<(f32, f32)> = (1..radii.len())
+            .filter(|&i| shells[i] > 0)
+            .map(|i| {
+                let volume = ball(radii[i]) - ball(radii[i - 1]);
+                ((radii[i] * radii[i - 1]).sqrt(), shells[i] as f32 / (references.len() as f32 * volume))
+            })
+            .collect();
+        morphology.correlation = Estimate {
+            fit: fit_dimension(&samples_c, (r_low, r_high), confidence, |x, y| (x.ln(), y.ln()), embedding as f32),
+            samples: samples_c,
+        };
+
+        // Sandbox: mean mass within each radius of the references
+        let masses = cumulative(&shells);
+        let samples_s: Vec<(f32, f32)> = radii
+            .iter()
+            .zip(&masses)
+            .filter(|_| !references.is_empty())
+            .map(|(&r, &m)| (r, m as f32 / references.len() as f32))
+            .collect();
+        morphology.sandbox = Estimate {
+            fit: fit_dimension(&samples_s, (r_low, r_high), confidence, |x, y| (x.ln(), y.ln()), 0.0),
+            samples: samples_s,
+        };
+
+        morphology.lacunarity = lacunarity(points);
+        morphology.angular = angular_mass(points, &center, settings.angular_bins.max(1));
+        morphology
+    }
+
+    /// One line per dimension estimate, as "name D ± margin"
+    pub fn summary(&self) -> Vec<String> {
+        self.estimates()
+            .iter()
+            .map(|(name, estimate)| match estimate.map(|e| e.fit) {
+                Some(Some(fit)) => {
+                    format!("{} D {:.2} ± {:.2} (R²={:.2})", name, fit.dimension, fit.margin(), fit.r_squared)
+                }
+                Some(None) => format!("{} D --", name),
+                None => format!("{} D n/a (no growth order)", name),
+            })
+            .collect()
+    }
+
+    /// The estimators with their export names (None where one doesn't apply)
+    pub fn estimates(&self) -> [(&'static str, Option<&Estimate>); 4] {
+        [
+            ("mass_radius", Some(&self.mass_radius)),
+            ("gyration", self.gyration.as_ref()),
+            ("correlation", Some(&self.correlation)),
+            ("sandbox", Some(&self.sandbox)),
+        ]
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|e| format!("Failed to serialize analysis: {}", e))
+    }
+
+    /// Every sampled curve as `estimator,x,y,fitted` rows (fitted is 1 inside the fit range)
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from("estimator,x,y,fitted\n");
+        for (name, estimate) in self.estimates() {
+            let Some(estimate) = estimate else {
+                continue;
+            };
+            for &(x, y) in &estimate.samples {
+                let fitted = estimate.fit.is_some_and(|fit| x >= fit.range.0 && x <= fit.range.1);
+                let _ = writeln!(out, "{},{},{},{}", name, x, y, fitted as u8);
+            }
+        }
+        for &(size, value) in &self.lacunarity {
+            let _ = writeln!(out, "lacunarity,{},{},0", size, value);
+        }
+        let bins = self.angular.bins.len();
+        for (i, &count) in self.angular.bins.iter().enumerate() {
+            let angle = -180.0 + (i as f32 + 0.5) * 360.0 / bins as f32;
+            let _ = writeln!(out, "angular,{},{},0", angle, count);
+        }
+        out
+    }
+
+    /// Whether a file name picks the analysis in the export popup: .csv or
+    /// .analysis.json (plain .json is a config)
+    pub fn is_analysis_filename(filename: &str) -> bool {
+        let lower = filename.to_lowercase();
+        lower.ends_with(".csv") || lower.ends_with(".analysis.json")
+    }
+
+    /// Write the analysis as CSV curves for a .csv path, JSON otherwise
+    pub fn save_to_file(&self, path: &Path) -> Result<(), String> {
+        let is_csv = path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("csv"));
+        let content = if is_csv { self.to_csv() } else { self.to_json()? };
+        fs::write(path, content).map_err(|e| format!("Failed to write analysis file: {}", e))
+    }
+}
+
+/// Particle centers with their ages (voxels in 3D runs, discs off-lattice),
+/// and the embedding dimension
+pub(crate) fn cluster_points(sim: &DlaSimulation) -> (Vec<([f32; 3], usize)>, usize) {
+    if sim.settings.voxels {
+        let points = sim
+            .voxels()
+            .iter()
+            .map(|v| ([v.x as f32 + 0.5, v.y as f32 + 0.5, v.z as f32 + 0.5], v.data.age))
+            .collect();
+        (points, 3)
+    } else if sim.settings.off_lattice {
+        (sim.discs().iter().map(|d| ([d.x, d.y, 0.0], d.data.age)).collect(), 2)
+    } else {
+        let lattice = sim.settings.active_lattice();
+        let points = sim
+            .particles()
+            .map(|(x, y, p)| {
+                let (cx, cy) = lattice.cell_center(x, y);
+                ([cx, cy, 0.0], p.age)
+            })
+            .collect();
+        (points, 2)
+    }
+}
+
+fn distance(a: &[f32; 3], b: &[f32; 3]) -> f32 {
+    ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)).sqrt()
+}
+
+fn centroid(points: &[[f32; 3]]) -> [f32; 3] {
+    let mut sum = [0.0f64; 3];
+    for p in points {
+        for (s, v) in sum.iter_mut().zip(p) {
+            *s += *v as f64;
+        }
+    }
+    sum.map(|s| (s / points.len() as f64) as f32)
+}
+
+/// Distances from `center` binned by `radii`: bin i counts the points with
+/// radii[i - 1] < d <= radii[i], and bin 0 those within radii[0]
+fn radial_histogram(points: &[[f32; 3]], center: &[f32; 3], radii: &[f32]) -> Vec<u64> {
+    let mut bins = vec![0u64; radii.len()];
+    for p in points {
+        let d = distance(p, center);
+        let bin = radii.partition_point(|&r| r < d);
+        if let Some(count) = bins.get_mut(bin) {
+            *count += 1;
+        }
+    }
+    bins
+}
+
+/// Running totals of a histogram
+fn cumulative(bins: &[u64]) -> Vec<u64> {
+    bins.iter()
+        .scan(0, |total, &count| {
+            *total += count;
+            Some(*total)
+        })
+        .collect()
+}
+
+/// Radius of gyration over the growth history, sampled at log-spaced particle counts
+fn gyration(points: &[[f32; 3]], samples: usize, mass_range: Option<(usize, usize)>, confidence: f32) -> Estimate {
+    let n = points.len();
+    let mut checkpoints: Vec<usize> = (0..samples)
+        .map(|i| (n as f32).powf(i as f32 / (samples - 1) as f32).round() as usize)
+        .filter(|&count| count >= 2)
+        .collect();
+    checkpoints.dedup();
+
+    let (mut sum, mut sum_sq) = ([0.0f64; 3], 0.0f64);
+    let mut next = checkpoints.iter().peekable();
+    let mut curve = Vec::new();
+    for (i, p) in points.iter().enumerate() {
+        for (s, v) in sum.iter_mut().zip(p) {
+            *s += *v as f64;
+        }
+        sum_sq += p.iter().map(|v| (*v as f64).powi(2)).sum::<f64>();
+        let count = i + 1;
+        if next.peek() == Some(&&count) {
+            next.next();
+            let mean_sq: f64 = sum.iter().map(|s| (s / count as f64).powi(2)).sum();
+            let rg = (sum_sq / count as f64 - mean_sq).max(0.0).sqrt();
+            curve.push((count as f32, rg as f32));
+        }
+    }
+
+    let (low, high) = mass_range.unwrap_or(((n / 100).max(20), n));
+    // N ~ Rg^D: the dimension is the slope of ln N against ln Rg
+    let fit = fit_dimension(
+        &curve,
+        (low as f32, high as f32),
+        confidence,
+        |count, rg| (rg.max(f32::MIN_POSITIVE).ln(), count.ln()),
+        0.0,
+    );
+    Estimate { samples: curve, fit }
+}
+
+/// Gliding-box lacunarity of the x-y occupancy, Λ(s) = <M²> / <M>² over
+/// every s x s box inside the bounding box, for s = 1, 2, 4, ... up to half its side
+fn lacunarity(points: &[[f32; 3]]) -> Vec<(f32, f32)> {
+    let cells: Vec<(i64, i64)> = points.iter().map(|p| (p[0].floor() as i64, p[1].floor() as i64)).collect();
+    let (x0, x1) = (cells.iter().map(|c| c.0).min().unwrap_or(0), cells.iter().map(|c| c.0).max().unwrap_or(0));
+    let (y0, y1) = (cells.iter().map(|c| c.1).min().unwrap_or(0), cells.iter().map(|c| c.1).max().unwrap_or(0));
+    let (w, h) = ((x1 - x0 + 1) as usize, (y1 - y0 + 1) as usize);
+
+    // Summed-area table of the occupancy
+    let mut occupied = vec![false; w * h];
+    for &(x, y) in &cells {
+        occupied[(y - y0) as usize * w + (x - x0) as usize] = true;
+    }
+    let mut table = vec![0u32; (w + 1) * (h + 1)];
+    for y in 0..h {
+        for x in 0..w {
+            table[(y + 1) * (w + 1) + x + 1] = occupied[y * w + x] as u32 + table[y * (w + 1) + x + 1]
+                + table[(y + 1) * (w + 1) + x]
+                - table[y * (w + 1) + x];
+        }
+    }
+
+    let mut curve = Vec::new();
+    let mut size = 1;
+    while size * 2 <= w.min(h) || size == 1 {
+        let (mut sum, mut sum_sq, mut boxes) = (0.0f64, 0.0f64, 0.0f64);
+        for y in 0..=h.saturating_sub(size) {
+            for x in 0..=w.saturating_sub(size) {
+                let (xe, ye) = ((x + size).min(w), (y + size).min(h));
+                let mass = (table[ye * (w + 1) + xe] + table[y * (w + 1) + x]
+                    - table[y * (w + 1) + xe]
+                    - table[ye * (w + 1) + x]) as f64;
+                sum += mass;
+                sum_sq += mass * mass;
+                boxes += 1.0;
+            }
+        }
+        let mean = sum / boxes;
+        if mean > 0.0 {
+            curve.push((size as f32, (sum_sq / boxes / (mean * mean)) as f32));
+        }
+        size *= 2;
+    }
+    curve
+}
+
+/// Angular mass distribution and symmetry harmonics around `center`
+fn angular_mass(points: &[[f32; 3]], center: &[f32; 3], bins: usize) -> AngularMass {
+    let mut angular = AngularMass {
+        bins: vec![0; bins],
+        harmonics: Vec::new(),
+    };
+    let mut moments = [(0.0f64, 0.0f64); ANGULAR_HARMONICS];
+    let mut counted = 0;
+    for p in points {
+        let (dx, dy) = (p[0] - center[0], p[1] - center[1]);
+        if dx == 0.0 && dy == 0.0 {
+            continue;
+        }
+        let angle = dy.atan2(dx);
+        let bin = (((angle + PI) / TAU * bins as f32) as usize).min(bins - 1);
+        angular.bins[bin] += 1;
+        for (k, moment) in moments.iter_mut().enumerate() {
+            let phase = (k + 1) as f64 * angle as f64;
+            moment.0 += phase.cos();
+            moment.1 += phase.sin();
+        }
+        counted += 1;
+    }
+    angular.harmonics = moments
+        .iter()
+        .map(|(c, s)| if counted > 0 { (c.hypot(*s) / counted as f64) as f32 } else { 0.0 })
+        .collect();
+    angular
+}
+
+/// Fit a line to the samples whose x lies in `range`, after mapping each
+/// (x, y) to log space with `transform`; the dimension is `offset` plus the slope
+fn fit_dimension(
+    samples: &[(f32, f32)],
+    range: (f32, f32),
+    confidence: f32,
+    transform: impl Fn(f32, f32) -> (f32, f32),
+    offset: f32,
+) -> Option<DimensionFit> {
+    let (xs, ys): (Vec<f64>, Vec<f64>) = samples
+        .iter()
+        .filter(|&&(x, y)| x >= range.0 && x <= range.1 && x > 0.0 && y > 0.0)
+        .map(|&(x, y)| {
+            let (lx, ly) = transform(x, y);
+            (lx as f64, ly as f64)
+        })
+        .unzip();
+    let n = xs.len();
+    if n < 3 {
+        return None;
+    }
+
+    let (mean_x, mean_y) = (xs.iter().sum::<f64>() / n as f64, ys.iter().sum::<f64>() / n as f64);
+    let sxx: f64 = xs.iter().map(|x| (x - mean_x).powi(2)).sum();
+    let sxy: f64 = xs.iter().zip(&ys).map(|(x, y)| (x - mean_x) * (y - mean_y)).sum();
+    let syy: f64 = ys.iter().map(|y| (y - mean_y).powi(2)).sum();
+    if sxx <= 0.0 {
+        return None;
+    }
+    let slope = sxy / sxx;
+    let residual = (syy - slope * sxy).max(0.0);
+    let std_error = (residual / (n - 2) as f64 / sxx).sqrt();
+    let r_squared = if syy > 0.0 { 1.0 - residual / syy } else { 1.0 };
+    let margin = t_quantile(0.5 + confidence.clamp(0.5, 0.999) as f64 / 2.0, (n - 2) as f64) * std_error;
+
+    let dimension = offset as f64 + slope;
+    Some(DimensionFit {
+        dimension: dimension as f32,
+        std_error: std_error as f32,
+        low: (dimension - margin) as f32,
+        high: (dimension + margin) as f32,
+        r_squared: r_squared as f32,
+        range,
+        points: n,
+    })
+}
+
+/// Quantile of the standard normal distribution (Acklam's rational approximation)
+fn normal_quantile(p: f64) -> f64 {
+    const A: [f64; 6] = [-39.69683028665376, 220.9460984245205, -275.9285104469687, 138.357751867269, -30.66479806614716, 2.506628277459239];
+    const B: [f64; 5] = [-54.47609879822406, 161.5858368580409, -155.6989798598866, 66.80131188771972, -13.28068155288572];
+    const C: [f64; 6] = [-0.007784894002430293, -0.3223964580411365, -2.400758277161838, -2.549732539343734, 4.374664141464968, 2.938163982698783];
+    const D: [f64; 4] = [0.007784695709041462, 0.3224671290700398, 2.445134137142996, 3.754408661907416];
+    let tail = |q: f64| {
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    };
+    if p < 0.02425 {
+        tail((-2.0 * p.ln()).sqrt())
+    } else if p > 1.0 - 0.02425 {
+        -tail((-2.0 * (1.0 - p).ln()).sqrt())
+    } else {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    }
+}
+
+/// Quantile of Student's t distribution with `df` degrees of freedom: exact
+/// for one and two, a Cornish-Fisher expansion of the normal quantile above
+fn t_quantile(p: f64, df: f64) -> f64 {
+    if df < 1.5 {
+        return (PI as f64 * (p - 0.5)).tan();
+    }
+    if df < 2.5 {
+        return (2.0 * p - 1.0) / (2.0 * p * (1.0 - p)).sqrt();
+    }
+    let z = normal_quantile(p);
+    let (z3, z5, z7) = (z.powi(3), z.powi(5), z.powi(7));
+    z + (z3 + z) / (4.0 * df)
+        + (5.0 * z5 + 16.0 * z3 + 3.0 * z) / (96.0 * df * df)
+        + (3.0 * z7 + 19.0 * z5 + 17.0 * z3 - 15.0 * z) / (384.0 * df.powi(3))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filled_disc_is_two_dimensional() {
+        // A filled disc of radius 40 grown outward, seeded at its middle
+        let mut points: Vec<[f32; 3]> = Vec::new();
+        for y in -40..=40 {
+            for x in -40..=40 {
+                if (x * x + y * y) as f32 <= 1600.0 {
+                    points.push([x as f32 + 100.5, y as f32 + 50.5, 0.0]);
+                }
+            }
+        }
+        points.sort_by(|a, b| (a[0] - 100.5).hypot(a[1] - 50.5).total_cmp(&(b[0] - 100.5).hypot(b[1] - 50.5)));
+
+        let morphology = Morphology::from_points(&points, 1, 2, &AnalysisSettings::default());
+        assert_eq!(morphology.center, [100.5, 50.5, 0.0]);
+        assert!((morphology.radius - 40.0).abs() < 0.1);
+        for (name, estimate) in morphology.estimates() {
+            let fit = estimate.unwrap().fit.unwrap();
+            assert!((fit.dimension - 2.0).abs() < 0.15, "{} D = {}", name, fit.dimension);
+            assert!(fit.low <= fit.dimension && fit.dimension <= fit.high);
+        }
+        // No gaps: the lacunarity of a filled shape stays near 1
+        assert!(morphology.lacunarity.iter().skip(1).all(|&(_, value)| value < 1.5));
+        // Round: no angular harmonic stands out
+        assert!(morphology.angular.harmonics.iter().all(|&h| h < 0.05));
+        assert_eq!(morphology.angular.bins.iter().sum::<usize>(), points.len() - 1);
+    }
+
+    #[test]
+    fn test_line_is_one_dimensional_with_two_fold_symmetry() {
+        let points: Vec<[f32; 3]> = (0..400).map(|i| [(i / 2) as f32 * if i % 2 == 0 { 1.0 } else { -1.0 }, 0.5, 0.0]).collect();
+        let settings = AnalysisSettings {
+            radius_range: Some((4.0, 100.0)),
+            ..AnalysisSettings::default()
+        };
+        let morphology = Morphology::from_points(&points, 1, 2, &settings);
+        let fit = morphology.mass_radius.fit.unwrap();
+        assert!((fit.dimension - 1.0).abs() < 0.05, "D = {}", fit.dimension);
+        assert_eq!(fit.range, (4.0, 100.0));
+        assert!((morphology.angular.harmonics[1] - 1.0).abs() < 1e-3);
+
+        let csv = morphology.to_csv();
+        assert!(csv.starts_with("estimator,x,y,fitted\n"));
+        assert!(csv.lines().any(|line| line.starts_with("mass_radius,") && line.ends_with(",1")));
+    }
+
+    #[test]
+    fn test_off_center_dla_cluster_is_measured_from_its_seed() {
+        use crate::settings::BoundaryBehavior;
+        use crate::simulation::{SeedParam, SeedPattern};
+
+        let mut sim = DlaSimulation::new(112, 112);
+        sim.num_particles = 500;
+        sim.settings.boundary_behavior = BoundaryBehavior::Clamp;
+        sim.settings.walker_density = 0.02;
+        sim.seed_params.set(SeedParam::OffsetX, Some(-20.0));
+        sim.set_rng_seed(Some(4));
+        sim.reset_with_seed(SeedPattern::Point);
+        while !sim.is_complete() {
+            sim.step();
+        }
+
+        let morphology = Morphology::from_simulation(&sim, &AnalysisSettings::default());
+        assert_eq!(morphology.particles, 500);
+        assert_eq!(morphology.center, [36.5, 56.5, 0.0]);
+        for (name, estimate) in morphology.estimates() {
+            let fit = estimate.unwrap().fit.unwrap();
+            assert!((1.4..2.0).contains(&fit.dimension), "{} D = {}", name, fit.dimension);
+        }
+        // Sparse branches leave big gaps at small scales
+        assert!(morphology.lacunarity[0].1 > 2.0);
+    }
+
+    #[test]
+    fn test_dlca_cluster_has_no_gyration_estimate() {
+        use crate::settings::BoundaryBehavior;
+
+        let mut sim = DlaSimulation::new(48, 48);
+        sim.num_particles = 200;
+        sim.settings.growth_model = GrowthModel::Dlca;
+        sim.settings.boundary_behavior = BoundaryBehavior::Clamp;
+        sim.set_rng_seed(Some(2));
+        sim.reset();
+        for _ in 0..50 {
+            sim.step();
+        }
+
+        let morphology = Morphology::from_simulation(&sim, &AnalysisSettings::default());
+        assert_eq!(morphology.gyration, None);
+        assert!(morphology.summary()[1].contains("n/a"));
+        assert!(!morphology.to_csv().contains("gyration,"));
+        assert!(morphology.to_json().unwrap().contains("\"gyration\": null"));
+        assert!(Morphology::is_analysis_filename("curves.CSV"));
+        assert!(Morphology::is_analysis_filename("dla.analysis.json"));
+        assert!(!Morphology::is_analysis_filename("dla-config.json"));
+    }
+
+    #[test]
+    fn test_confidence_interval_uses_the_t_distribution() {
+        assert!((normal_quantile(0.975) - 1.959964).abs() < 1e-6);
+        assert!((t_quantile(0.975, 1.0) - 12.7062).abs() < 1e-3);
+        assert!((t_quantile(0.975, 2.0) - 4.3027).abs() < 1e-3);
+        assert!((t_quantile(0.975, 10.0) - 2.2281).abs() < 1e-3);
+        assert!((t_quantile(0.995, 22.0) - 2.8188).abs() < 1e-2);
+    }
+}
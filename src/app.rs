@@ -1,10 +1,26 @@
 use crate::braille;
-use crate::color::{ColorLut, ColorScheme};
-use crate::config::AppConfig;
-use crate::recorder::Recorder;
-use crate::simulation::{DlaSimulation, SeedPattern};
+use dla_sim_tui::analysis::{AnalysisSettings, Morphology};
+use dla_sim_tui::metrics::{self, MetricsLog};
+use dla_sim_tui::color::{ColorLut, ColorScheme};
+use dla_sim_tui::config::AppConfig;
+use dla_sim_tui::graph::{ClusterGraph, GraphFormat};
+use dla_sim_tui::harmonic::{self, HarmonicMeasure, MultifractalSpectrum};
+use dla_sim_tui::presets::{Preset, PresetManager};
+use dla_sim_tui::recorder::Recorder;
+use dla_sim_tui::settings::VoxelView;
+use dla_sim_tui::simulation::{DlaSimulation, SeedParam, SeedPattern};
+use dla_sim_tui::snapshot::{self, Snapshot};
+use dla_sim_tui::viewport::Viewport;
+use dla_sim_tui::voxel::{self, PointFormat, Voxel};
 use std::path::Path;
 
+/// Turn of the rotating 3D view per frame (radians)
+const VIEW_ROTATION_SPEED: f32 = 0.03;
+
+/// Distance evaluations the live morphology panel may spend per refresh
+/// (reference points x particles); exports always use every reference point
+const LIVE_ANALYSIS_BUDGET: usize = 20_000_000;
+
 /// Popup menu state for Shift+letter parameter selection
 #[derive(Debug, Clone)]
 pub struct ParamPopup {
@@ -52,6 +68,25 @@ impl TextInputPopup {
     }
 }
 
+/// Preset browser popup state (list comes from the app's PresetManager)
+#[derive(Debug, Clone, Default)]
+pub struct PresetPopup {
+    pub selected_idx: usize,
+}
+
+/// Seed panel state: the highlighted parameter of the current seed pattern
+#[derive(Debug, Clone, Default)]
+pub struct SeedPopup {
+    pub selected_idx: usize,
+}
+
+/// What the snapshot filename popup does on confirm
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SnapshotAction {
+    Save,
+    Load,
+}
+
 /// View mode for the UI layout
 #[derive(Debug, Clone, Copy, PartialEq, Default)]
 pub enum ViewMode {
@@ -89,26 +124,36 @@ pub enum Focus {
     ColorScheme,
     Direction,
     EscapeMult,
+    Eta,            // dielectric breakdown exponent
     Force,
+    GrowthModel,    // DLA, RLA, ballistic, Eden, DBM or DLCA
     Highlight,
     Invert,
+    Jump,           // toggle circle jumping on/off
+    LatticeType,    // square/hex/triangular cells
     LatticeWalk,    // toggle lattice walk on/off
     MaxIterations,
     MinRadius,
+    Mobility,       // cluster-cluster mobility exponent
     Mode,
     MultiContact,
     Neighborhood,
+    OffLattice,     // toggle off-lattice disc mode on/off
     Particles,
     RadialBias,
     Seed,
     SideSticky,
+    Slice,          // 3D slice depth
     Spawn,
     SpawnOffset,
     Speed,
     Stickiness,
     StickyGradient,
     TipSticky,
+    VoxelView,      // 3D view (slice/projection/rotate)
+    Voxels,         // toggle 3D voxel mode on/off
     WalkStep,
+    Walkers,
     // Controls box (not a param)
     Controls,
 }
@@ -118,13 +163,20 @@ impl Focus {
     pub fn next(&self) -> Focus {
         match self {
             Focus::None | Focus::Controls => Focus::AdaptiveStep,
-            // Movement: adaptive, adapt factor, direction, force, lattice, radial, walk
+            // Movement: adaptive, adapt factor, direction, eta, force, growth, jump, lat type, lattice, mobility, off-lattice, radial, voxels, walk
             Focus::AdaptiveStep => Focus::AdaptiveFactor,
             Focus::AdaptiveFactor => Focus::Direction,
-            Focus::Direction => Focus::Force,
-            Focus::Force => Focus::LatticeWalk,
-            Focus::LatticeWalk => Focus::RadialBias,
-            Focus::RadialBias => Focus::WalkStep,
+            Focus::Direction => Focus::Eta,
+            Focus::Eta => Focus::Force,
+            Focus::Force => Focus::GrowthModel,
+            Focus::GrowthModel => Focus::Jump,
+            Focus::Jump => Focus::LatticeType,
+            Focus::LatticeType => Focus::LatticeWalk,
+            Focus::LatticeWalk => Focus::Mobility,
+            Focus::Mobility => Focus::OffLattice,
+            Focus::OffLattice => Focus::RadialBias,
+            Focus::RadialBias => Focus::Voxels,
+            Focus::Voxels => Focus::WalkStep,
             // Sticking: contacts, gradient, neighbors, sticky, side stick, tip stick
             Focus::WalkStep => Focus::MultiContact,
             Focus::MultiContact => Focus::StickyGradient,
@@ -132,38 +184,48 @@ impl Focus {
             Focus::Neighborhood => Focus::Stickiness,
             Focus::Stickiness => Focus::SideSticky,
             Focus::SideSticky => Focus::TipSticky,
-            // Spawn: bound, escape, max steps, min radius, spawn, spawn off
+            // Spawn: bound, escape, max steps, min radius, spawn, spawn off, walkers
             Focus::TipSticky => Focus::Boundary,
             Focus::Boundary => Focus::EscapeMult,
             Focus::EscapeMult => Focus::MaxIterations,
             Focus::MaxIterations => Focus::MinRadius,
             Focus::MinRadius => Focus::Spawn,
             Focus::Spawn => Focus::SpawnOffset,
-            // Visual: age, color, highlight, invert, mode, particles, seed, speed
-            Focus::SpawnOffset => Focus::Age,
+            Focus::SpawnOffset => Focus::Walkers,
+            // Visual: age, color, highlight, invert, mode, particles, seed, slice, speed, view
+            Focus::Walkers => Focus::Age,
             Focus::Age => Focus::ColorScheme,
             Focus::ColorScheme => Focus::Highlight,
             Focus::Highlight => Focus::Invert,
             Focus::Invert => Focus::Mode,
             Focus::Mode => Focus::Particles,
             Focus::Particles => Focus::Seed,
-            Focus::Seed => Focus::Speed,
-            Focus::Speed => Focus::Speed, // Stop at boundary
+            Focus::Seed => Focus::Slice,
+            Focus::Slice => Focus::Speed,
+            Focus::Speed => Focus::VoxelView,
+            Focus::VoxelView => Focus::VoxelView, // Stop at boundary
         }
     }
 
     /// Navigate to previous parameter (grouped, matches UI order)
     pub fn prev(&self) -> Focus {
         match self {
-            Focus::None | Focus::Controls => Focus::Speed,
-            // Movement: adaptive, adapt factor, direction, force, lattice, radial, walk
+            Focus::None | Focus::Controls => Focus::VoxelView,
+            // Movement: adaptive, adapt factor, direction, eta, force, growth, jump, lat type, lattice, mobility, off-lattice, radial, voxels, walk
             Focus::AdaptiveStep => Focus::AdaptiveStep, // Stop at boundary
             Focus::AdaptiveFactor => Focus::AdaptiveStep,
             Focus::Direction => Focus::AdaptiveFactor,
-            Focus::Force => Focus::Direction,
-            Focus::LatticeWalk => Focus::Force,
-            Focus::RadialBias => Focus::LatticeWalk,
-            Focus::WalkStep => Focus::RadialBias,
+            Focus::Eta => Focus::Direction,
+            Focus::Force => Focus::Eta,
+            Focus::GrowthModel => Focus::Force,
+            Focus::Jump => Focus::GrowthModel,
+            Focus::LatticeType => Focus::Jump,
+            Focus::LatticeWalk => Focus::LatticeType,
+            Focus::Mobility => Focus::LatticeWalk,
+            Focus::OffLattice => Focus::Mobility,
+            Focus::RadialBias => Focus::OffLattice,
+            Focus::Voxels => Focus::RadialBias,
+            Focus::WalkStep => Focus::Voxels,
             // Sticking: contacts, gradient, neighbors, sticky, side stick, tip stick
             Focus::MultiContact => Focus::WalkStep,
             Focus::StickyGradient => Focus::MultiContact,
@@ -171,69 +233,82 @@ impl Focus {
             Focus::Stickiness => Focus::Neighborhood,
             Focus::SideSticky => Focus::Stickiness,
             Focus::TipSticky => Focus::SideSticky,
-            // Spawn: bound, escape, max steps, min radius, spawn, spawn off
+            // Spawn: bound, escape, max steps, min radius, spawn, spawn off, walkers
             Focus::Boundary => Focus::TipSticky,
             Focus::EscapeMult => Focus::Boundary,
             Focus::MaxIterations => Focus::EscapeMult,
             Focus::MinRadius => Focus::MaxIterations,
             Focus::Spawn => Focus::MinRadius,
             Focus::SpawnOffset => Focus::Spawn,
-            // Visual: age, color, highlight, invert, mode, particles, seed, speed
-            Focus::Age => Focus::SpawnOffset,
+            Focus::Walkers => Focus::SpawnOffset,
+            // Visual: age, color, highlight, invert, mode, particles, seed, slice, speed, view
+            Focus::Age => Focus::Walkers,
             Focus::ColorScheme => Focus::Age,
             Focus::Highlight => Focus::ColorScheme,
             Focus::Invert => Focus::Highlight,
             Focus::Mode => Focus::Invert,
             Focus::Particles => Focus::Mode,
             Focus::Seed => Focus::Particles,
-            Focus::Speed => Focus::Seed,
+            Focus::Slice => Focus::Seed,
+            Focus::Speed => Focus::Slice,
+            Focus::VoxelView => Focus::Speed,
         }
     }
 
     /// Get the line index in the parameters box for this focus (matches UI order)
-    pub fn line_index(&self) -> u16 {
+    pub const fn line_index(&self) -> u16 {
         // Line indices account for section headers:
         // 0: -- movement --
-        // 1-7: adaptive, adapt factor, direction, force, lattice, radial, walk
-        // 8: -- sticking --
-        // 9-14: contacts, gradient, neighbors, sticky, side stick, tip stick
-        // 15: -- spawn --
-        // 16-21: bound, escape, max steps, min radius, spawn, spawn off
-        // 22: -- visual --
-        // 23-30: age, color, highlight, invert, mode, particles, seed, speed
+        // 1-14: adaptive, adapt factor, direction, eta, force, growth, jump, lat type, lattice, mobility, off-lattice, radial, voxels, walk
+        // 15: -- sticking --
+        // 16-21: contacts, gradient, neighbors, sticky, side stick, tip stick
+        // 22: -- spawn --
+        // 23-29: bound, escape, max steps, min radius, spawn, spawn off, walkers
+        // 30: -- visual --
+        // 31-40: age, color, highlight, invert, mode, particles, seed, slice, speed, view
         match self {
             Focus::None | Focus::Controls => 0,
             // Movement (after header at line 0)
             Focus::AdaptiveStep => 1,
             Focus::AdaptiveFactor => 2,
             Focus::Direction => 3,
-            Focus::Force => 4,
-            Focus::LatticeWalk => 5,
-            Focus::RadialBias => 6,
-            Focus::WalkStep => 7,
-            // Sticking (after header at line 8)
-            Focus::MultiContact => 9,
-            Focus::StickyGradient => 10,
-            Focus::Neighborhood => 11,
-            Focus::Stickiness => 12,
-            Focus::SideSticky => 13,
-            Focus::TipSticky => 14,
-            // Spawn (after header at line 15)
-            Focus::Boundary => 16,
-            Focus::EscapeMult => 17,
-            Focus::MaxIterations => 18,
-            Focus::MinRadius => 19,
-            Focus::Spawn => 20,
-            Focus::SpawnOffset => 21,
-            // Visual (after header at line 22)
-            Focus::Age => 23,
-            Focus::ColorScheme => 24,
-            Focus::Highlight => 25,
-            Focus::Invert => 26,
-            Focus::Mode => 27,
-            Focus::Particles => 28,
-            Focus::Seed => 29,
-            Focus::Speed => 30,
+            Focus::Eta => 4,
+            Focus::Force => 5,
+            Focus::GrowthModel => 6,
+            Focus::Jump => 7,
+            Focus::LatticeType => 8,
+            Focus::LatticeWalk => 9,
+            Focus::Mobility => 10,
+            Focus::OffLattice => 11,
+            Focus::RadialBias => 12,
+            Focus::Voxels => 13,
+            Focus::WalkStep => 14,
+            // Sticking (after header at line 15)
+            Focus::MultiContact => 16,
+            Focus::StickyGradient => 17,
+            Focus::Neighborhood => 18,
+            Focus::Stickiness => 19,
+            Focus::SideSticky => 20,
+            Focus::TipSticky => 21,
+            // Spawn (after header at line 22)
+            Focus::Boundary => 23,
+            Focus::EscapeMult => 24,
+            Focus::MaxIterations => 25,
+            Focus::MinRadius => 26,
+            Focus::Spawn => 27,
+            Focus::SpawnOffset => 28,
+            Focus::Walkers => 29,
+            // Visual (after header at line 30)
+            Focus::Age => 31,
+            Focus::ColorScheme => 32,
+            Focus::Highlight => 33,
+            Focus::Invert => 34,
+            Focus::Mode => 35,
+            Focus::Particles => 36,
+            Focus::Seed => 37,
+            Focus::Slice => 38,
+            Focus::Speed => 39,
+            Focus::VoxelView => 40,
         }
     }
 
@@ -251,6 +326,10 @@ pub struct App {
     pub color_by_age: bool,
     pub focus: Focus,
     pub view_mode: ViewMode,
+    /// Canvas area in braille dots (2x4 per terminal cell)
+    pub canvas_dots: (usize, usize),
+    /// Panned/zoomed view of the grid (None = fit the whole walk domain)
+    pub viewport: Option<Viewport>,
     pub steps_per_frame: usize,
     pub show_help: bool,
     pub help_scroll: u16,
@@ -258,17 +337,52 @@ pub struct App {
     pub param_popup: Option<ParamPopup>,
     pub export_popup: Option<TextInputPopup>,
     pub export_result: Option<Result<String, String>>,
+    pub png_popup: Option<TextInputPopup>,
+    // Snapshot state
+    pub snapshot_popup: Option<(SnapshotAction, TextInputPopup)>,
+    pub snapshot_result: Option<Result<String, String>>,
+    // Preset state
+    pub preset_manager: PresetManager,
+    pub preset_popup: Option<PresetPopup>,
+    pub preset_save_popup: Option<TextInputPopup>,
+    pub preset_result: Option<Result<String, String>>,
+    /// Seed parameter panel
+    pub seed_popup: Option<SeedPopup>,
     // Recording state
     pub recorder: Recorder,
     pub recording_popup: Option<TextInputPopup>,
     pub recording_result: Option<Result<String, String>>,
     /// Tracks if simulation was paused before opening recording popup
     pub recording_was_paused: bool,
+    // Growth probe state
+    /// Probe walkers fired per growth probe
+    pub probes: usize,
+    /// Probe hits and their spectrum, shown as a heatmap while the run is paused
+    pub harmonic: Option<(HarmonicMeasure, MultifractalSpectrum)>,
+    pub probe_result: Option<Result<String, String>>,
+    // Morphology panel state
+    pub analysis_settings: AnalysisSettings,
+    pub show_morphology: bool,
+    /// Last analysis shown in the panel (refreshed as the cluster grows)
+    pub morphology: Option<Morphology>,
+    /// Time-series metrics file and its open log (None = not logging)
+    pub metrics: Option<(String, MetricsLog)>,
+    /// Stuck particles between metric samples of a log started in the TUI
+    pub metrics_interval: usize,
+    pub metrics_popup: Option<TextInputPopup>,
+    pub metrics_result: Option<Result<String, String>>,
 }
 
 impl App {
     pub fn new(canvas_width: u16, canvas_height: u16) -> Self {
         let (sim_width, sim_height) = braille::calculate_simulation_size(canvas_width, canvas_height);
+        let mut app = Self::with_simulation_size(sim_width, sim_height);
+        app.resize(canvas_width, canvas_height);
+        app
+    }
+
+    /// Create an app with an explicit simulation grid size (no terminal needed)
+    pub fn with_simulation_size(sim_width: usize, sim_height: usize) -> Self {
         let color_scheme = ColorScheme::default();
         Self {
             simulation: DlaSimulation::new(sim_width, sim_height),
@@ -277,6 +391,8 @@ impl App {
             color_by_age: true,
             focus: Focus::AdaptiveStep,
             view_mode: ViewMode::Default,
+            canvas_dots: (sim_width, sim_height),
+            viewport: None,
             steps_per_frame: 5,
             show_help: false,
             help_scroll: 0,
@@ -284,22 +400,53 @@ impl App {
             param_popup: None,
             export_popup: None,
             export_result: None,
+            png_popup: None,
+            snapshot_popup: None,
+            snapshot_result: None,
+            preset_manager: PresetManager::new(),
+            preset_popup: None,
+            preset_save_popup: None,
+            preset_result: None,
+            seed_popup: None,
             recorder: Recorder::new(),
             recording_popup: None,
             recording_result: None,
             recording_was_paused: false,
+            probes: harmonic::DEFAULT_PROBES,
+            harmonic: None,
+            probe_result: None,
+            analysis_settings: AnalysisSettings::default(),
+            show_morphology: false,
+            morphology: None,
+            metrics: None,
+            metrics_interval: metrics::DEFAULT_INTERVAL,
+            metrics_popup: None,
+            metrics_result: None,
         }
     }
 
     /// Run simulation steps for current frame
     pub fn tick(&mut self) {
         if !self.simulation.paused {
+            // The probe heatmap only holds for the cluster it was taken on
+            self.harmonic = None;
             for _ in 0..self.steps_per_frame {
                 if !self.simulation.step() {
                     break;
                 }
+                self.record_metrics();
             }
         }
+        if self.show_morphology {
+            self.refresh_morphology();
+        }
+        if self.simulation.settings.voxels {
+            // The rotating view keeps turning while paused so the cluster can be inspected
+            if self.simulation.settings.voxel_view == VoxelView::Rotate {
+                self.simulation.rotate_view(VIEW_ROTATION_SPEED);
+            }
+            self.simulation.refresh_view();
+        }
     }
 
     /// Handle adjusting the currently focused parameter
@@ -322,12 +469,21 @@ impl App {
             Focus::Mode => self.cycle_color_mode(),
             Focus::Highlight => self.adjust_highlight(5),
             Focus::Invert => self.toggle_invert_colors(),
+            Focus::Slice => self.simulation.adjust_slice_offset(1),
+            Focus::VoxelView => self.cycle_voxel_view(),
             // Movement
             Focus::AdaptiveStep => self.simulation.settings.toggle_adaptive_step(),
             Focus::AdaptiveFactor => self.simulation.settings.adjust_adaptive_step_factor(0.5),
+            Focus::Jump => self.simulation.settings.toggle_circle_jump(),
             Focus::LatticeWalk => self.simulation.settings.toggle_lattice_walk(),
+            Focus::LatticeType => self.cycle_lattice(),
+            Focus::OffLattice => self.toggle_off_lattice(),
+            Focus::Voxels => self.toggle_voxels(),
             Focus::WalkStep => self.adjust_walk_step(0.5),
             Focus::Direction => self.simulation.settings.adjust_walk_bias_angle(15.0),
+            Focus::Eta => self.simulation.settings.adjust_dbm_eta(0.25),
+            Focus::Mobility => self.simulation.settings.adjust_cluster_mobility(0.1),
+            Focus::GrowthModel => self.cycle_growth_model(),
             Focus::Force => self.simulation.settings.adjust_walk_bias_strength(0.05),
             Focus::RadialBias => self.simulation.settings.adjust_radial_bias(0.05),
             // Sticking
@@ -343,6 +499,7 @@ impl App {
             Focus::EscapeMult => self.simulation.settings.adjust_escape_multiplier(0.5),
             Focus::MinRadius => self.simulation.settings.adjust_min_spawn_radius(10.0),
             Focus::MaxIterations => self.simulation.settings.adjust_max_walk_iterations(1000),
+            Focus::Walkers => self.simulation.settings.adjust_walker_density(0.005),
         }
     }
 
@@ -366,12 +523,21 @@ impl App {
             Focus::Mode => self.cycle_color_mode_prev(),
             Focus::Highlight => self.adjust_highlight(-5),
             Focus::Invert => self.toggle_invert_colors(),
+            Focus::Slice => self.simulation.adjust_slice_offset(-1),
+            Focus::VoxelView => self.cycle_voxel_view_prev(),
             // Movement
             Focus::AdaptiveStep => self.simulation.settings.toggle_adaptive_step(),
             Focus::AdaptiveFactor => self.simulation.settings.adjust_adaptive_step_factor(-0.5),
+            Focus::Jump => self.simulation.settings.toggle_circle_jump(),
             Focus::LatticeWalk => self.simulation.settings.toggle_lattice_walk(),
+            Focus::LatticeType => self.cycle_lattice_prev(),
+            Focus::OffLattice => self.toggle_off_lattice(),
+            Focus::Voxels => self.toggle_voxels(),
             Focus::WalkStep => self.adjust_walk_step(-0.5),
             Focus::Direction => self.simulation.settings.adjust_walk_bias_angle(-15.0),
+            Focus::Eta => self.simulation.settings.adjust_dbm_eta(-0.25),
+            Focus::Mobility => self.simulation.settings.adjust_cluster_mobility(-0.1),
+            Focus::GrowthModel => self.cycle_growth_model_prev(),
             Focus::Force => self.simulation.settings.adjust_walk_bias_strength(-0.05),
             Focus::RadialBias => self.simulation.settings.adjust_radial_bias(-0.05),
             // Sticking
@@ -387,6 +553,7 @@ impl App {
             Focus::EscapeMult => self.simulation.settings.adjust_escape_multiplier(-0.5),
             Focus::MinRadius => self.simulation.settings.adjust_min_spawn_radius(-10.0),
             Focus::MaxIterations => self.simulation.settings.adjust_max_walk_iterations(-1000),
+            Focus::Walkers => self.simulation.settings.adjust_walker_density(-0.005),
         }
     }
 
@@ -410,11 +577,104 @@ impl App {
         self.simulation.reset();
     }
 
+    /// Toggle off-lattice disc mode (restarts the run, since discs and cells don't mix)
+    pub fn toggle_off_lattice(&mut self) {
+        self.simulation.settings.toggle_off_lattice();
+        self.simulation.reset();
+    }
+
+    /// Cycle the lattice type (restarts the run, since cells of different shapes don't mix)
+    pub fn cycle_lattice(&mut self) {
+        self.simulation.settings.lattice = self.simulation.settings.lattice.next();
+        self.simulation.reset();
+    }
+
+    /// Cycle the lattice type backward
+    pub fn cycle_lattice_prev(&mut self) {
+        self.simulation.settings.lattice = self.simulation.settings.lattice.prev();
+        self.simulation.reset();
+    }
+
+    /// Cycle the growth model (the cluster keeps growing under the new model)
+    pub fn cycle_growth_model(&mut self) {
+        self.simulation.settings.growth_model = self.simulation.settings.growth_model.next();
+    }
+
+    /// Cycle the growth model backward
+    pub fn cycle_growth_model_prev(&mut self) {
+        self.simulation.settings.growth_model = self.simulation.settings.growth_model.prev();
+    }
+
+    /// Toggle 3D voxel mode (restarts the run in the new dimension)
+    pub fn toggle_voxels(&mut self) {
+        self.simulation.settings.toggle_voxels();
+        self.simulation.reset();
+    }
+
     /// Set seed pattern directly (1-0 keys)
     pub fn set_seed_pattern(&mut self, pattern: SeedPattern) {
         self.simulation.reset_with_seed(pattern);
     }
 
+    // === Seed panel methods ===
+
+    /// Open the seed panel for the current pattern
+    pub fn open_seed_popup(&mut self) {
+        self.seed_popup = Some(SeedPopup::default());
+    }
+
+    /// Close the seed panel (edits are already applied)
+    pub fn close_seed_popup(&mut self) {
+        self.seed_popup = None;
+    }
+
+    /// Parameter highlighted in the seed panel
+    pub fn selected_seed_param(&self) -> Option<SeedParam> {
+        let popup = self.seed_popup.as_ref()?;
+        self.simulation.seed_pattern.params().get(popup.selected_idx).copied()
+    }
+
+    /// Navigate up in the seed panel
+    pub fn seed_nav_up(&mut self) {
+        let count = self.simulation.seed_pattern.params().len();
+        if let Some(popup) = &mut self.seed_popup {
+            if popup.selected_idx > 0 {
+                popup.selected_idx -= 1;
+            } else {
+                popup.selected_idx = count.saturating_sub(1);
+            }
+        }
+    }
+
+    /// Navigate down in the seed panel
+    pub fn seed_nav_down(&mut self) {
+        let count = self.simulation.seed_pattern.params().len();
+        if let Some(popup) = &mut self.seed_popup {
+            if popup.selected_idx < count.saturating_sub(1) {
+                popup.selected_idx += 1;
+            } else {
+                popup.selected_idx = 0;
+            }
+        }
+    }
+
+    /// Step the highlighted seed parameter by `steps` increments and reseed
+    pub fn adjust_seed_param(&mut self, steps: f32) {
+        if let Some(param) = self.selected_seed_param() {
+            let value = self.simulation.seed_value(param) + steps * param.step();
+            self.simulation.seed_params.set(param, Some(value));
+            self.simulation.reset();
+        }
+    }
+
+    /// Return the highlighted seed parameter to the pattern default and reseed
+    pub fn clear_seed_param(&mut self) {
+        if let Some(param) = self.selected_seed_param() {
+            self.simulation.seed_params.set(param, None);
+            self.simulation.reset();
+        }
+    }
+
     /// Toggle color-by-age mode
     pub fn toggle_color_by_age(&mut self) {
         self.color_by_age = !self.color_by_age;
@@ -459,10 +719,53 @@ impl App {
         self.controls_scroll = (self.controls_scroll + 1).min(max_scroll);
     }
 
-    /// Resize simulation to match new canvas size
+    /// Track a new canvas size (the simulation keeps running; only the view changes)
     pub fn resize(&mut self, canvas_width: u16, canvas_height: u16) {
-        let (sim_width, sim_height) = braille::calculate_simulation_size(canvas_width, canvas_height);
-        self.simulation.resize(sim_width, sim_height);
+        let (old_width, _) = self.canvas_dots;
+        self.canvas_dots = (canvas_width as usize * 2, canvas_height as usize * 4);
+        // Keep a zoomed view's center and scale, showing more or less around it
+        if let Some(view) = self.viewport {
+            let cells_per_dot = view.width / old_width.max(1) as f32;
+            let (cx, cy) = view.center();
+            let (width, height) = (
+                self.canvas_dots.0 as f32 * cells_per_dot,
+                self.canvas_dots.1 as f32 * cells_per_dot,
+            );
+            self.viewport = Some(Viewport {
+                x: cx - width / 2.0,
+                y: cy - height / 2.0,
+                width,
+                height,
+            });
+        }
+    }
+
+    /// Viewport currently shown on the canvas
+    pub fn current_viewport(&self) -> Viewport {
+        let (width, height) = self.canvas_dots;
+        self.viewport
+            .unwrap_or_else(|| Viewport::fit(self.simulation.bounds(), width, height))
+    }
+
+    /// Zoom the view around its center (factor > 1 zooms in)
+    pub fn zoom_view(&mut self, factor: f32) {
+        let view = self.current_viewport().zoomed(factor);
+        // Stop at four dots per cell and at four times the fitted view
+        let fit = Viewport::fit(self.simulation.bounds(), self.canvas_dots.0, self.canvas_dots.1);
+        let min_width = self.canvas_dots.0.max(1) as f32 / 4.0;
+        if view.width >= min_width && view.width <= fit.width * 4.0 {
+            self.viewport = Some(view);
+        }
+    }
+
+    /// Pan the view by a fraction of its size
+    pub fn pan_view(&mut self, dx: f32, dy: f32) {
+        self.viewport = Some(self.current_viewport().panned(dx, dy));
+    }
+
+    /// Go back to fitting the whole walk domain
+    pub fn fit_view(&mut self) {
+        self.viewport = None;
     }
 
     /// Increase simulation speed
@@ -487,9 +790,14 @@ impl App {
         self.simulation.settings.invert_colors = !self.simulation.settings.invert_colors;
     }
 
-    /// Cycle through neighborhood types
+    /// Cycle through neighborhood types (the 3D neighborhood in voxel mode)
     pub fn cycle_neighborhood(&mut self) {
-        self.simulation.settings.neighborhood = self.simulation.settings.neighborhood.next();
+        let settings = &mut self.simulation.settings;
+        if settings.voxels {
+            settings.neighborhood_3d = settings.neighborhood_3d.next();
+        } else {
+            settings.neighborhood = settings.neighborhood.next();
+        }
     }
 
     /// Cycle through boundary behaviors
@@ -519,7 +827,24 @@ impl App {
 
     /// Cycle neighborhood backward
     pub fn cycle_neighborhood_prev(&mut self) {
-        self.simulation.settings.neighborhood = self.simulation.settings.neighborhood.prev();
+        let settings = &mut self.simulation.settings;
+        if settings.voxels {
+            settings.neighborhood_3d = settings.neighborhood_3d.prev();
+        } else {
+            settings.neighborhood = settings.neighborhood.prev();
+        }
+    }
+
+    /// Cycle through 3D views
+    pub fn cycle_voxel_view(&mut self) {
+        self.simulation.settings.voxel_view = self.simulation.settings.voxel_view.next();
+        self.simulation.refresh_view();
+    }
+
+    /// Cycle 3D view backward
+    pub fn cycle_voxel_view_prev(&mut self) {
+        self.simulation.settings.voxel_view = self.simulation.settings.voxel_view.prev();
+        self.simulation.refresh_view();
     }
 
     /// Cycle boundary backward
@@ -545,16 +870,22 @@ impl App {
             ('c', Focus::ColorScheme, "Color Scheme"),
             ('d', Focus::Direction, "Direction"),
             ('e', Focus::EscapeMult, "Escape Multiplier"),
+            ('e', Focus::Eta, "Eta (DBM)"),
             ('f', Focus::Force, "Force (Bias Strength)"),
             ('g', Focus::StickyGradient, "Gradient (Stickiness)"),
+            ('g', Focus::GrowthModel, "Growth Model"),
             ('h', Focus::Highlight, "Highlight"),
             ('i', Focus::Invert, "Invert"),
+            ('j', Focus::Jump, "Jump (Circle)"),
+            ('l', Focus::LatticeType, "Lattice Type"),
             ('l', Focus::LatticeWalk, "Lattice Walk"),
             ('m', Focus::Mode, "Mode (Color)"),
             ('m', Focus::MultiContact, "Multi-Contact Min"),
             ('m', Focus::MinRadius, "Min Spawn Radius"),
             ('m', Focus::MaxIterations, "Max Steps"),
+            ('m', Focus::Mobility, "Mobility (DLCA)"),
             ('n', Focus::Neighborhood, "Neighborhood"),
+            ('o', Focus::OffLattice, "Off-Lattice"),
             ('o', Focus::SpawnOffset, "Offset (Spawn)"),
             ('p', Focus::Particles, "Particles"),
             ('r', Focus::RadialBias, "Radial Bias"),
@@ -562,9 +893,14 @@ impl App {
             ('s', Focus::Seed, "Seed Pattern"),
             ('s', Focus::Speed, "Speed"),
             ('s', Focus::SideSticky, "Side Stickiness"),
+            ('s', Focus::Slice, "Slice (3D)"),
             ('s', Focus::Spawn, "Spawn Mode"),
             ('t', Focus::TipSticky, "Tip Stickiness"),
+            ('v', Focus::VoxelView, "View (3D)"),
             ('w', Focus::WalkStep, "Walk Step"),
+            ('w', Focus::Walkers, "Walkers (Density)"),
+            // V is taken by the 3D view; Z for the third axis
+            ('z', Focus::Voxels, "Voxels (3D)"),
         ];
 
         all_params
@@ -595,26 +931,36 @@ impl App {
             (Focus::ColorScheme, "Color Scheme"),
             (Focus::Direction, "Direction"),
             (Focus::EscapeMult, "Escape Multiplier"),
+            (Focus::Eta, "Eta (DBM)"),
             (Focus::Force, "Force (Bias Strength)"),
             (Focus::StickyGradient, "Gradient (Stickiness)"),
+            (Focus::GrowthModel, "Growth Model"),
             (Focus::Highlight, "Highlight"),
             (Focus::Invert, "Invert"),
+            (Focus::Jump, "Jump (Circle)"),
+            (Focus::LatticeType, "Lattice Type"),
             (Focus::LatticeWalk, "Lattice Walk"),
             (Focus::MaxIterations, "Max Steps"),
             (Focus::MinRadius, "Min Spawn Radius"),
+            (Focus::Mobility, "Mobility (DLCA)"),
             (Focus::Mode, "Mode (Color)"),
             (Focus::MultiContact, "Multi-Contact Min"),
             (Focus::Neighborhood, "Neighborhood"),
+            (Focus::OffLattice, "Off-Lattice"),
             (Focus::SpawnOffset, "Offset (Spawn)"),
             (Focus::Particles, "Particles"),
             (Focus::RadialBias, "Radial Bias"),
             (Focus::Seed, "Seed Pattern"),
             (Focus::SideSticky, "Side Stickiness"),
+            (Focus::Slice, "Slice (3D)"),
             (Focus::Spawn, "Spawn Mode"),
             (Focus::Speed, "Speed"),
             (Focus::Stickiness, "Stickiness"),
             (Focus::TipSticky, "Tip Stickiness"),
+            (Focus::VoxelView, "View (3D)"),
+            (Focus::Voxels, "Voxels (3D)"),
             (Focus::WalkStep, "Walk Step"),
+            (Focus::Walkers, "Walkers (Density)"),
         ]
     }
 
@@ -680,9 +1026,10 @@ impl App {
 
     // === Export popup methods ===
 
-    /// Open export popup with default filename
+    /// Open export popup with default filename (a graph or point cloud
+    /// extension exports the cluster graph or points)
     pub fn open_export_popup(&mut self) {
-        self.export_popup = Some(TextInputPopup::new(" Export Config ", "dla-config.json"));
+        self.export_popup = Some(TextInputPopup::new(" Export Config / Graph / Points / Analysis ", "dla-config.json"));
     }
 
     /// Close export popup without saving
@@ -692,12 +1039,211 @@ impl App {
 
     /// Confirm export and save file
     pub fn confirm_export(&mut self) {
-        if let Some(popup) = &self.export_popup {
-            let config = self.to_config();
-            let path = Path::new(&popup.input);
-            self.export_result = Some(config.save_to_file(path).map(|_| popup.input.clone()));
+        if let Some(popup) = self.export_popup.take() {
+            let filename = &popup.input;
+            let result = if let Some(format) = GraphFormat::from_filename(filename) {
+                self.export_graph(filename, format)
+            } else if let Some(format) = PointFormat::from_filename(filename) {
+                self.export_points(filename, format)
+            } else if Morphology::is_analysis_filename(filename) {
+                self.save_analysis(filename)
+            } else {
+                let config = self.to_config();
+                config.save_to_file(Path::new(filename)).map(|_| filename.clone())
+            };
+            self.export_result = Some(result);
+        }
+    }
+
+    /// Export the cluster and its attachment links as a graph
+    pub fn export_graph(&self, filename: &str, format: GraphFormat) -> Result<String, String> {
+        let graph = ClusterGraph::from_simulation(&self.simulation);
+        graph.save_to_file(Path::new(filename), format)?;
+        Ok(filename.to_string())
+    }
+
+    /// Export the cluster as a point cloud: the voxels of a 3D run, or the
+    /// particle cells at z = 0 otherwise
+    pub fn export_points(&self, filename: &str, format: PointFormat) -> Result<String, String> {
+        let voxels: Vec<Voxel> = if self.simulation.settings.voxels {
+            self.simulation.voxels().to_vec()
+        } else {
+            self.simulation
+                .particles()
+                .map(|(x, y, data)| Voxel { x, y, z: 0, data })
+                .collect()
+        };
+        voxel::save_points(&voxels, Path::new(filename), format)?;
+        Ok(filename.to_string())
+    }
+
+    /// Pause the run and probe the growth probabilities of its perimeter,
+    /// or hide the probe heatmap if it is shown
+    pub fn toggle_harmonic(&mut self) {
+        if self.harmonic.take().is_some() {
+            return;
+        }
+        self.simulation.paused = true;
+        if let Err(e) = self.probe_growth() {
+            self.probe_result = Some(Err(e));
+        }
+    }
+
+    /// Fire the probe walkers at the current cluster and keep their hits
+    fn probe_growth(&mut self) -> Result<(), String> {
+        let measure = self.simulation.probe_growth(self.probes)?;
+        let spectrum = measure.spectrum(&harmonic::default_moments());
+        self.harmonic = Some((measure, spectrum));
+        Ok(())
+    }
+
+    /// Save the probe hits and their spectrum as JSON, probing first unless the
+    /// heatmap already holds them
+    pub fn save_harmonic(&mut self, filename: &str) -> Result<String, String> {
+        if self.harmonic.is_none() {
+            self.probe_growth()?;
+        }
+        let (measure, _) = self.harmonic.as_ref().expect("probed");
+        measure.save_to_file(Path::new(filename))?;
+        Ok(filename.to_string())
+    }
+
+    /// One-line summary of the last probe: hits and the first generalized dimensions
+    pub fn harmonic_summary(&self) -> Option<String> {
+        let (measure, spectrum) = self.harmonic.as_ref()?;
+        let dimension = |q| spectrum.dimension(q).map_or("-".to_string(), |d| format!("{:.2}", d));
+        Some(format!(
+            "{} / {} probes hit {} perimeter cells, D_0 {} D_1 {} D_2 {}",
+            measure.total_hits(),
+            measure.probes,
+            measure.site_count(),
+            dimension(0.0),
+            dimension(1.0),
+            dimension(2.0)
+        ))
+    }
+
+    pub fn clear_probe_result(&mut self) {
+        self.probe_result = None;
+    }
+
+    /// Show or hide the morphology panel
+    pub fn toggle_morphology(&mut self) {
+        self.show_morphology = !self.show_morphology;
+        self.morphology = None;
+        if self.show_morphology {
+            self.refresh_morphology();
+        }
+    }
+
+    /// Re-run the analysis once the cluster has changed size by 5%. Big
+    /// clusters get fewer reference points so a refresh can't stall the UI
+    fn refresh_morphology(&mut self) {
+        let particles = self.simulation.particles_stuck;
+        let stale = self
+            .morphology
+            .as_ref()
+            .is_none_or(|m| m.particles.abs_diff(particles) * 20 > m.particles);
+        if stale {
+            let settings = AnalysisSettings {
+                reference_points: self
+                    .analysis_settings
+                    .reference_points
+                    .min(LIVE_ANALYSIS_BUDGET / particles.max(1))
+                    .max(1),
+                ..self.analysis_settings.clone()
+            };
+            self.morphology = Some(Morphology::from_simulation(&self.simulation, &settings));
+        }
+    }
+
+    /// Analyze the current cluster and save it as CSV curves (.csv) or JSON
+    pub fn save_analysis(&mut self, filename: &str) -> Result<String, String> {
+        let morphology = Morphology::from_simulation(&self.simulation, &self.analysis_settings);
+        morphology.save_to_file(Path::new(filename))?;
+        self.morphology = Some(morphology);
+        Ok(filename.to_string())
+    }
+
+    /// Start logging metrics to a file every `interval` stuck particles
+    pub fn start_metrics(&mut self, filename: &str, interval: usize) -> Result<(), String> {
+        let mut log = MetricsLog::create(Path::new(filename), interval)?;
+        log.record(&self.simulation)?;
+        self.metrics = Some((filename.to_string(), log));
+        Ok(())
+    }
+
+    /// Sample the metrics log if one is open (stops logging on a write error)
+    pub fn record_metrics(&mut self) {
+        if let Some((_, log)) = &mut self.metrics {
+            if let Err(e) = log.record(&self.simulation) {
+                self.metrics_result = Some(Err(e));
+                self.metrics = None;
+            }
+        }
+    }
+
+    /// Write the final sample and close the metrics log
+    pub fn finish_metrics(&mut self) -> Option<Result<String, String>> {
+        let (filename, mut log) = self.metrics.take()?;
+        Some(log.finish(&self.simulation).map(|_| format!("{} ({} samples)", filename, log.samples())))
+    }
+
+    /// Stop logging metrics if a log is open, or ask for a file to log to
+    pub fn toggle_metrics(&mut self) {
+        match self.finish_metrics() {
+            Some(result) => self.metrics_result = Some(result.map(|saved| format!("Saved metrics: {}", saved))),
+            None => self.metrics_popup = Some(TextInputPopup::new(" Log Metrics ", "dla-metrics.csv")),
+        }
+    }
+
+    /// Close metrics popup without logging
+    pub fn close_metrics_popup(&mut self) {
+        self.metrics_popup = None;
+    }
+
+    /// Confirm metrics popup and start logging to its file
+    pub fn confirm_metrics_popup(&mut self) {
+        if let Some(popup) = self.metrics_popup.take() {
+            self.metrics_result = Some(
+                self.start_metrics(&popup.input, self.metrics_interval)
+                    .map(|_| format!("Logging metrics: {}", popup.input)),
+            );
+        }
+    }
+
+    /// Clear metrics result (call after displaying it)
+    pub fn clear_metrics_result(&mut self) {
+        self.metrics_result = None;
+    }
+
+    /// Open PNG export popup with default filename
+    pub fn open_png_popup(&mut self) {
+        self.png_popup = Some(TextInputPopup::new(" Export PNG ", "dla-cluster.png"));
+    }
+
+    /// Close PNG export popup without saving
+    pub fn close_png_popup(&mut self) {
+        self.png_popup = None;
+    }
+
+    /// Confirm PNG export and save file
+    pub fn confirm_png_export(&mut self) {
+        if let Some(popup) = self.png_popup.take() {
+            self.export_result = Some(self.export_png(&popup.input));
         }
-        self.export_popup = None;
+    }
+
+    /// Export the current grid as a PNG using the recorder's color pipeline
+    pub fn export_png(&self, filename: &str) -> Result<String, String> {
+        self.recorder.export_png(
+            filename,
+            &self.simulation,
+            &self.color_scheme,
+            self.color_by_age,
+            self.simulation.settings.color_mode,
+            self.simulation.settings.invert_colors,
+        )
     }
 
     /// Clear export result (call after displaying it)
@@ -710,25 +1256,244 @@ impl App {
         AppConfig {
             version: 1,
             settings: self.simulation.settings.clone(),
-            seed_pattern: self.simulation.seed_pattern,
+            seed_pattern: self.simulation.seed_pattern.clone(),
+            seed_params: self.simulation.seed_params,
             stickiness: self.simulation.stickiness,
             num_particles: self.simulation.num_particles,
             color_scheme: self.color_scheme,
             steps_per_frame: self.steps_per_frame,
             color_by_age: self.color_by_age,
+            rng_seed: self.simulation.rng_seed,
         }
     }
 
-    /// Apply AppConfig to current state
-    pub fn apply_config(&mut self, config: &AppConfig) {
+    /// Apply AppConfig to current state, loading the images it names (an
+    /// image that fails to load is left out and reported)
+    pub fn apply_config(&mut self, config: &AppConfig) -> Result<(), String> {
         self.simulation.settings = config.settings.clone();
-        self.simulation.seed_pattern = config.seed_pattern;
+        self.simulation.seed_pattern = config.seed_pattern.clone();
+        self.simulation.seed_params = config.seed_params;
         self.simulation.stickiness = config.stickiness;
         self.simulation.num_particles = config.num_particles;
         self.color_scheme = config.color_scheme;
         self.color_lut = self.color_scheme.build_lut();
         self.steps_per_frame = config.steps_per_frame;
         self.color_by_age = config.color_by_age;
+        self.simulation.set_rng_seed(config.rng_seed);
+        self.simulation.load_images()
+    }
+
+    // === Snapshot methods ===
+
+    /// Open snapshot popup for saving or loading
+    pub fn open_snapshot_popup(&mut self, action: SnapshotAction) {
+        let title = match action {
+            SnapshotAction::Save => " Save Snapshot ",
+            SnapshotAction::Load => " Load Snapshot ",
+        };
+        self.snapshot_popup = Some((action, TextInputPopup::new(title, "dla-snapshot.dla")));
+    }
+
+    /// Close snapshot popup without saving or loading
+    pub fn close_snapshot_popup(&mut self) {
+        self.snapshot_popup = None;
+    }
+
+    /// Confirm snapshot popup and perform its action
+    pub fn confirm_snapshot_popup(&mut self) {
+        if let Some((action, popup)) = self.snapshot_popup.take() {
+            self.snapshot_result = Some(match action {
+                SnapshotAction::Save => self
+                    .save_snapshot(&popup.input)
+                    .map(|f| format!("Saved snapshot: {}", f)),
+                SnapshotAction::Load => self.load_snapshot(&popup.input).map(|warning| match warning {
+                    Some(warning) => format!("Loaded snapshot: {} ({})", popup.input, warning),
+                    None => format!("Loaded snapshot: {}", popup.input),
+                }),
+            });
+        }
+    }
+
+    /// Clear snapshot result (call after displaying it)
+    pub fn clear_snapshot_result(&mut self) {
+        self.snapshot_result = None;
+    }
+
+    /// Build a snapshot of the full simulation state
+    pub fn to_snapshot(&self) -> Snapshot {
+        Snapshot::new(self.to_config(), self.simulation.state())
+    }
+
+    /// Save a snapshot to disk, returning the filename used
+    pub fn save_snapshot(&self, filename: &str) -> Result<String, String> {
+        let filename = snapshot::with_snapshot_extension(filename);
+        self.to_snapshot().save_to_file(Path::new(&filename))?;
+        Ok(filename)
+    }
+
+    /// Load a snapshot from disk and continue from it
+    pub fn load_snapshot(&mut self, filename: &str) -> Result<Option<String>, String> {
+        let snapshot = Snapshot::load_from_file(Path::new(filename))?;
+        self.apply_snapshot(snapshot)
+    }
+
+    /// Apply a loaded snapshot (settings first, then grid and RNG state).
+    /// The state is checked up front so a rejected snapshot changes nothing.
+    /// The saved walls and cluster don't need the seed or obstacle images, so
+    /// a missing one still restores and comes back as a warning
+    pub fn apply_snapshot(&mut self, snapshot: Snapshot) -> Result<Option<String>, String> {
+        snapshot.state.validate()?;
+        let images = self.apply_config(&snapshot.config);
+        self.simulation.restore_state(snapshot.state)?;
+        Ok(images.err())
+    }
+
+    // === Preset methods ===
+
+    /// Open the preset browser
+    pub fn open_preset_popup(&mut self) {
+        self.preset_popup = Some(PresetPopup::default());
+    }
+
+    /// Close the preset browser without applying
+    pub fn close_preset_popup(&mut self) {
+        self.preset_popup = None;
+    }
+
+    /// Number of presets shown in the browser (builtin + user)
+    pub fn preset_count(&self) -> usize {
+        self.preset_manager.builtin.len() + self.preset_manager.user.len()
+    }
+
+    /// Preset currently highlighted in the browser
+    pub fn selected_preset(&self) -> Option<&Preset> {
+        let popup = self.preset_popup.as_ref()?;
+        self.preset_manager.all_presets().nth(popup.selected_idx)
+    }
+
+    /// Navigate up in preset browser
+    pub fn preset_nav_up(&mut self) {
+        let count = self.preset_count();
+        if let Some(popup) = &mut self.preset_popup {
+            if popup.selected_idx > 0 {
+                popup.selected_idx -= 1;
+            } else {
+                popup.selected_idx = count.saturating_sub(1);
+            }
+        }
+    }
+
+    /// Navigate down in preset browser
+    pub fn preset_nav_down(&mut self) {
+        let count = self.preset_count();
+        if let Some(popup) = &mut self.preset_popup {
+            if popup.selected_idx < count.saturating_sub(1) {
+                popup.selected_idx += 1;
+            } else {
+                popup.selected_idx = 0;
+            }
+        }
+    }
+
+    /// Apply the highlighted preset and close the browser
+    pub fn confirm_preset_popup(&mut self) {
+        if let Some(preset) = self.selected_preset().cloned() {
+            self.preset_result = Some(self.apply_preset(&preset).map(|_| format!("Preset: {}", preset.name)));
+        }
+        self.preset_popup = None;
+    }
+
+    /// Delete the highlighted preset if it is a user preset
+    pub fn delete_selected_preset(&mut self) {
+        let Some(popup) = &self.preset_popup else {
+            return;
+        };
+        let builtin_count = self.preset_manager.builtin.len();
+        if popup.selected_idx < builtin_count {
+            self.preset_result = Some(Err("Built-in presets cannot be deleted".to_string()));
+            return;
+        }
+        let name = self.preset_manager.user[popup.selected_idx - builtin_count].name.clone();
+        self.preset_result = Some(
+            self.preset_manager
+                .delete_preset(&name)
+                .map(|_| format!("Deleted preset: {}", name)),
+        );
+        let count = self.preset_count();
+        if let Some(popup) = &mut self.preset_popup {
+            popup.selected_idx = popup.selected_idx.min(count.saturating_sub(1));
+        }
+    }
+
+    /// Find a preset by name (case-insensitive) and apply it
+    pub fn apply_preset_by_name(&mut self, name: &str) -> Result<(), String> {
+        let preset = self.preset_manager.find(name).cloned().ok_or_else(|| {
+            format!(
+                "Unknown preset '{}' (available: {})",
+                name,
+                self.preset_manager.preset_names().join(", ")
+            )
+        })?;
+        self.apply_preset(&preset)
+    }
+
+    /// Apply a preset's settings and restart with its seed pattern (an image
+    /// that fails to load is left out and reported)
+    pub fn apply_preset(&mut self, preset: &Preset) -> Result<(), String> {
+        self.simulation.settings = preset.settings.clone();
+        self.simulation.stickiness = preset.base_stickiness;
+        self.simulation.num_particles = preset.num_particles.min(self.simulation.max_particles());
+        self.simulation.seed_params = preset.seed_params;
+        self.simulation.seed_pattern = preset.seed_pattern.clone();
+        let images = self.simulation.load_images();
+        self.simulation.reset_with_seed(self.simulation.seed_pattern.clone());
+        images
+    }
+
+    /// Build a preset from the current simulation settings
+    pub fn to_preset(&self, name: &str) -> Preset {
+        Preset {
+            seed_params: self.simulation.seed_params,
+            ..Preset::new(
+                name,
+                "Saved from current settings",
+                self.simulation.settings.clone(),
+                self.simulation.seed_pattern.clone(),
+                self.simulation.stickiness,
+                self.simulation.num_particles,
+            )
+        }
+    }
+
+    /// Open popup to name a new preset from the current settings
+    pub fn open_preset_save_popup(&mut self) {
+        self.preset_popup = None;
+        self.preset_save_popup = Some(TextInputPopup::new(" Save Preset ", "My Preset"));
+    }
+
+    /// Close preset save popup without saving
+    pub fn close_preset_save_popup(&mut self) {
+        self.preset_save_popup = None;
+    }
+
+    /// Save current settings as a user preset
+    pub fn confirm_preset_save(&mut self) {
+        if let Some(popup) = self.preset_save_popup.take() {
+            let name = popup.input.trim();
+            self.preset_result = Some(if name.is_empty() {
+                Err("Preset name cannot be empty".to_string())
+            } else {
+                let preset = self.to_preset(name);
+                self.preset_manager
+                    .save_preset(preset)
+                    .map(|_| format!("Saved preset: {}", name))
+            });
+        }
+    }
+
+    /// Clear preset result (call after displaying it)
+    pub fn clear_preset_result(&mut self) {
+        self.preset_result = None;
     }
 
     // === Recording methods ===
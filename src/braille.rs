@@ -1,6 +1,8 @@
-use crate::color::{map_from_lut, ColorLut};
-use crate::settings::ColorMode;
-use crate::simulation::DlaSimulation;
+use dla_sim_tui::color::{map_from_lut, ColorLut, OBSTACLE_RGB};
+use dla_sim_tui::harmonic::HarmonicMeasure;
+use dla_sim_tui::settings::ColorMode;
+use dla_sim_tui::simulation::DlaSimulation;
+use dla_sim_tui::viewport::Viewport;
 use ratatui::style::Color;
 
 /// Braille character rendering for high-resolution terminal graphics.
@@ -32,10 +34,12 @@ pub struct BrailleCell {
     pub color: Color,
 }
 
-/// Render the simulation grid to Braille characters (uses LUT for fast color lookup)
+/// Render the part of the grid under `viewport` to Braille characters
+/// (uses LUT for fast color lookup)
 #[allow(clippy::too_many_arguments)]
 pub fn render_to_braille(
     simulation: &DlaSimulation,
+    viewport: &Viewport,
     canvas_width: u16,
     canvas_height: u16,
     color_lut: &ColorLut,
@@ -44,20 +48,18 @@ pub fn render_to_braille(
     highlight_recent: usize,
     invert_colors: bool,
 ) -> Vec<BrailleCell> {
-    let sim_width = simulation.grid_width;
-    let sim_height = simulation.grid_height;
-
-    // Braille effective resolution (must match calculate_simulation_size min constraints)
-    let braille_width = (canvas_width as usize * 2).max(64);
-    let braille_height = (canvas_height as usize * 4).max(64);
-
-    // Scale factors (pre-calculated once)
-    let scale_x = sim_width as f32 / braille_width as f32;
-    let scale_y = sim_height as f32 / braille_height as f32;
+    // Braille effective resolution: every dot samples one cell of the viewport
+    let braille_width = canvas_width as usize * 2;
+    let braille_height = canvas_height as usize * 4;
 
     // Pre-calculate for color mapping
     let inv_num_particles = 1.0 / simulation.num_particles.max(1) as f32;
     let max_radius = simulation.max_radius.max(1.0);
+    let inv_species = 1.0 / (simulation.settings.species_count() - 1).max(1) as f32;
+    let tree = simulation.tree_extent();
+    let inv_generation = 1.0 / tree.generation.max(1) as f32;
+    let inv_path_length = 1.0 / tree.path_length.max(1.0);
+    let inv_strahler = 1.0 / tree.strahler.saturating_sub(1).max(1) as f32;
     let particles_stuck = simulation.particles_stuck;
 
     let mut cells = Vec::with_capacity((canvas_width * canvas_height) as usize);
@@ -78,10 +80,9 @@ pub fn render_to_braille(
                     let braille_x = base_bx + dx;
                     let braille_y = base_by + dy;
 
-                    let sim_x = (braille_x as f32 * scale_x) as usize;
-                    let sim_y = (braille_y as f32 * scale_y) as usize;
+                    let (sim_x, sim_y) = viewport.point_at(braille_x, braille_y, braille_width, braille_height);
 
-                    if let Some(particle) = simulation.get_particle(sim_x, sim_y) {
+                    if let Some(particle) = simulation.particle_at(sim_x, sim_y) {
                         pattern |= dot_pattern;
                         dot_count += 1;
 
@@ -99,8 +100,14 @@ pub fn render_to_braille(
                                 // Map angle (-PI to PI) to 0-1
                                 (particle.direction + std::f32::consts::PI) / std::f32::consts::TAU
                             }
+                            ColorMode::Species => particle.species as f32 * inv_species,
+                            ColorMode::Generation => particle.generation as f32 * inv_generation,
+                            ColorMode::PathLength => particle.path_length * inv_path_length,
+                            ColorMode::Strahler => particle.strahler.saturating_sub(1) as f32 * inv_strahler,
                         };
                         total_value += value;
+                    } else if simulation.obstacle_at(sim_x, sim_y) {
+                        pattern |= dot_pattern;
                     }
                 }
             }
@@ -112,7 +119,10 @@ pub fn render_to_braille(
                 let color = if is_recent {
                     // Highlight recent particles in a contrasting color
                     Color::Rgb(255, 255, 255)
-                } else if color_by_age && dot_count > 0 {
+                } else if dot_count == 0 {
+                    // Only wall dots
+                    Color::Rgb(OBSTACLE_RGB.0, OBSTACLE_RGB.1, OBSTACLE_RGB.2)
+                } else if color_by_age {
                     let avg_value = total_value / dot_count as f32;
                     let t = if invert_colors { 1.0 - avg_value } else { avg_value };
                     map_from_lut(color_lut, t)
@@ -133,6 +143,61 @@ pub fn render_to_braille(
     cells
 }
 
+/// Render the probe hits on the perimeter as a heatmap over the cluster.
+///
+/// Characters with hit cells take the heat color of their most-hit cell, on a
+/// log scale; the cluster and walls are drawn in gray underneath.
+pub fn render_harmonic_to_braille(
+    simulation: &DlaSimulation,
+    viewport: &Viewport,
+    canvas_width: u16,
+    canvas_height: u16,
+    measure: &HarmonicMeasure,
+    heat_lut: &ColorLut,
+) -> Vec<BrailleCell> {
+    let braille_width = canvas_width as usize * 2;
+    let braille_height = canvas_height as usize * 4;
+    let lattice = simulation.settings.active_lattice();
+    let inv_log_max = 1.0 / (measure.max_hits() as f32).ln_1p().max(f32::EPSILON);
+
+    let mut cells = Vec::with_capacity((canvas_width * canvas_height) as usize);
+    for cy in 0..canvas_height {
+        for cx in 0..canvas_width {
+            let mut pattern: u8 = 0;
+            let mut most_hits = 0;
+            for (dx, dots_col) in BRAILLE_DOTS.iter().enumerate() {
+                for (dy, &dot_pattern) in dots_col.iter().enumerate() {
+                    let (sim_x, sim_y) =
+                        viewport.point_at(cx as usize * 2 + dx, cy as usize * 4 + dy, braille_width, braille_height);
+                    let (col, row) = lattice.cell_at(sim_x, sim_y);
+                    let hits = measure.hits_at(col, row);
+                    if hits > 0 || simulation.particle_at(sim_x, sim_y).is_some() || simulation.obstacle_at(sim_x, sim_y) {
+                        pattern |= dot_pattern;
+                    }
+                    most_hits = most_hits.max(hits);
+                }
+            }
+
+            if pattern != 0 {
+                let color = if most_hits > 0 {
+                    // Start a fifth of the way up so single hits stay visible
+                    map_from_lut(heat_lut, 0.2 + 0.8 * (most_hits as f32).ln_1p() * inv_log_max)
+                } else {
+                    Color::Rgb(OBSTACLE_RGB.0, OBSTACLE_RGB.1, OBSTACLE_RGB.2)
+                };
+                cells.push(BrailleCell {
+                    x: cx,
+                    y: cy,
+                    char: char::from_u32(BRAILLE_BASE + pattern as u32).unwrap_or(' '),
+                    color,
+                });
+            }
+        }
+    }
+
+    cells
+}
+
 /// Calculate optimal simulation grid size for a given canvas size
 /// Returns (width, height) for the simulation grid
 pub fn calculate_simulation_size(canvas_width: u16, canvas_height: u16) -> (usize, usize) {
@@ -1,6 +1,9 @@
 use ratatui::style::Color;
 use serde::{Deserialize, Serialize};
 
+/// Color of obstacle walls on the canvas and in images
+pub const OBSTACLE_RGB: (u8, u8, u8) = (96, 96, 104);
+
 /// Pre-computed color lookup table (256 entries for fast gradient access)
 pub type ColorLut = [Color; 256];
 
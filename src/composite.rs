@@ -0,0 +1,255 @@
+//! Composite seeds: initial clusters built from several shapes, loaded from a
+//! JSON seed file or embedded in a config.
+//!
+//! Coordinates are in grid cells relative to the middle of the grid, with y
+//! pointing down, so a seed file fits any grid size:
+//!
+//! ```json
+//! {
+//!   "shapes": [
+//!     { "type": "line", "from": [-60, -40], "to": [-60, 40], "thickness": 2 },
+//!     { "type": "line", "from": [60, -40], "to": [60, 40], "thickness": 2 },
+//!     { "type": "point", "at": [0, 0] }
+//!   ]
+//! }
+//! ```
+
+use crate::font;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// One shape of a composite seed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum SeedShape {
+    /// Single cell
+    Point { at: [f32; 2] },
+    /// Line segment
+    Line {
+        from: [f32; 2],
+        to: [f32; 2],
+        #[serde(default = "default_thickness")]
+        thickness: f32,
+    },
+    /// Circular arc from `start` to `end` degrees, clockwise on screen (a full circle by default)
+    Arc {
+        center: [f32; 2],
+        radius: f32,
+        #[serde(default)]
+        start: f32,
+        #[serde(default = "default_arc_end")]
+        end: f32,
+        #[serde(default = "default_thickness")]
+        thickness: f32,
+    },
+    /// Closed polygon, outlined or filled
+    Polygon {
+        points: Vec<[f32; 2]>,
+        #[serde(default)]
+        filled: bool,
+        #[serde(default = "default_thickness")]
+        thickness: f32,
+    },
+    /// Filled disc
+    Disc { center: [f32; 2], radius: f32 },
+    /// Text in the built-in bitmap font, centered on `at`, `scale` cells per font pixel
+    Text {
+        at: [f32; 2],
+        text: String,
+        #[serde(default = "default_text_scale")]
+        scale: f32,
+    },
+}
+
+fn default_thickness() -> f32 {
+    1.0
+}
+
+fn default_arc_end() -> f32 {
+    360.0
+}
+
+fn default_text_scale() -> f32 {
+    2.0
+}
+
+/// Distance from p to the segment a-b
+fn segment_distance(p: [f32; 2], a: [f32; 2], b: [f32; 2]) -> f32 {
+    let (abx, aby) = (b[0] - a[0], b[1] - a[1]);
+    let len_sq = abx * abx + aby * aby;
+    let t = if len_sq > 0.0 {
+        (((p[0] - a[0]) * abx + (p[1] - a[1]) * aby) / len_sq).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    (p[0] - a[0] - t * abx).hypot(p[1] - a[1] - t * aby)
+}
+
+/// Even-odd test of p against the closed polygon
+fn polygon_contains(points: &[[f32; 2]], p: [f32; 2]) -> bool {
+    let mut inside = false;
+    for (i, a) in points.iter().enumerate() {
+        let b = points[(i + 1) % points.len()];
+        if (a[1] > p[1]) != (b[1] > p[1]) && p[0] < a[0] + (p[1] - a[1]) / (b[1] - a[1]) * (b[0] - a[0]) {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+impl SeedShape {
+    /// Whether point (x, y) lies in the shape
+    pub fn contains(&self, x: f32, y: f32) -> bool {
+        let p = [x, y];
+        match self {
+            SeedShape::Point { at } => (x - at[0]).abs() <= 0.5 && (y - at[1]).abs() <= 0.5,
+            SeedShape::Line { from, to, thickness } => segment_distance(p, *from, *to) <= thickness / 2.0,
+            SeedShape::Arc {
+                center,
+                radius,
+                start,
+                end,
+                thickness,
+            } => {
+                let (dx, dy) = (x - center[0], y - center[1]);
+                if (dx.hypot(dy) - radius).abs() > thickness / 2.0 {
+                    return false;
+                }
+                let span = end - start;
+                span >= 360.0 || (dy.atan2(dx).to_degrees() - start).rem_euclid(360.0) <= span
+            }
+            SeedShape::Polygon {
+                points,
+                filled,
+                thickness,
+            } => {
+                if points.is_empty() {
+                    return false;
+                }
+                (*filled && polygon_contains(points, p))
+                    || (0..points.len())
+                        .any(|i| segment_distance(p, points[i], points[(i + 1) % points.len()]) <= thickness / 2.0)
+            }
+            SeedShape::Disc { center, radius } => (x - center[0]).hypot(y - center[1]) <= *radius,
+            SeedShape::Text { at, text, scale } => font::text_contains(text, *scale, x - at[0], y - at[1]),
+        }
+    }
+
+    /// Farthest distance from the origin any point of the shape can lie at
+    pub fn extent(&self) -> f32 {
+        let reach = |p: &[f32; 2], margin: f32| p[0].hypot(p[1]) + margin;
+        match self {
+            SeedShape::Point { at } => reach(at, 1.0),
+            SeedShape::Line { from, to, thickness } => reach(from, *thickness).max(reach(to, *thickness)),
+            SeedShape::Arc {
+                center,
+                radius,
+                thickness,
+                ..
+            } => reach(center, radius + thickness),
+            SeedShape::Polygon { points, thickness, .. } => {
+                points.iter().map(|p| reach(p, *thickness)).fold(0.0, f32::max)
+            }
+            SeedShape::Disc { center, radius } => reach(center, *radius + 1.0),
+            SeedShape::Text { at, text, scale } => reach(at, font::text_reach(text, *scale) + 1.0),
+        }
+    }
+}
+
+/// Seed made of several shapes
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct CompositeSeed {
+    pub shapes: Vec<SeedShape>,
+}
+
+impl CompositeSeed {
+    /// Read a seed file
+    pub fn load_from_file(path: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(path).map_err(|e| format!("Failed to read seed file: {}", e))?;
+        serde_json::from_str(&content).map_err(|e| format!("Failed to parse seed file: {}", e))
+    }
+
+    /// Whether point (x, y) lies in any of the shapes
+    pub fn contains(&self, x: f32, y: f32) -> bool {
+        self.shapes.iter().any(|shape| shape.contains(x, y))
+    }
+
+    /// Farthest distance from the origin any shape can reach
+    pub fn extent(&self) -> f32 {
+        self.shapes.iter().map(SeedShape::extent).fold(0.0, f32::max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shapes_contain_their_cells() {
+        let line = SeedShape::Line {
+            from: [-5.0, 0.0],
+            to: [5.0, 0.0],
+            thickness: 3.0,
+        };
+        assert!(line.contains(0.0, 1.5) && !line.contains(0.0, 2.0) && !line.contains(7.0, 0.0));
+
+        // Lower half of a circle (y points down)
+        let arc = SeedShape::Arc {
+            center: [0.0, 0.0],
+            radius: 10.0,
+            start: 0.0,
+            end: 180.0,
+            thickness: 1.0,
+        };
+        assert!(arc.contains(0.0, 10.0) && !arc.contains(0.0, -10.0) && !arc.contains(0.0, 0.0));
+
+        let square = vec![[0.0, 0.0], [10.0, 0.0], [10.0, 10.0], [0.0, 10.0]];
+        let outline = SeedShape::Polygon {
+            points: square.clone(),
+            filled: false,
+            thickness: 1.0,
+        };
+        let filled = SeedShape::Polygon {
+            points: square,
+            filled: true,
+            thickness: 1.0,
+        };
+        assert!(outline.contains(10.0, 5.0) && !outline.contains(5.0, 5.0));
+        assert!(filled.contains(5.0, 5.0) && !filled.contains(12.0, 5.0));
+
+        let disc = SeedShape::Disc {
+            center: [3.0, 4.0],
+            radius: 2.0,
+        };
+        assert!(disc.contains(3.0, 6.0) && !disc.contains(0.0, 0.0));
+        assert_eq!(disc.extent(), 8.0);
+
+        // "I" at scale 2: 10x14 cells centered on the origin, stem in the middle
+        let text = SeedShape::Text {
+            at: [0.0, 0.0],
+            text: "I".to_string(),
+            scale: 2.0,
+        };
+        assert!(text.contains(0.5, 0.5) && !text.contains(-3.5, 0.5) && !text.contains(0.5, 8.0));
+    }
+
+    #[test]
+    fn test_seed_file_parses_with_defaults() {
+        let json = r#"{ "shapes": [
+            { "type": "line", "from": [-60, -40], "to": [-60, 40] },
+            { "type": "arc", "center": [0, 0], "radius": 20 },
+            { "type": "text", "at": [0, 50], "text": "DLA" },
+            { "type": "point", "at": [0, 0] }
+        ] }"#;
+        let seed: CompositeSeed = serde_json::from_str(json).unwrap();
+        assert_eq!(seed.shapes.len(), 4);
+        assert!(matches!(seed.shapes[0], SeedShape::Line { thickness, .. } if thickness == 1.0));
+        assert!(matches!(seed.shapes[1], SeedShape::Arc { end, .. } if end == 360.0));
+        assert!(seed.contains(-60.0, 0.0) && seed.contains(20.0, 0.0) && !seed.contains(5.0, 5.0));
+        assert!(seed.extent() >= 60.0_f32.hypot(40.0));
+
+        let unknown = r#"{ "shapes": [{ "type": "star", "at": [0, 0] }] }"#;
+        assert!(serde_json::from_str::<CompositeSeed>(unknown).is_err());
+    }
+}
@@ -1,6 +1,6 @@
 use crate::color::ColorScheme;
 use crate::settings::SimulationSettings;
-use crate::simulation::SeedPattern;
+use crate::simulation::{SeedParams, SeedPattern};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::Path;
@@ -14,6 +14,9 @@ pub struct AppConfig {
     pub settings: SimulationSettings,
     /// Seed pattern
     pub seed_pattern: SeedPattern,
+    /// Geometry overrides of the seed pattern
+    #[serde(default)]
+    pub seed_params: SeedParams,
     /// Base stickiness (simulation-level)
     pub stickiness: f32,
     /// Number of particles
@@ -24,6 +27,9 @@ pub struct AppConfig {
     pub steps_per_frame: usize,
     /// Color by age toggle (app-level)
     pub color_by_age: bool,
+    /// Fixed RNG seed for reproducible runs (None = random)
+    #[serde(default)]
+    pub rng_seed: Option<u64>,
 }
 
 impl AppConfig {
@@ -39,7 +45,13 @@ impl AppConfig {
     pub fn load_from_file(path: &Path) -> Result<Self, String> {
         let content =
             fs::read_to_string(path).map_err(|e| format!("Failed to read config file: {}", e))?;
-        serde_json::from_str(&content).map_err(|e| format!("Failed to parse config file: {}", e))
+        let config: Self =
+            serde_json::from_str(&content).map_err(|e| format!("Failed to parse config file: {}", e))?;
+        config
+            .settings
+            .validate()
+            .map_err(|e| format!("Invalid config file: {}", e))?;
+        Ok(config)
     }
 }
 
@@ -49,11 +61,13 @@ impl Default for AppConfig {
             version: 1,
             settings: SimulationSettings::default(),
             seed_pattern: SeedPattern::default(),
+            seed_params: SeedParams::default(),
             stickiness: 1.0,
             num_particles: 5000,
             color_scheme: ColorScheme::default(),
             steps_per_frame: 5,
             color_by_age: true,
+            rng_seed: None,
         }
     }
 }
@@ -61,7 +75,8 @@ impl Default for AppConfig {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::settings::{BoundaryBehavior, ColorMode, NeighborhoodType, SpawnMode};
+    use crate::lattice::LatticeType;
+    use crate::settings::{BoundaryBehavior, ColorMode, GrowthModel, Neighborhood3d, NeighborhoodType, Species, SpawnMode, VoxelView};
     use tempfile::NamedTempFile;
 
     #[test]
@@ -76,7 +91,17 @@ mod tests {
                 adaptive_step: true,
                 adaptive_step_factor: 5.0,
                 lattice_walk: false,
+                lattice: LatticeType::Square,
+                circle_jump: false,
+                off_lattice: false,
+                disc_radius: 0.5,
+                voxels: false,
+                growth_model: GrowthModel::Dla,
+                rla_stickiness: 0.1,
+                dbm_eta: 1.0,
+                cluster_mobility: 0.5,
                 neighborhood: NeighborhoodType::VonNeumann,
+                neighborhood_3d: Neighborhood3d::Faces,
                 multi_contact_min: 2,
                 tip_stickiness: 0.8,
                 side_stickiness: 0.6,
@@ -87,16 +112,24 @@ mod tests {
                 escape_multiplier: 3.0,
                 min_spawn_radius: 30.0,
                 max_walk_iterations: 5000,
+                walker_density: 0.01,
+                obstacle_mask: None,
+                species: Vec::new(),
+                stickiness_matrix: Vec::new(),
                 color_mode: ColorMode::Distance,
                 highlight_recent: 10,
                 invert_colors: true,
+                voxel_view: VoxelView::Slice,
+                slice_offset: 0,
             },
             seed_pattern: SeedPattern::Cross,
+            seed_params: SeedParams::default(),
             stickiness: 0.7,
             num_particles: 3000,
             color_scheme: ColorScheme::Fire,
             steps_per_frame: 10,
             color_by_age: false,
+            rng_seed: Some(1234),
         };
 
         // Serialize to JSON
@@ -118,6 +151,7 @@ mod tests {
         assert_eq!(parsed.color_scheme, config.color_scheme);
         assert_eq!(parsed.steps_per_frame, config.steps_per_frame);
         assert_eq!(parsed.color_by_age, config.color_by_age);
+        assert_eq!(parsed.rng_seed, config.rng_seed);
     }
 
     #[test]
@@ -151,7 +185,17 @@ mod tests {
                 adaptive_step: true,
                 adaptive_step_factor: 8.0,
                 lattice_walk: false,
+                lattice: LatticeType::Triangular,
+                circle_jump: true,
+                off_lattice: true,
+                disc_radius: 1.5,
+                voxels: true,
+                growth_model: GrowthModel::Dlca,
+                rla_stickiness: 0.25,
+                dbm_eta: 2.5,
+                cluster_mobility: 1.25,
                 neighborhood: NeighborhoodType::Extended,
+                neighborhood_3d: Neighborhood3d::Corners,
                 multi_contact_min: 3,
                 tip_stickiness: 0.5,
                 side_stickiness: 0.9,
@@ -162,16 +206,37 @@ mod tests {
                 escape_multiplier: 4.5,
                 min_spawn_radius: 60.0,
                 max_walk_iterations: 20000,
+                walker_density: 0.05,
+                obstacle_mask: None,
+                species: vec![
+                    Species::default(),
+                    Species {
+                        fraction: 0.25,
+                        spawn_mode: Some(SpawnMode::Top),
+                        walk_step_size: Some(2.0),
+                        ..Species::default()
+                    },
+                ],
+                stickiness_matrix: vec![vec![1.0, 0.2], vec![0.7, 1.0]],
                 color_mode: ColorMode::Density,
                 highlight_recent: 25,
                 invert_colors: true,
+                voxel_view: VoxelView::Rotate,
+                slice_offset: -7,
             },
             seed_pattern: SeedPattern::Starburst,
+            seed_params: SeedParams {
+                spokes: Some(12),
+                angle: Some(22.5),
+                offset_x: Some(-10.0),
+                ..SeedParams::default()
+            },
             stickiness: 0.5,
             num_particles: 8000,
             color_scheme: ColorScheme::Neon,
             steps_per_frame: 25,
             color_by_age: false,
+            rng_seed: Some(u64::MAX),
         };
 
         let json = serde_json::to_string(&original).unwrap();
@@ -196,15 +261,39 @@ mod tests {
         assert_eq!(restored.settings.escape_multiplier, 4.5);
         assert_eq!(restored.settings.min_spawn_radius, 60.0);
         assert_eq!(restored.settings.max_walk_iterations, 20000);
+        assert_eq!(restored.settings.walker_density, 0.05);
+        assert_eq!(restored.settings.species, original.settings.species);
+        assert_eq!(restored.settings.species_stickiness(1, 0), 0.7);
+        assert_eq!(restored.settings.lattice, LatticeType::Triangular);
+        assert!(restored.settings.circle_jump);
+        assert!(restored.settings.off_lattice);
+        assert!(restored.settings.voxels);
+        assert_eq!(restored.settings.growth_model, GrowthModel::Dlca);
+        assert_eq!(restored.settings.dbm_eta, 2.5);
+        assert_eq!(restored.settings.rla_stickiness, 0.25);
+        assert_eq!(restored.settings.cluster_mobility, 1.25);
+        assert_eq!(restored.settings.neighborhood_3d, Neighborhood3d::Corners);
+        assert_eq!(restored.settings.voxel_view, VoxelView::Rotate);
+        assert_eq!(restored.settings.slice_offset, -7);
         assert_eq!(restored.settings.color_mode, ColorMode::Density);
         assert_eq!(restored.settings.highlight_recent, 25);
         assert!(restored.settings.invert_colors);
         assert_eq!(restored.seed_pattern, SeedPattern::Starburst);
+        assert_eq!(restored.seed_params, original.seed_params);
         assert_eq!(restored.stickiness, 0.5);
         assert_eq!(restored.num_particles, 8000);
         assert_eq!(restored.color_scheme, ColorScheme::Neon);
         assert_eq!(restored.steps_per_frame, 25);
         assert!(!restored.color_by_age);
+        assert_eq!(restored.rng_seed, Some(u64::MAX));
+    }
+
+    #[test]
+    fn test_config_without_rng_seed_loads() {
+        let mut value = serde_json::to_value(AppConfig::default()).unwrap();
+        value.as_object_mut().unwrap().remove("rng_seed");
+        let parsed: AppConfig = serde_json::from_value(value).unwrap();
+        assert_eq!(parsed.rng_seed, None);
     }
 
     #[test]
@@ -216,6 +305,20 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_config_with_too_many_species_is_rejected() {
+        let mut config = AppConfig::default();
+        config.settings.species = vec![Species::default(); crate::settings::MAX_SPECIES + 1];
+        let temp_file = NamedTempFile::new().unwrap();
+        config.save_to_file(temp_file.path()).unwrap();
+
+        let err = AppConfig::load_from_file(temp_file.path()).unwrap_err();
+        assert!(err.contains("species"), "{}", err);
+        config.settings.species.pop();
+        config.save_to_file(temp_file.path()).unwrap();
+        assert!(AppConfig::load_from_file(temp_file.path()).is_ok());
+    }
+
     #[test]
     fn test_missing_config_file() {
         let result = AppConfig::load_from_file(Path::new("/nonexistent/path/config.json"));
@@ -0,0 +1,352 @@
+//! Potential field for the dielectric breakdown model (Laplacian growth).
+//!
+//! The field covers the lattice cells inside a circle around the cluster.
+//! Cluster cells are held at potential 0, cells on or outside the circle (or
+//! outside the walk domain) at 1, and the open cells in between solve the
+//! Laplace equation, relaxed with successive over-relaxation (SOR). After
+//! each new cell the field is solved to tolerance again, warm-started from
+//! the previous solution: a new cell mostly disturbs the field around itself,
+//! so a few sweeps of a small window there come first and the whole-box
+//! sweeps that follow converge quickly.
+
+use crate::lattice::LatticeType;
+use crate::settings::NeighborhoodType;
+use crate::sparse_grid::SparseGrid;
+use serde::{Deserialize, Serialize};
+
+/// Half-width (in cells) of the window relaxed around each new cell
+const LOCAL_RADIUS: usize = 6;
+/// SOR sweeps of that window after each new cell
+const LOCAL_SWEEPS: usize = 8;
+/// Largest change of a whole-field sweep at which the field counts as solved
+pub const SOLVE_TOLERANCE: f32 = 1e-4;
+/// Cap on whole-field sweeps per solve
+const MAX_SOLVE_SWEEPS: usize = 5000;
+
+/// Role of a cell in the field
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+enum Site {
+    /// Free cell whose potential is relaxed
+    #[default]
+    Open,
+    /// Part of the cluster (potential 0)
+    Cluster,
+    /// Outer electrode (potential 1)
+    Boundary,
+}
+
+/// Empty cell next to the cluster where the next particle may attach
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GrowthSite {
+    pub col: i32,
+    pub row: i32,
+    /// Potential at the cell, proportional to the local field strength
+    pub potential: f32,
+    /// Cluster cells among its neighbors
+    pub neighbors: usize,
+}
+
+/// Laplace potential over a box of lattice cells
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PotentialField {
+    lattice: LatticeType,
+    /// Center and radius of the outer electrode
+    center: (f32, f32),
+    radius: f32,
+    /// Half-open walk domain bounds; cells centered outside it sit at potential 1
+    bounds: (i32, i32, i32, i32),
+    /// Cell index of the box's top-left corner
+    col0: i32,
+    row0: i32,
+    width: usize,
+    height: usize,
+    phi: Vec<f32>,
+    /// Derived from the grid and geometry, so not saved
+    #[serde(skip)]
+    sites: Vec<Site>,
+    /// Number of cluster cells inside the box
+    #[serde(skip)]
+    cluster_cells: usize,
+    /// Box-relative (min col, min row, max col, max row) of the cluster cells
+    #[serde(skip)]
+    cluster_box: (usize, usize, usize, usize),
+    /// A cluster cell touches the electrode (the discharge is complete)
+    #[serde(skip)]
+    reached: bool,
+}
+
+impl PotentialField {
+    /// True before the first rebuild (no DBM step has run)
+    pub fn is_empty(&self) -> bool {
+        self.phi.is_empty()
+    }
+
+    /// Radius of the outer electrode (0 before the first rebuild)
+    pub fn radius(&self) -> f32 {
+        self.radius
+    }
+
+    /// True once the cluster touches the outer electrode
+    pub fn reached_electrode(&self) -> bool {
+        self.reached
+    }
+
+    /// True if the field was built for this lattice, center and domain
+    pub fn matches(&self, lattice: LatticeType, center: (f32, f32), bounds: (i32, i32, i32, i32)) -> bool {
+        !self.phi.is_empty() && self.lattice == lattice && self.center == center && self.bounds == bounds
+    }
+
+    /// Rebuild the field for a new electrode circle, keeping the potential of
+    /// cells that were already covered, and relax it to convergence
+    pub fn rebuild(
+        &mut self,
+        lattice: LatticeType,
+        center: (f32, f32),
+        radius: f32,
+        bounds: (i32, i32, i32, i32),
+        grid: &SparseGrid,
+    ) {
+        let old = std::mem::take(self);
+        let (cx, cy) = center;
+        let (x0, y0, x1, y1) = bounds;
+        // Only the part of the circle inside the walk domain holds open cells
+        let (c0, r0) = lattice.cell_at((cx - radius).max(x0 as f32), (cy - radius).max(y0 as f32));
+        let (c1, r1) = lattice.cell_at((cx + radius).min(x1 as f32), (cy + radius).min(y1 as f32));
+        self.lattice = lattice;
+        self.center = center;
+        self.radius = radius;
+        self.bounds = bounds;
+        // Two cells of margin keep open cells and their neighbors inside the box
+        self.col0 = c0 - 2;
+        self.row0 = r0 - 2;
+        self.width = (c1 - c0 + 5) as usize;
+        self.height = (r1 - r0 + 5) as usize;
+        self.phi = vec![1.0; self.width * self.height];
+        if old.lattice == lattice {
+            for row in 0..self.height {
+                for col in 0..self.width {
+                    let (c, r) = (self.col0 + col as i32, self.row0 + row as i32);
+                    if let Some(idx) = old.index(c, r) {
+                        self.phi[row * self.width + col] = old.phi[idx];
+                    }
+                }
+            }
+        }
+        self.classify(grid);
+        self.solve(SOLVE_TOLERANCE, MAX_SOLVE_SWEEPS);
+    }
+
+    /// Re-derive cluster and boundary cells (after a rebuild or a restore)
+    pub fn classify(&mut self, grid: &SparseGrid) {
+        let (x0, y0, x1, y1) = self.bounds;
+        let (cx, cy) = self.center;
+        let radius_sq = self.radius * self.radius;
+        self.sites = vec![Site::Open; self.width * self.height];
+        self.cluster_cells = 0;
+        self.cluster_box = (usize::MAX, usize::MAX, 0, 0);
+        self.reached = false;
+        for row in 0..self.height {
+            for col in 0..self.width {
+                let idx = row * self.width + col;
+                let (c, r) = (self.col0 + col as i32, self.row0 + row as i32);
+                let (x, y) = self.lattice.cell_center(c, r);
+                let outside = (x - cx).powi(2) + (y - cy).powi(2) >= radius_sq
+                    || x < x0 as f32
+                    || y < y0 as f32
+                    || x >= x1 as f32
+                    || y >= y1 as f32;
+                if grid.is_occupied(c, r) {
+                    self.mark_cluster(idx);
+                } else if outside {
+                    self.sites[idx] = Site::Boundary;
+                    self.phi[idx] = 1.0;
+                }
+            }
+        }
+        for idx in 0..self.sites.len() {
+            if self.sites[idx] == Site::Cluster && self.touches_boundary(idx) {
+                self.reached = true;
+            }
+        }
+    }
+
+    /// True if a lattice neighbor of the cell at `idx` is part of the electrode
+    fn touches_boundary(&self, idx: usize) -> bool {
+        let (c, r) = (self.col0 + (idx % self.width) as i32, self.row0 + (idx / self.width) as i32);
+        self.lattice
+            .neighbor_offsets(c, r, NeighborhoodType::VonNeumann)
+            .iter()
+            .any(|&(dc, dr)| self.index(c + dc, r + dr).is_none_or(|n| self.sites[n] == Site::Boundary))
+    }
+
+    fn index(&self, col: i32, row: i32) -> Option<usize> {
+        let (dc, dr) = (col - self.col0, row - self.row0);
+        (dc >= 0 && (dc as usize) < self.width && dr >= 0 && (dr as usize) < self.height)
+            .then(|| dr as usize * self.width + dc as usize)
+    }
+
+    /// Potential at cell (col, row); 1 outside the box
+    pub fn potential(&self, col: i32, row: i32) -> f32 {
+        self.index(col, row).map_or(1.0, |idx| self.phi[idx])
+    }
+
+    /// One in-place SOR sweep over the open cells of a box-relative window;
+    /// returns the largest change
+    fn sweep(&mut self, cols: (usize, usize), rows: (usize, usize)) -> f32 {
+        // Near-optimal over-relaxation for a window of this size
+        let size = (cols.1 - cols.0).max(rows.1 - rows.0).max(2);
+        let omega = 2.0 / (1.0 + std::f32::consts::PI / size as f32);
+        let mut max_change: f32 = 0.0;
+        for row in rows.0..rows.1 {
+            for col in cols.0..cols.1 {
+                let idx = row * self.width + col;
+                if self.sites[idx] != Site::Open {
+                    continue;
+                }
+                // Open cells never touch the box edge, so their neighbors are in the box
+                let (c, r) = (self.col0 + col as i32, self.row0 + row as i32);
+                let offsets = self.lattice.neighbor_offsets(c, r, NeighborhoodType::VonNeumann);
+                let sum: f32 = offsets
+                    .iter()
+                    .map(|&(dc, dr)| self.phi[(idx as isize + dr as isize * self.width as isize + dc as isize) as usize])
+                    .sum();
+                let change = omega * (sum / offsets.len() as f32 - self.phi[idx]);
+                self.phi[idx] = (self.phi[idx] + change).clamp(0.0, 1.0);
+                max_change = max_change.max(change.abs());
+            }
+        }
+        max_change
+    }
+
+    /// One SOR sweep over the whole field; returns the largest change
+    pub fn relax(&mut self) -> f32 {
+        self.sweep((0, self.width), (0, self.height))
+    }
+
+    /// Relax until the largest change drops below `tolerance` (or `max_sweeps` is hit)
+    pub fn solve(&mut self, tolerance: f32, max_sweeps: usize) {
+        for _ in 0..max_sweeps {
+            if self.relax() < tolerance {
+                break;
+            }
+        }
+    }
+
+    fn mark_cluster(&mut self, idx: usize) {
+        if self.sites[idx] == Site::Cluster {
+            return;
+        }
+        self.sites[idx] = Site::Cluster;
+        self.phi[idx] = 0.0;
+        self.cluster_cells += 1;
+        let (col, row) = (idx % self.width, idx / self.width);
+        let (c0, r0, c1, r1) = self.cluster_box;
+        self.cluster_box = (c0.min(col), r0.min(row), c1.max(col), r1.max(row));
+    }
+
+    /// Turn a cell into part of the cluster and solve the field again: the
+    /// neighborhood of the new cell first, then the whole box to tolerance
+    pub fn insert(&mut self, col: i32, row: i32) {
+        let Some(idx) = self.index(col, row) else {
+            return;
+        };
+        self.mark_cluster(idx);
+        self.reached |= self.touches_boundary(idx);
+        let (col, row) = (idx % self.width, idx / self.width);
+        let cols = (col.saturating_sub(LOCAL_RADIUS), (col + LOCAL_RADIUS + 1).min(self.width));
+        let rows = (row.saturating_sub(LOCAL_RADIUS), (row + LOCAL_RADIUS + 1).min(self.height));
+        for _ in 0..LOCAL_SWEEPS {
+            self.sweep(cols, rows);
+        }
+        self.solve(SOLVE_TOLERANCE, MAX_SOLVE_SWEEPS);
+    }
+
+    /// Open cells touching the cluster under the given neighborhood
+    pub fn growth_sites(&self, neighborhood: NeighborhoodType) -> Vec<GrowthSite> {
+        let mut sites = Vec::new();
+        if self.cluster_cells == 0 {
+            return sites;
+        }
+        // Every growth site lies within reach of the cluster's bounding box
+        let (c0, r0, c1, r1) = self.cluster_box;
+        for row in r0.saturating_sub(2)..(r1 + 3).min(self.height) {
+            for col in c0.saturating_sub(2)..(c1 + 3).min(self.width) {
+                let idx = row * self.width + col;
+                if self.sites[idx] != Site::Open {
+                    continue;
+                }
+                let (c, r) = (self.col0 + col as i32, self.row0 + row as i32);
+                let neighbors = self
+                    .lattice
+                    .neighbor_offsets(c, r, neighborhood)
+                    .iter()
+                    .filter(|&&(dc, dr)| self.index(c + dc, r + dr).is_some_and(|n| self.sites[n] == Site::Cluster))
+                    .count();
+                if neighbors > 0 {
+                    sites.push(GrowthSite {
+                        col: c,
+                        row: r,
+                        potential: self.phi[idx],
+                        neighbors,
+                    });
+                }
+            }
+        }
+        sites
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::ParticleData;
+
+    fn field_around(cells: &[(i32, i32)], lattice: LatticeType) -> PotentialField {
+        let mut grid = SparseGrid::new();
+        for &(x, y) in cells {
+            grid.set(x, y, ParticleData::default());
+        }
+        let mut field = PotentialField::default();
+        field.rebuild(lattice, (50.0, 50.0), 30.0, (0, 0, 100, 100), &grid);
+        field
+    }
+
+    #[test]
+    fn test_potential_rises_logarithmically_from_a_point() {
+        let field = field_around(&[(50, 50)], LatticeType::Square);
+        assert_eq!(field.potential(50, 50), 0.0);
+        // Solution of the 2D Laplace equation between two circles: ln(r) profile
+        let expected = |r: f32| (r / 0.5).ln() / (30.0f32 / 0.5).ln();
+        let mut last = 0.0;
+        for r in [2, 4, 8, 16] {
+            let phi = field.potential(50 + r, 50);
+            assert!(phi > last, "not increasing at {}", r);
+            assert!((phi - expected(r as f32 + 0.5)).abs() < 0.1, "r={}: {} vs {}", r, phi, expected(r as f32));
+            last = phi;
+        }
+        assert_eq!(field.potential(90, 50), 1.0);
+    }
+
+    #[test]
+    fn test_tips_see_a_stronger_field_than_sides() {
+        // A horizontal rod: the cells past its ends lie in a stronger field than beside its middle
+        let rod: Vec<(i32, i32)> = (40..=60).map(|x| (x, 50)).collect();
+        for lattice in [LatticeType::Square, LatticeType::Hexagonal] {
+            let field = field_around(&rod, lattice);
+            let sites = field.growth_sites(NeighborhoodType::VonNeumann);
+            let at = |col, row| sites.iter().find(|s| (s.col, s.row) == (col, row)).unwrap().potential;
+            assert!(at(61, 50) > 2.0 * at(50, 49), "{:?}", lattice);
+        }
+    }
+
+    #[test]
+    fn test_insert_marks_the_cell_and_new_sites() {
+        let mut field = field_around(&[(50, 50)], LatticeType::Square);
+        assert_eq!(field.growth_sites(NeighborhoodType::VonNeumann).len(), 4);
+        assert_eq!(field.growth_sites(NeighborhoodType::Moore).len(), 8);
+        field.insert(51, 50);
+        let sites = field.growth_sites(NeighborhoodType::VonNeumann);
+        assert_eq!(sites.len(), 6);
+        assert!(sites.iter().all(|s| s.neighbors == 1 && s.potential > 0.0));
+    }
+}
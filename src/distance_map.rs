@@ -0,0 +1,261 @@
+//! Distance-to-cluster field used for circle jumping.
+//!
+//! Every cell near the cluster holds the 3-4 chamfer distance to the nearest
+//! occupied cell, capped at `MAX_DISTANCE` cells. The field is rebuilt with the
+//! classic two-pass sweep after seeding and updated incrementally as particles
+//! stick, so a walker can look up how far it may jump without touching the
+//! cluster.
+//!
+//! The map only covers the occupied cells' bounding box padded by
+//! `MAX_DISTANCE`, growing as the cluster does; every cell outside it is at
+//! least `MAX_DISTANCE` away from the cluster.
+
+use crate::sparse_grid::SparseGrid;
+use std::collections::VecDeque;
+
+/// Distances are tracked up to this many cells; farther cells read as this value
+pub const MAX_DISTANCE: u16 = 64;
+
+/// Chamfer weight of an orthogonal step
+const ORTHO: u16 = 3;
+/// Chamfer weight of a diagonal step
+const DIAG: u16 = 4;
+/// Capped distance in chamfer units
+const CAP: u16 = MAX_DISTANCE * ORTHO;
+/// Largest ratio of the 3-4 chamfer distance (in cells) to the Euclidean distance
+const CHAMFER_OVERESTIMATE: f32 = 1.055;
+
+const NEIGHBORS: [(i32, i32, u16); 8] = [
+    (-1, -1, DIAG),
+    (0, -1, ORTHO),
+    (1, -1, DIAG),
+    (-1, 0, ORTHO),
+    (1, 0, ORTHO),
+    (-1, 1, DIAG),
+    (0, 1, ORTHO),
+    (1, 1, DIAG),
+];
+
+/// Chamfer distance map over the rectangle of the simulation grid around the cluster
+#[derive(Debug, Clone, Default)]
+pub struct DistanceMap {
+    /// Cell coordinates of the map's top-left corner
+    x0: i32,
+    y0: i32,
+    width: usize,
+    height: usize,
+    dist: Vec<u16>,
+    queue: VecDeque<usize>,
+}
+
+impl DistanceMap {
+    /// Recompute the field, measuring the distance to the grid's particles
+    /// and any `extra` cells
+    pub fn rebuild(&mut self, grid: &SparseGrid, extra: impl Iterator<Item = (i32, i32)>) {
+        let cells: Vec<(i32, i32)> = grid.iter().map(|(x, y, _)| (x, y)).chain(extra).collect();
+        *self = Self::default();
+        let Some((x0, y0, x1, y1)) = cells.iter().fold(None, |bounds, &(x, y)| {
+            Some(match bounds {
+                Some((x0, y0, x1, y1)) => (x.min(x0), y.min(y0), x.max(x1), y.max(y1)),
+                None => (x, y, x, y),
+            })
+        }) else {
+            return;
+        };
+        let pad = MAX_DISTANCE as i32;
+        self.relayout(x0 - pad, y0 - pad, x1 + pad + 1, y1 + pad + 1);
+        for (x, y) in cells {
+            if let Some(idx) = self.index(x, y) {
+                self.dist[idx] = 0;
+            }
+        }
+        let (width, height) = (self.width, self.height);
+
+        // Forward pass (top-left to bottom-right), then backward pass
+        for y in 0..height {
+            for x in 0..width {
+                self.relax(x, y, &NEIGHBORS[..4]);
+            }
+        }
+        for y in (0..height).rev() {
+            for x in (0..width).rev() {
+                self.relax(x, y, &NEIGHBORS[4..]);
+            }
+        }
+    }
+
+    /// Move the map to the half-open rectangle (x0, y0)..(x1, y1), which must
+    /// contain the current one; the new cells are beyond the cap
+    fn relayout(&mut self, x0: i32, y0: i32, x1: i32, y1: i32) {
+        let (width, height) = ((x1 - x0) as usize, (y1 - y0) as usize);
+        let mut dist = vec![CAP; width * height];
+        let (dx, dy) = ((self.x0 - x0) as usize, (self.y0 - y0) as usize);
+        for (row, old) in self.dist.chunks_exact(self.width.max(1)).enumerate() {
+            let start = (row + dy) * width + dx;
+            dist[start..start + self.width].copy_from_slice(old);
+        }
+        self.x0 = x0;
+        self.y0 = y0;
+        self.width = width;
+        self.height = height;
+        self.dist = dist;
+    }
+
+    /// Grow the map so it covers every cell within `MAX_DISTANCE` of (x, y).
+    ///
+    /// The map grows by at least a quarter of its size on each side that
+    /// needs room, so growing with the cluster is amortized.
+    fn cover(&mut self, x: i32, y: i32) {
+        let pad = MAX_DISTANCE as i32;
+        let (x1, y1) = (self.x0 + self.width as i32, self.y0 + self.height as i32);
+        if self.width > 0 && x - pad >= self.x0 && x + pad < x1 && y - pad >= self.y0 && y + pad < y1 {
+            return;
+        }
+        if self.width == 0 {
+            self.relayout(x - pad, y - pad, x + pad + 1, y + pad + 1);
+            return;
+        }
+        let slack = pad.max(self.width.max(self.height) as i32 / 4);
+        self.relayout(
+            if x - pad < self.x0 { x - pad - slack } else { self.x0 },
+            if y - pad < self.y0 { y - pad - slack } else { self.y0 },
+            if x + pad >= x1 { x + pad + 1 + slack } else { x1 },
+            if y + pad >= y1 { y + pad + 1 + slack } else { y1 },
+        );
+    }
+
+    fn index(&self, x: i32, y: i32) -> Option<usize> {
+        let (dx, dy) = (x - self.x0, y - self.y0);
+        (dx >= 0 && (dx as usize) < self.width && dy >= 0 && (dy as usize) < self.height)
+            .then(|| dy as usize * self.width + dx as usize)
+    }
+
+    /// Lower a cell's distance from already-swept neighbors
+    fn relax(&mut self, x: usize, y: usize, neighbors: &[(i32, i32, u16)]) {
+        let idx = y * self.width + x;
+        let mut best = self.dist[idx];
+        for &(dx, dy, weight) in neighbors {
+            let nx = x as i32 + dx;
+            let ny = y as i32 + dy;
+            if nx >= 0 && nx < self.width as i32 && ny >= 0 && ny < self.height as i32 {
+                let n = self.dist[ny as usize * self.width + nx as usize];
+                best = best.min(n.saturating_add(weight));
+            }
+        }
+        self.dist[idx] = best.min(CAP);
+    }
+
+    /// Mark a cell occupied and lower the distances around it
+    pub fn insert(&mut self, x: i32, y: i32) {
+        self.cover(x, y);
+        let Some(idx) = self.index(x, y) else {
+            return;
+        };
+        if self.dist[idx] == 0 {
+            return;
+        }
+        self.dist[idx] = 0;
+        self.queue.push_back(idx);
+
+        while let Some(idx) = self.queue.pop_front() {
+            let (x, y) = ((idx % self.width) as i32, (idx / self.width) as i32);
+            let d = self.dist[idx];
+            for &(dx, dy, weight) in &NEIGHBORS {
+                let nx = x + dx;
+                let ny = y + dy;
+                if nx < 0 || nx >= self.width as i32 || ny < 0 || ny >= self.height as i32 {
+                    continue;
+                }
+                let nidx = ny as usize * self.width + nx as usize;
+                let nd = d + weight;
+                if nd < self.dist[nidx] && nd < CAP {
+                    self.dist[nidx] = nd;
+                    self.queue.push_back(nidx);
+                }
+            }
+        }
+    }
+
+    /// Lower bound on the Euclidean distance (in cells) from the center of
+    /// (x, y) to the center of the nearest occupied cell
+    pub fn distance_at(&self, x: i32, y: i32) -> f32 {
+        let d = self.index(x, y).map_or(CAP, |idx| self.dist[idx]);
+        d as f32 / ORTHO as f32 / CHAMFER_OVERESTIMATE
+    }
+
+    /// Number of cells the map stores
+    pub fn area(&self) -> usize {
+        self.dist.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::simulation::ParticleData;
+
+    fn grid_with(cells: &[(i32, i32)]) -> SparseGrid {
+        let mut grid = SparseGrid::new();
+        for &(x, y) in cells {
+            grid.set(x, y, ParticleData::default());
+        }
+        grid
+    }
+
+    #[test]
+    fn test_incremental_matches_rebuild() {
+        // The last cell lies far outside the first map, which has to grow
+        let cells = [(30, 20), (31, 20), (0, -5), (60, 45), (15, 34), (400, -300)];
+        let mut incremental = DistanceMap::default();
+        incremental.rebuild(&grid_with(&cells[..1]), std::iter::empty());
+        for &(x, y) in &cells[1..] {
+            incremental.insert(x, y);
+        }
+
+        let mut full = DistanceMap::default();
+        full.rebuild(&grid_with(&cells), std::iter::empty());
+        for y in -400..150 {
+            for x in -100..500 {
+                assert_eq!(incremental.distance_at(x, y), full.distance_at(x, y), "({}, {})", x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn test_map_only_covers_the_cluster() {
+        let mut map = DistanceMap::default();
+        map.rebuild(&grid_with(&[(-5000, 7000), (-4990, 7003)]), std::iter::empty());
+        let side = 2 * MAX_DISTANCE as usize + 1;
+        assert_eq!(map.area(), (side + 10) * (side + 3));
+        // Beyond the map every cell is at least the cap away
+        assert_eq!(map.distance_at(0, 0), map.distance_at(-5000, 7000 + MAX_DISTANCE as i32));
+        assert_eq!(map.distance_at(-4990, 7003), 0.0);
+
+        map.rebuild(&SparseGrid::new(), std::iter::empty());
+        assert_eq!(map.area(), 0);
+    }
+
+    #[test]
+    fn test_distance_is_a_lower_bound() {
+        let (w, h) = (90, 90);
+        let cells = [(45, 45), (20, 70), (80, 10)];
+        let mut map = DistanceMap::default();
+        map.rebuild(&grid_with(&cells), std::iter::empty());
+
+        for y in 0..h {
+            for x in 0..w {
+                let exact = cells
+                    .iter()
+                    .map(|&(cx, cy)| ((x as f32 - cx as f32).powi(2) + (y as f32 - cy as f32).powi(2)).sqrt())
+                    .fold(f32::MAX, f32::min);
+                let bound = map.distance_at(x, y);
+                assert!(bound <= exact + 1e-4, "({}, {}): {} > {}", x, y, bound, exact);
+                if exact < MAX_DISTANCE as f32 * 0.9 {
+                    // Tight enough to be useful: within the chamfer error
+                    assert!(bound >= exact * 0.88, "({}, {}): {} << {}", x, y, bound, exact);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,430 @@
+//! Diffusion-limited cluster-cluster aggregation (DLCA).
+//!
+//! Particles start scattered over a box of square cells and every cluster
+//! random-walks as a rigid body, one cell per move, with a mobility that
+//! falls off with its mass as size^-gamma. Clusters that touch merge for good.
+//! Membership is tracked with a union-find over particle indices, and the
+//! root of each set also keeps its member list so a cluster moves in one pass.
+//! Every move is mirrored into the sparse grid, which stays what is drawn.
+
+use crate::settings::NeighborhoodType;
+use crate::simulation::ParticleData;
+use crate::sparse_grid::SparseGrid;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Occupancy value of an empty cell
+const EMPTY: u32 = u32::MAX;
+/// Samples of the cluster-count history kept before its resolution is halved
+const HISTORY_LEN: usize = 512;
+/// Unit moves of a cluster
+const MOVES: [(i32, i32); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+
+/// Scattered particles merging into clusters inside a `width` x `height` box
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ClusterAggregation {
+    width: usize,
+    height: usize,
+    /// Clusters leaving one edge re-enter at the other (otherwise the edges are walls)
+    periodic: bool,
+    /// Cell of each particle
+    cells: Vec<(i32, i32)>,
+    /// What each particle is drawn with
+    data: Vec<ParticleData>,
+    /// Union-find parent of each particle
+    parent: Vec<u32>,
+    /// Elapsed time in moves of a monomer
+    time: f64,
+    sweeps: usize,
+    /// Cluster count after every `history_stride` sweeps
+    history: Vec<u32>,
+    history_stride: usize,
+    /// Particles of each cluster, stored at its root (empty for other particles)
+    members: Vec<Vec<u32>>,
+    /// Roots of the live clusters
+    roots: Vec<u32>,
+    /// Particle index at each cell of the box (EMPTY if free); rebuilt on restore
+    #[serde(skip)]
+    occupancy: Vec<u32>,
+    /// Position of each root in `roots`
+    #[serde(skip)]
+    root_slot: Vec<u32>,
+    /// Number of clusters of each size
+    #[serde(skip)]
+    size_counts: BTreeMap<usize, usize>,
+}
+
+impl ClusterAggregation {
+    pub fn new(width: usize, height: usize, periodic: bool) -> Self {
+        Self {
+            width,
+            height,
+            periodic,
+            history_stride: 1,
+            occupancy: vec![EMPTY; width * height],
+            ..Self::default()
+        }
+    }
+
+    /// Rebuild the lookup tables that are not saved (after deserializing)
+    pub fn rebuild_index(&mut self) {
+        self.occupancy = vec![EMPTY; self.width * self.height];
+        for (i, &(x, y)) in self.cells.iter().enumerate() {
+            let idx = self.index(x, y);
+            self.occupancy[idx] = i as u32;
+        }
+        self.root_slot = vec![0; self.cells.len()];
+        self.size_counts.clear();
+        for (slot, &root) in self.roots.iter().enumerate() {
+            self.root_slot[root as usize] = slot as u32;
+            *self.size_counts.entry(self.members[root as usize].len()).or_default() += 1;
+        }
+    }
+
+    /// True if this aggregation runs in a box of the given size and wrapping
+    pub fn matches(&self, width: usize, height: usize, periodic: bool) -> bool {
+        !self.is_empty() && (self.width, self.height, self.periodic) == (width, height, periodic)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn particle_count(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn cluster_count(&self) -> usize {
+        self.roots.len()
+    }
+
+    /// All particles have joined a single cluster
+    pub fn is_done(&self) -> bool {
+        self.roots.len() == 1
+    }
+
+    /// Elapsed time in moves of a monomer
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
+    /// Mass of the largest cluster
+    pub fn largest(&self) -> usize {
+        self.size_counts.keys().next_back().copied().unwrap_or(0)
+    }
+
+    /// Mean cluster mass
+    pub fn mean_size(&self) -> f64 {
+        self.cells.len() as f64 / self.roots.len().max(1) as f64
+    }
+
+    /// Cluster counts by mass in powers of two, as (min mass, max mass, clusters)
+    pub fn size_histogram(&self) -> Vec<(usize, usize, usize)> {
+        let mut bins: Vec<(usize, usize, usize)> = Vec::new();
+        for (&size, &count) in &self.size_counts {
+            let bin = size.ilog2() as usize;
+            while bins.len() <= bin {
+                let lo = 1 << bins.len();
+                bins.push((lo, 2 * lo - 1, 0));
+            }
+            bins[bin].2 += count;
+        }
+        bins
+    }
+
+    /// Cluster count over time, oldest first, evenly spaced in sweeps
+    pub fn history(&self) -> &[u32] {
+        &self.history
+    }
+
+    /// Particles as (x, y, data)
+    pub fn iter(&self) -> impl Iterator<Item = (i32, i32, ParticleData)> + '_ {
+        self.cells.iter().zip(&self.data).map(|(&(x, y), &data)| (x, y, data))
+    }
+
+    fn index(&self, x: i32, y: i32) -> usize {
+        y as usize * self.width + x as usize
+    }
+
+    /// Cell (x + dx, y + dy), wrapped on a periodic box; None if it leaves a walled box
+    fn shifted(&self, (x, y): (i32, i32), dx: i32, dy: i32) -> Option<(i32, i32)> {
+        let (x, y) = (x + dx, y + dy);
+        let (w, h) = (self.width as i32, self.height as i32);
+        if self.periodic {
+            Some((x.rem_euclid(w), y.rem_euclid(h)))
+        } else {
+            (x >= 0 && x < w && y >= 0 && y < h).then_some((x, y))
+        }
+    }
+
+    /// Root of particle `i`'s cluster (with path halving)
+    fn find(&mut self, mut i: u32) -> u32 {
+        while self.parent[i as usize] != i {
+            let grandparent = self.parent[self.parent[i as usize] as usize];
+            self.parent[i as usize] = grandparent;
+            i = grandparent;
+        }
+        i
+    }
+
+    /// Add a particle as a new cluster, merged with any cluster it touches.
+    /// Returns false if the cell is outside the box or already taken.
+    pub fn add(&mut self, x: i32, y: i32, data: ParticleData, neighborhood: NeighborhoodType) -> bool {
+        if self.shifted((x, y), 0, 0) != Some((x, y)) || self.occupancy[self.index(x, y)] != EMPTY {
+            return false;
+        }
+        let id = self.cells.len() as u32;
+        let idx = self.index(x, y);
+        self.occupancy[idx] = id;
+        self.cells.push((x, y));
+        self.data.push(data);
+        self.parent.push(id);
+        self.members.push(vec![id]);
+        self.root_slot.push(self.roots.len() as u32);
+        self.roots.push(id);
+        *self.size_counts.entry(1).or_default() += 1;
+        for other in self.touching(id, neighborhood) {
+            let root = self.find(id);
+            self.union(root, other);
+        }
+        true
+    }
+
+    /// Roots of the other clusters in contact with cluster `root`
+    fn touching(&mut self, root: u32, neighborhood: NeighborhoodType) -> Vec<u32> {
+        let mut found = Vec::new();
+        for k in 0..self.members[root as usize].len() {
+            let cell = self.cells[self.members[root as usize][k] as usize];
+            for &(dx, dy) in neighborhood.offsets() {
+                let Some((nx, ny)) = self.shifted(cell, dx, dy) else {
+                    continue;
+                };
+                let occupant = self.occupancy[self.index(nx, ny)];
+                if occupant != EMPTY {
+                    let other = self.find(occupant);
+                    if other != root && !found.contains(&other) {
+                        found.push(other);
+                    }
+                }
+            }
+        }
+        found
+    }
+
+    /// Merge two clusters by root (union by size: the smaller one joins the larger)
+    fn union(&mut self, a: u32, b: u32) {
+        let (big, small) = if self.members[a as usize].len() >= self.members[b as usize].len() {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        let joined = std::mem::take(&mut self.members[small as usize]);
+        let big_size = self.members[big as usize].len();
+        for size in [big_size, joined.len()] {
+            if let Some(count) = self.size_counts.get_mut(&size) {
+                *count -= 1;
+                if *count == 0 {
+                    self.size_counts.remove(&size);
+                }
+            }
+        }
+        *self.size_counts.entry(big_size + joined.len()).or_default() += 1;
+        self.members[big as usize].extend(joined);
+        self.parent[small as usize] = big;
+
+        let slot = self.root_slot[small as usize] as usize;
+        self.roots.swap_remove(slot);
+        if let Some(&moved) = self.roots.get(slot) {
+            self.root_slot[moved as usize] = slot as u32;
+        }
+    }
+
+    /// Give every cluster one chance to move on average (one unit of time for
+    /// the most mobile cluster), merging clusters that come into contact
+    pub fn sweep<R: Rng>(&mut self, grid: &mut SparseGrid, rng: &mut R, gamma: f32, neighborhood: NeighborhoodType) {
+        let Some(&min_size) = self.size_counts.keys().next() else {
+            return;
+        };
+        for _ in 0..self.roots.len() {
+            if self.roots.len() <= 1 {
+                break;
+            }
+            let root = self.roots[rng.gen_range(0..self.roots.len())];
+            // Mobility relative to the smallest cluster, so something always moves
+            let size = self.members[root as usize].len();
+            if size > min_size && rng.gen::<f64>() >= (min_size as f64 / size as f64).powf(gamma as f64) {
+                continue;
+            }
+            let (dx, dy) = MOVES[rng.gen_range(0..MOVES.len())];
+            self.try_move(root, dx, dy, grid, neighborhood);
+        }
+        self.time += (min_size as f64).powf(gamma as f64);
+        self.sweeps += 1;
+        if self.sweeps.is_multiple_of(self.history_stride) {
+            self.history.push(self.roots.len() as u32);
+            if self.history.len() >= HISTORY_LEN {
+                self.history = self.history.iter().step_by(2).copied().collect();
+                self.history_stride *= 2;
+            }
+        }
+    }
+
+    /// Move a cluster by one cell unless a wall or another cluster is in the way
+    fn try_move(&mut self, root: u32, dx: i32, dy: i32, grid: &mut SparseGrid, neighborhood: NeighborhoodType) {
+        let members = std::mem::take(&mut self.members[root as usize]);
+        let mut targets = Vec::with_capacity(members.len());
+        for &m in &members {
+            let target = self.shifted(self.cells[m as usize], dx, dy);
+            let blocked = target.is_none_or(|(x, y)| {
+                let occupant = self.occupancy[self.index(x, y)];
+                occupant != EMPTY && self.find(occupant) != root
+            });
+            if blocked {
+                self.members[root as usize] = members;
+                return;
+            }
+            targets.extend(target);
+        }
+
+        for &m in &members {
+            let (x, y) = self.cells[m as usize];
+            let idx = self.index(x, y);
+            self.occupancy[idx] = EMPTY;
+            grid.remove(x, y);
+        }
+        for (&m, &(x, y)) in members.iter().zip(&targets) {
+            let idx = self.index(x, y);
+            self.occupancy[idx] = m;
+            self.cells[m as usize] = (x, y);
+            grid.set(x, y, self.data[m as usize]);
+        }
+        self.members[root as usize] = members;
+
+        for other in self.touching(root, neighborhood) {
+            let survivor = self.find(root);
+            let other = self.find(other);
+            if survivor == other {
+                continue;
+            }
+            // A particle counts as stuck from the first time it joins another
+            let monomers: Vec<u32> = [survivor, other]
+                .into_iter()
+                .filter(|&r| self.members[r as usize].len() == 1)
+                .collect();
+            self.union(survivor, other);
+            for m in monomers {
+                self.mark_stuck(m, (dx, dy), neighborhood, grid);
+            }
+        }
+    }
+
+    /// Record particle `m`, which just joined a cluster while moving in `direction`, as stuck now
+    fn mark_stuck(&mut self, m: u32, direction: (i32, i32), neighborhood: NeighborhoodType, grid: &mut SparseGrid) {
+        let m = m as usize;
+        let (x, y) = self.cells[m];
+        let neighbors = neighborhood
+            .offsets()
+            .iter()
+            .filter(|&&(dx, dy)| {
+                self.shifted((x, y), dx, dy)
+                    .is_some_and(|(nx, ny)| self.occupancy[self.index(nx, ny)] != EMPTY)
+            })
+            .count();
+        let (px, py) = (x as f32 + 0.5 - self.width as f32 / 2.0, y as f32 + 0.5 - self.height as f32 / 2.0);
+        self.data[m] = ParticleData {
+            // Merges so far, which is the particle count a walker run would be at
+            age: self.cells.len() - self.roots.len(),
+            distance: (px * px + py * py).sqrt(),
+            direction: (direction.1 as f32).atan2(direction.0 as f32),
+            neighbor_count: neighbors as u8,
+            species: self.data[m].species,
+            ..ParticleData::default()
+        };
+        grid.set(x, y, self.data[m]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    const FOUR: NeighborhoodType = NeighborhoodType::VonNeumann;
+
+    fn aggregation(width: usize, height: usize, periodic: bool, cells: &[(i32, i32)]) -> (ClusterAggregation, SparseGrid) {
+        let mut aggregation = ClusterAggregation::new(width, height, periodic);
+        let mut grid = SparseGrid::new();
+        for &(x, y) in cells {
+            assert!(aggregation.add(x, y, ParticleData::default(), FOUR));
+            grid.set(x, y, ParticleData::default());
+        }
+        (aggregation, grid)
+    }
+
+    #[test]
+    fn test_touching_particles_start_merged() {
+        let (aggregation, _) = aggregation(10, 10, false, &[(0, 0), (5, 5), (1, 0), (2, 0)]);
+        assert_eq!(aggregation.cluster_count(), 2);
+        assert_eq!(aggregation.largest(), 3);
+        assert_eq!(aggregation.size_histogram(), vec![(1, 1, 1), (2, 3, 1)]);
+        let mut taken = aggregation.clone();
+        assert!(!taken.add(5, 5, ParticleData::default(), FOUR));
+        assert!(!taken.add(10, 0, ParticleData::default(), FOUR));
+    }
+
+    #[test]
+    fn test_walls_block_and_periodic_edges_wrap() {
+        let (mut walled, mut grid) = aggregation(4, 4, false, &[(0, 0)]);
+        walled.try_move(0, -1, 0, &mut grid, FOUR);
+        assert_eq!(walled.cells[0], (0, 0));
+
+        let (mut periodic, mut grid) = aggregation(4, 4, true, &[(0, 0)]);
+        periodic.try_move(0, -1, 0, &mut grid, FOUR);
+        assert_eq!(periodic.cells[0], (3, 0));
+        assert!(grid.is_occupied(3, 0) && !grid.is_occupied(0, 0));
+    }
+
+    #[test]
+    fn test_clusters_diffuse_until_one_is_left() {
+        let (mut aggregation, mut grid) = aggregation(12, 12, false, &[(1, 1), (2, 1), (10, 10), (6, 2), (3, 9)]);
+        let mut rng = ChaCha8Rng::seed_from_u64(4);
+        while !aggregation.is_done() {
+            aggregation.sweep(&mut grid, &mut rng, 0.5, FOUR);
+        }
+        assert_eq!(aggregation.largest(), 5);
+        assert!(aggregation.time() > 0.0);
+        assert!(!aggregation.history().is_empty());
+        // The grid mirrors the particles, and those that started out alone carry their merge order
+        let mut cells: Vec<_> = aggregation.iter().map(|(x, y, _)| (x, y)).collect();
+        cells.sort();
+        let mut drawn: Vec<_> = grid.iter().map(|(x, y, _)| (x, y)).collect();
+        drawn.sort();
+        assert_eq!(cells, drawn);
+        assert_eq!(aggregation.iter().filter(|(_, _, data)| data.age > 0).count(), 3);
+    }
+
+    #[test]
+    fn test_saved_aggregation_restores_its_index() {
+        let (mut aggregation, mut grid) = aggregation(16, 16, true, &[(0, 0), (1, 0), (8, 8), (12, 3), (15, 15)]);
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        aggregation.sweep(&mut grid, &mut rng, 1.0, FOUR);
+
+        let json = serde_json::to_string(&aggregation).unwrap();
+        let mut restored: ClusterAggregation = serde_json::from_str(&json).unwrap();
+        restored.rebuild_index();
+        assert_eq!(restored.cluster_count(), aggregation.cluster_count());
+        assert_eq!(restored.size_histogram(), aggregation.size_histogram());
+        assert_eq!(restored.occupancy, aggregation.occupancy);
+
+        // Both continue identically from the same random state
+        let (mut rng_a, mut rng_b) = (ChaCha8Rng::seed_from_u64(2), ChaCha8Rng::seed_from_u64(2));
+        let mut restored_grid = grid.clone();
+        for _ in 0..20 {
+            aggregation.sweep(&mut grid, &mut rng_a, 1.0, FOUR);
+            restored.sweep(&mut restored_grid, &mut rng_b, 1.0, FOUR);
+        }
+        assert_eq!(restored.cells, aggregation.cells);
+    }
+}
@@ -0,0 +1,88 @@
+//! Growth front of the Eden model.
+//!
+//! Eden growth attaches every new cell at a uniformly random empty site next
+//! to the cluster. The perimeter keeps those sites in a vector indexed by
+//! position, so picking, adding and removing a site are all O(1) and the
+//! cluster's bounding box is never rescanned per particle.
+
+use std::collections::HashMap;
+
+/// Set of empty lattice cells next to the cluster
+#[derive(Debug, Clone, Default)]
+pub struct Perimeter {
+    sites: Vec<(i32, i32)>,
+    /// Position of each site in `sites`
+    index: HashMap<(i32, i32), usize>,
+}
+
+impl Perimeter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.sites.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sites.is_empty()
+    }
+
+    /// Site number `i` (in no particular order, for uniform picks)
+    pub fn get(&self, i: usize) -> (i32, i32) {
+        self.sites[i]
+    }
+
+    pub fn contains(&self, col: i32, row: i32) -> bool {
+        self.index.contains_key(&(col, row))
+    }
+
+    /// Add a site, returning false if it was already there
+    pub fn insert(&mut self, col: i32, row: i32) -> bool {
+        if self.contains(col, row) {
+            return false;
+        }
+        self.index.insert((col, row), self.sites.len());
+        self.sites.push((col, row));
+        true
+    }
+
+    /// Remove a site, returning false if it wasn't there
+    pub fn remove(&mut self, col: i32, row: i32) -> bool {
+        let Some(i) = self.index.remove(&(col, row)) else {
+            return false;
+        };
+        self.sites.swap_remove(i);
+        if let Some(&moved) = self.sites.get(i) {
+            self.index.insert(moved, i);
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_and_remove_keep_the_index() {
+        let mut perimeter = Perimeter::new();
+        for site in [(0, 0), (1, 0), (-3, 7), (5, 5)] {
+            assert!(perimeter.insert(site.0, site.1));
+        }
+        assert!(!perimeter.insert(1, 0));
+        assert!(perimeter.remove(0, 0));
+        assert!(!perimeter.remove(0, 0));
+        assert!(!perimeter.contains(0, 0));
+        assert_eq!(perimeter.len(), 3);
+
+        // Every remaining site is found where the index says
+        let mut sites: Vec<_> = (0..perimeter.len()).map(|i| perimeter.get(i)).collect();
+        sites.sort();
+        assert_eq!(sites, [(-3, 7), (1, 0), (5, 5)]);
+        for &(col, row) in &sites {
+            assert!(perimeter.remove(col, row));
+        }
+        assert!(perimeter.is_empty());
+    }
+}
@@ -0,0 +1,148 @@
+//! Built-in 5x7 bitmap font for text seeds.
+//!
+//! Covers digits, letters (lowercase is drawn as uppercase) and common
+//! punctuation; other characters are drawn as '?'. Text may span several
+//! lines separated by '\n', each centered in the block.
+
+/// Glyph width in font pixels
+pub const GLYPH_WIDTH: usize = 5;
+/// Glyph height in font pixels
+pub const GLYPH_HEIGHT: usize = 7;
+/// Blank font pixels between characters and between lines
+const GAP: usize = 1;
+
+/// Glyph rows, top to bottom; bit 4 is the leftmost pixel
+fn glyph(c: char) -> [u8; GLYPH_HEIGHT] {
+    match c.to_ascii_uppercase() {
+        'A' => [0b01110, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001],
+        'B' => [0b11110, 0b10001, 0b10001, 0b11110, 0b10001, 0b10001, 0b11110],
+        'C' => [0b01110, 0b10001, 0b10000, 0b10000, 0b10000, 0b10001, 0b01110],
+        'D' => [0b11100, 0b10010, 0b10001, 0b10001, 0b10001, 0b10010, 0b11100],
+        'E' => [0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b11111],
+        'F' => [0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b10000],
+        'G' => [0b01110, 0b10001, 0b10000, 0b10111, 0b10001, 0b10001, 0b01111],
+        'H' => [0b10001, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001],
+        'I' => [0b01110, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110],
+        'J' => [0b00111, 0b00010, 0b00010, 0b00010, 0b00010, 0b10010, 0b01100],
+        'K' => [0b10001, 0b10010, 0b10100, 0b11000, 0b10100, 0b10010, 0b10001],
+        'L' => [0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b11111],
+        'M' => [0b10001, 0b11011, 0b10101, 0b10101, 0b10001, 0b10001, 0b10001],
+        'N' => [0b10001, 0b10001, 0b11001, 0b10101, 0b10011, 0b10001, 0b10001],
+        'O' => [0b01110, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110],
+        'P' => [0b11110, 0b10001, 0b10001, 0b11110, 0b10000, 0b10000, 0b10000],
+        'Q' => [0b01110, 0b10001, 0b10001, 0b10001, 0b10101, 0b10010, 0b01101],
+        'R' => [0b11110, 0b10001, 0b10001, 0b11110, 0b10100, 0b10010, 0b10001],
+        'S' => [0b01111, 0b10000, 0b10000, 0b01110, 0b00001, 0b00001, 0b11110],
+        'T' => [0b11111, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100],
+        'U' => [0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110],
+        'V' => [0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01010, 0b00100],
+        'W' => [0b10001, 0b10001, 0b10001, 0b10101, 0b10101, 0b10101, 0b01010],
+        'X' => [0b10001, 0b10001, 0b01010, 0b00100, 0b01010, 0b10001, 0b10001],
+        'Y' => [0b10001, 0b10001, 0b10001, 0b01010, 0b00100, 0b00100, 0b00100],
+        'Z' => [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0b11111],
+        '0' => [0b01110, 0b10001, 0b10011, 0b10101, 0b11001, 0b10001, 0b01110],
+        '1' => [0b00100, 0b01100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110],
+        '2' => [0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b01000, 0b11111],
+        '3' => [0b11111, 0b00010, 0b00100, 0b00010, 0b00001, 0b10001, 0b01110],
+        '4' => [0b00010, 0b00110, 0b01010, 0b10010, 0b11111, 0b00010, 0b00010],
+        '5' => [0b11111, 0b10000, 0b11110, 0b00001, 0b00001, 0b10001, 0b01110],
+        '6' => [0b00110, 0b01000, 0b10000, 0b11110, 0b10001, 0b10001, 0b01110],
+        '7' => [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b01000, 0b01000],
+        '8' => [0b01110, 0b10001, 0b10001, 0b01110, 0b10001, 0b10001, 0b01110],
+        '9' => [0b01110, 0b10001, 0b10001, 0b01111, 0b00001, 0b00010, 0b01100],
+        ' ' => [0; GLYPH_HEIGHT],
+        '.' => [0, 0, 0, 0, 0, 0b01100, 0b01100],
+        ',' => [0, 0, 0, 0, 0b01100, 0b00100, 0b01000],
+        '!' => [0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0, 0b00100],
+        '-' => [0, 0, 0, 0b11111, 0, 0, 0],
+        '+' => [0, 0b00100, 0b00100, 0b11111, 0b00100, 0b00100, 0],
+        '=' => [0, 0, 0b11111, 0, 0b11111, 0, 0],
+        '_' => [0, 0, 0, 0, 0, 0, 0b11111],
+        '*' => [0, 0b00100, 0b10101, 0b01110, 0b10101, 0b00100, 0],
+        ':' => [0, 0b01100, 0b01100, 0, 0b01100, 0b01100, 0],
+        ';' => [0, 0b01100, 0b01100, 0, 0b01100, 0b00100, 0b01000],
+        '\'' => [0b00100, 0b00100, 0b01000, 0, 0, 0, 0],
+        '"' => [0b01010, 0b01010, 0, 0, 0, 0, 0],
+        '/' => [0, 0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0],
+        '(' => [0b00010, 0b00100, 0b01000, 0b01000, 0b01000, 0b00100, 0b00010],
+        ')' => [0b01000, 0b00100, 0b00010, 0b00010, 0b00010, 0b00100, 0b01000],
+        '<' => [0b00010, 0b00100, 0b01000, 0b10000, 0b01000, 0b00100, 0b00010],
+        '>' => [0b01000, 0b00100, 0b00010, 0b00001, 0b00010, 0b00100, 0b01000],
+        '#' => [0b01010, 0b01010, 0b11111, 0b01010, 0b11111, 0b01010, 0b01010],
+        '%' => [0b11000, 0b11001, 0b00010, 0b00100, 0b01000, 0b10011, 0b00011],
+        '&' => [0b01100, 0b10010, 0b10100, 0b01000, 0b10101, 0b10010, 0b01101],
+        '@' => [0b01110, 0b10001, 0b00001, 0b01101, 0b10101, 0b10101, 0b01110],
+        _ => [0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0, 0b00100],
+    }
+}
+
+/// Width in font pixels of one line of text
+fn line_width(line: &str) -> usize {
+    let chars = line.chars().count();
+    (chars * (GLYPH_WIDTH + GAP)).saturating_sub(GAP)
+}
+
+/// Size in font pixels of the block `text` renders to
+pub fn text_size(text: &str) -> (usize, usize) {
+    let width = text.lines().map(line_width).max().unwrap_or(0);
+    let lines = text.lines().count();
+    (width, (lines * (GLYPH_HEIGHT + GAP)).saturating_sub(GAP))
+}
+
+/// Whether font pixel (x, y) of the block `text` renders to is ink
+pub fn text_pixel(text: &str, x: usize, y: usize) -> bool {
+    let (block_width, _) = text_size(text);
+    let Some(line) = text.lines().nth(y / (GLYPH_HEIGHT + GAP)) else {
+        return false;
+    };
+    let gy = y % (GLYPH_HEIGHT + GAP);
+    // Lines are centered in the block
+    let Some(x) = x.checked_sub((block_width - line_width(line)) / 2) else {
+        return false;
+    };
+    let gx = x % (GLYPH_WIDTH + GAP);
+    if gx >= GLYPH_WIDTH || gy >= GLYPH_HEIGHT {
+        return false;
+    }
+    match line.chars().nth(x / (GLYPH_WIDTH + GAP)) {
+        Some(c) => glyph(c)[gy] & (1 << (GLYPH_WIDTH - 1 - gx)) != 0,
+        None => false,
+    }
+}
+
+/// Whether point (x, y) is ink of `text` drawn centered on the origin,
+/// `scale` cells per font pixel
+pub fn text_contains(text: &str, scale: f32, x: f32, y: f32) -> bool {
+    let (width, height) = text_size(text);
+    let scale = scale.max(0.1);
+    let fx = x / scale + width as f32 / 2.0;
+    let fy = y / scale + height as f32 / 2.0;
+    fx >= 0.0 && fy >= 0.0 && text_pixel(text, fx as usize, fy as usize)
+}
+
+/// Distance from the origin to the farthest corner of `text` drawn centered on it
+pub fn text_reach(text: &str, scale: f32) -> f32 {
+    let (width, height) = text_size(text);
+    (width as f32).hypot(height as f32) * scale.max(0.1) / 2.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_block_layout() {
+        assert_eq!(text_size("DLA"), (17, 7));
+        assert_eq!(text_size("AB\nC"), (11, 15));
+        assert_eq!(text_size(""), (0, 0));
+
+        // 'T': full top row, then the middle column only
+        assert!((0..5).all(|x| text_pixel("T", x, 0)));
+        assert!(text_pixel("T", 2, 6) && !text_pixel("T", 1, 6));
+        // Gap column between characters, lowercase drawn as uppercase
+        assert!((0..7).all(|y| !text_pixel("tt", 5, y)));
+        assert_eq!(text_pixel("t", 2, 3), text_pixel("T", 2, 3));
+        // The short second line is centered: 'C' starts 3 pixels in
+        assert!(!text_pixel("AB\nC", 1, 9) && text_pixel("AB\nC", 3, 9));
+    }
+}
@@ -0,0 +1,283 @@
+//! Cluster export as a graph for network-analysis tools.
+//!
+//! Nodes are the stuck particles with their data, and edges are the
+//! attachment links of the growth tree, from parent to child. The graph is
+//! written as GraphML, Graphviz DOT or a plain JSON node and edge list.
+//! Off-lattice and 3D runs don't track the tree, so their graphs have nodes only.
+
+use crate::simulation::{DlaSimulation, ParticleData};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+/// File format of a graph export
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GraphFormat {
+    GraphMl,
+    Dot,
+    Json,
+}
+
+impl GraphFormat {
+    /// Graph format named by the file extension: .graphml, .dot or .gv, and
+    /// .graph.json (None for anything else, such as config JSON)
+    pub fn from_filename(filename: &str) -> Option<Self> {
+        let lower = filename.to_lowercase();
+        if lower.ends_with(".graphml") {
+            Some(GraphFormat::GraphMl)
+        } else if lower.ends_with(".dot") || lower.ends_with(".gv") {
+            Some(GraphFormat::Dot)
+        } else if lower.ends_with(".graph.json") {
+            Some(GraphFormat::Json)
+        } else {
+            None
+        }
+    }
+}
+
+/// A particle of the cluster
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GraphNode {
+    pub id: usize,
+    /// Center of the particle in grid units
+    pub x: f32,
+    pub y: f32,
+    /// Depth of the voxel (3D runs only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub z: Option<f32>,
+    pub age: usize,
+    pub distance: f32,
+    pub direction: f32,
+    pub neighbor_count: u8,
+    pub species: u8,
+    pub generation: u32,
+    pub path_length: f32,
+    pub strahler: u8,
+}
+
+/// Attachment link from a parent to the particle that stuck to it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GraphEdge {
+    pub source: usize,
+    pub target: usize,
+    /// Distance between the two particle centers
+    pub length: f32,
+}
+
+/// Cluster as nodes and attachment edges
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ClusterGraph {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+/// Node attributes written to GraphML and DOT, as (name, GraphML type)
+const NODE_KEYS: [(&str, &str); 11] = [
+    ("x", "float"),
+    ("y", "float"),
+    ("z", "float"),
+    ("age", "long"),
+    ("distance", "float"),
+    ("direction", "float"),
+    ("neighbor_count", "int"),
+    ("species", "int"),
+    ("generation", "long"),
+    ("path_length", "float"),
+    ("strahler", "int"),
+];
+
+/// Attributes of a node as (name, value), in `NODE_KEYS` order; 2D
+/// particles have no z
+fn node_values(node: &GraphNode) -> Vec<(&'static str, String)> {
+    let values = [
+        Some(node.x.to_string()),
+        Some(node.y.to_string()),
+        node.z.map(|z| z.to_string()),
+        Some(node.age.to_string()),
+        Some(node.distance.to_string()),
+        Some(node.direction.to_string()),
+        Some(node.neighbor_count.to_string()),
+        Some(node.species.to_string()),
+        Some(node.generation.to_string()),
+        Some(node.path_length.to_string()),
+        Some(node.strahler.to_string()),
+    ];
+    NODE_KEYS
+        .iter()
+        .zip(values)
+        .filter_map(|(&(name, _), value)| value.map(|v| (name, v)))
+        .collect()
+}
+
+impl ClusterGraph {
+    /// Graph of the current cluster (voxels in 3D runs, discs off-lattice)
+    pub fn from_simulation(sim: &DlaSimulation) -> Self {
+        let node = |id, (x, y, z): (f32, f32, Option<f32>), p: ParticleData| GraphNode {
+            id,
+            x,
+            y,
+            z,
+            age: p.age,
+            distance: p.distance,
+            direction: p.direction,
+            neighbor_count: p.neighbor_count,
+            species: p.species,
+            generation: p.generation,
+            path_length: p.path_length,
+            strahler: p.strahler,
+        };
+
+        if sim.settings.voxels {
+            let nodes = sim
+                .voxels()
+                .iter()
+                .enumerate()
+                .map(|(id, v)| node(id, (v.x as f32 + 0.5, v.y as f32 + 0.5, Some(v.z as f32 + 0.5)), v.data))
+                .collect();
+            return Self { nodes, edges: Vec::new() };
+        }
+        if sim.settings.off_lattice {
+            let nodes = sim
+                .discs()
+                .iter()
+                .enumerate()
+                .map(|(id, d)| node(id, (d.x, d.y, None), d.data))
+                .collect();
+            return Self { nodes, edges: Vec::new() };
+        }
+
+        // Number particles in sticking order
+        let mut particles: Vec<_> = sim.particles().collect();
+        particles.sort_by_key(|&(x, y, p)| (p.age, y, x));
+        let ids: HashMap<(i32, i32), usize> =
+            particles.iter().enumerate().map(|(id, &(x, y, _))| ((x, y), id)).collect();
+
+        let lattice = sim.settings.active_lattice();
+        let mut graph = Self::default();
+        for (id, &(x, y, p)) in particles.iter().enumerate() {
+            let (cx, cy) = lattice.cell_center(x, y);
+            graph.nodes.push(node(id, (cx, cy, None), p));
+            if let Some(&parent) = p.parent.as_ref().and_then(|cell| ids.get(cell)) {
+                let (px, py) = lattice.cell_center(particles[parent].0, particles[parent].1);
+                graph.edges.push(GraphEdge {
+                    source: parent,
+                    target: id,
+                    length: (cx - px).hypot(cy - py),
+                });
+            }
+        }
+        graph
+    }
+
+    /// GraphML document (directed, with every node attribute as a data key)
+    pub fn to_graphml(&self) -> String {
+        let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        out.push_str("<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n");
+        let has_z = self.nodes.iter().any(|node| node.z.is_some());
+        for (name, kind) in NODE_KEYS {
+            if name == "z" && !has_z {
+                continue;
+            }
+            let _ = writeln!(out, "  <key id=\"{0}\" for=\"node\" attr.name=\"{0}\" attr.type=\"{1}\"/>", name, kind);
+        }
+        out.push_str("  <key id=\"length\" for=\"edge\" attr.name=\"length\" attr.type=\"float\"/>\n");
+        out.push_str("  <graph id=\"cluster\" edgedefault=\"directed\">\n");
+        for node in &self.nodes {
+            let _ = write!(out, "    <node id=\"n{}\">", node.id);
+            for (name, value) in node_values(node) {
+                let _ = write!(out, "<data key=\"{}\">{}</data>", name, value);
+            }
+            out.push_str("</node>\n");
+        }
+        for edge in &self.edges {
+            let _ = writeln!(
+                out,
+                "    <edge source=\"n{}\" target=\"n{}\"><data key=\"length\">{}</data></edge>",
+                edge.source, edge.target, edge.length
+            );
+        }
+        out.push_str("  </graph>\n</graphml>\n");
+        out
+    }
+
+    /// Graphviz DOT digraph, with pinned positions for neato and fdp
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph cluster {\n  node [shape=point];\n");
+        for node in &self.nodes {
+            let _ = write!(out, "  n{} [", node.id);
+            for (name, value) in node_values(node) {
+                let _ = write!(out, "{}={}, ", name, value);
+            }
+            // DOT's y axis points up
+            let _ = writeln!(out, "pos=\"{},{}!\"];", node.x, -node.y);
+        }
+        for edge in &self.edges {
+            let _ = writeln!(out, "  n{} -> n{} [length={}];", edge.source, edge.target, edge.length);
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    /// JSON object with a node list and an edge list
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string(self).map_err(|e| format!("Failed to serialize graph: {}", e))
+    }
+
+    /// Write the graph to a file in the given format
+    pub fn save_to_file(&self, path: &Path, format: GraphFormat) -> Result<(), String> {
+        let content = match format {
+            GraphFormat::GraphMl => self.to_graphml(),
+            GraphFormat::Dot => self.to_dot(),
+            GraphFormat::Json => self.to_json()?,
+        };
+        fs::write(path, content).map_err(|e| format!("Failed to write graph file: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::SeedPattern;
+
+    #[test]
+    fn test_graph_links_every_particle_to_its_parent() {
+        let mut sim = DlaSimulation::new(64, 64);
+        sim.num_particles = 150;
+        sim.set_rng_seed(Some(9));
+        sim.reset_with_seed(SeedPattern::Line);
+        let seeds = sim.particles_stuck;
+        while !sim.is_complete() {
+            sim.step();
+        }
+
+        let graph = ClusterGraph::from_simulation(&sim);
+        assert_eq!(graph.nodes.len(), 150);
+        // A forest: one root per seed cell, every other particle has one parent
+        assert_eq!(graph.edges.len(), 150 - seeds);
+        for edge in &graph.edges {
+            let (parent, child) = (&graph.nodes[edge.source], &graph.nodes[edge.target]);
+            assert_eq!(child.generation, parent.generation + 1);
+            assert!(edge.length >= 1.0 && edge.length < 1.5);
+        }
+
+        let graphml = graph.to_graphml();
+        assert_eq!(graphml.matches("<node ").count(), 150);
+        assert_eq!(graphml.matches("<edge ").count(), graph.edges.len());
+        assert!(!graphml.contains("\"z\""));
+        let dot = graph.to_dot();
+        assert_eq!(dot.matches(" -> ").count(), graph.edges.len());
+        assert!(!dot.contains(" z="));
+        let parsed: ClusterGraph = serde_json::from_str(&graph.to_json().unwrap()).unwrap();
+        assert_eq!(parsed, graph);
+    }
+
+    #[test]
+    fn test_format_follows_the_extension() {
+        assert_eq!(GraphFormat::from_filename("tree.GraphML"), Some(GraphFormat::GraphMl));
+        assert_eq!(GraphFormat::from_filename("tree.gv"), Some(GraphFormat::Dot));
+        assert_eq!(GraphFormat::from_filename("tree.graph.json"), Some(GraphFormat::Json));
+        assert_eq!(GraphFormat::from_filename("dla-config.json"), None);
+    }
+}
@@ -0,0 +1,279 @@
+//! Harmonic measure: where random walkers reach a frozen cluster.
+//!
+//! Probe walkers are fired at the cluster with the walk and spawn rules of a
+//! normal step, but never stick. Each one is tallied at the empty perimeter
+//! cell it would have stuck at, which estimates the growth probability of
+//! every perimeter cell (the harmonic measure when stickiness is 1).
+//!
+//! The multifractal spectrum of the measure comes from box counting with the
+//! direct method of Chhabra and Jensen: over box sizes e, the box
+//! probabilities P give tau(q) from sum P^q ~ e^tau, the generalized dimensions
+//! D_q = tau(q) / (q - 1), and alpha(q), f(alpha) from the q-weighted averages
+//! of ln P and ln (P^q / sum P^q). Negative q would weight the deepest fjord
+//! cells, whose probability is far below one hit in any feasible number of
+//! probes, so the default moments only cover q >= 0 (the left, alpha < D_0
+//! branch of f(alpha)).
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// Probe walkers fired by default
+pub const DEFAULT_PROBES: usize = 10000;
+
+/// Moment orders q of the spectrum, 0 to 8 in steps of 0.5
+pub fn default_moments() -> Vec<f32> {
+    (0..=16).map(|i| i as f32 * 0.5).collect()
+}
+
+/// Hit count of one perimeter cell
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SiteHits {
+    pub x: i32,
+    pub y: i32,
+    pub hits: u32,
+}
+
+/// One point of the multifractal spectrum
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SpectrumPoint {
+    pub q: f32,
+    pub tau: f32,
+    /// Generalized dimension D_q
+    pub dimension: f32,
+    pub alpha: f32,
+    pub f_alpha: f32,
+}
+
+/// Generalized dimensions and f(alpha) of a measure
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MultifractalSpectrum {
+    /// Box sizes the scaling was fitted over, in cells
+    pub box_sizes: Vec<u32>,
+    pub points: Vec<SpectrumPoint>,
+}
+
+impl MultifractalSpectrum {
+    /// D_q for one of the moment orders of the spectrum
+    pub fn dimension(&self, q: f32) -> Option<f32> {
+        self.points.iter().find(|p| p.q == q).map(|p| p.dimension)
+    }
+
+    /// Range of alpha over the spectrum, as (min, max)
+    pub fn alpha_range(&self) -> Option<(f32, f32)> {
+        let alphas = self.points.iter().map(|p| p.alpha);
+        let min = alphas.clone().reduce(f32::min)?;
+        let max = alphas.reduce(f32::max)?;
+        Some((min, max))
+    }
+}
+
+/// Probe hits on the perimeter of a frozen cluster
+#[derive(Debug, Clone, Default)]
+pub struct HarmonicMeasure {
+    hits: HashMap<(i32, i32), u32>,
+    /// Probe walkers fired
+    pub probes: usize,
+    /// Probes that escaped, were absorbed or ran out of iterations
+    pub lost: usize,
+}
+
+/// File contents of a harmonic measure export
+#[derive(Serialize)]
+struct HarmonicReport<'a> {
+    probes: usize,
+    lost: usize,
+    sites: Vec<SiteHits>,
+    spectrum: &'a MultifractalSpectrum,
+}
+
+impl HarmonicMeasure {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Tally a probe that reached the perimeter cell (x, y)
+    pub fn record_hit(&mut self, x: i32, y: i32) {
+        *self.hits.entry((x, y)).or_default() += 1;
+        self.probes += 1;
+    }
+
+    /// Tally a probe that never reached the cluster
+    pub fn record_lost(&mut self) {
+        self.probes += 1;
+        self.lost += 1;
+    }
+
+    pub fn hits_at(&self, x: i32, y: i32) -> u32 {
+        self.hits.get(&(x, y)).copied().unwrap_or(0)
+    }
+
+    /// Probes that reached the cluster
+    pub fn total_hits(&self) -> usize {
+        self.probes - self.lost
+    }
+
+    /// Most hits on any one cell
+    pub fn max_hits(&self) -> u32 {
+        self.hits.values().copied().max().unwrap_or(0)
+    }
+
+    /// Number of perimeter cells hit at least once
+    pub fn site_count(&self) -> usize {
+        self.hits.len()
+    }
+
+    /// Hit cells in row-major order
+    pub fn sites(&self) -> Vec<SiteHits> {
+        let mut sites: Vec<_> = self.hits.iter().map(|(&(x, y), &hits)| SiteHits { x, y, hits }).collect();
+        sites.sort_by_key(|s| (s.y, s.x));
+        sites
+    }
+
+    /// Box probabilities at box size `size`
+    fn box_probabilities(&self, size: i32) -> Vec<f64> {
+        let mut boxes: HashMap<(i32, i32), u32> = HashMap::new();
+        for (&(x, y), &hits) in &self.hits {
+            *boxes.entry((x.div_euclid(size), y.div_euclid(size))).or_default() += hits;
+        }
+        let total = self.total_hits() as f64;
+        boxes.into_values().map(|hits| hits as f64 / total).collect()
+    }
+
+    /// Multifractal spectrum at the moment orders `moments`, fitted over box
+    /// sizes 1, 2, 4, ... up to a quarter of the extent of the hit cells
+    /// (empty when the hits span fewer than three box sizes)
+    pub fn spectrum(&self, moments: &[f32]) -> MultifractalSpectrum {
+        let Some(extent) = self.extent() else {
+            return MultifractalSpectrum::default();
+        };
+        let box_sizes: Vec<u32> = (0..).map(|i| 1 << i).take_while(|&s| s * 4 <= extent).collect();
+        if box_sizes.len() < 3 {
+            return MultifractalSpectrum::default();
+        }
+
+        let log_sizes: Vec<f64> = box_sizes.iter().map(|&s| (s as f64).ln()).collect();
+        let boxes: Vec<Vec<f64>> = box_sizes.iter().map(|&s| self.box_probabilities(s as i32)).collect();
+        let points = moments
+            .iter()
+            .map(|&q| {
+                let (mut log_z, mut mean_log_p, mut mean_log_mu) = (Vec::new(), Vec::new(), Vec::new());
+                for probabilities in &boxes {
+                    let weights: Vec<f64> = probabilities.iter().map(|p| p.powf(q as f64)).collect();
+                    let z: f64 = weights.iter().sum();
+                    let (mut a, mut f) = (0.0, 0.0);
+                    for (p, w) in probabilities.iter().zip(&weights) {
+                        let mu = w / z;
+                        a += mu * p.ln();
+                        f += mu * mu.ln();
+                    }
+                    log_z.push(z.ln());
+                    mean_log_p.push(a);
+                    mean_log_mu.push(f);
+                }
+                let tau = slope(&log_sizes, &log_z);
+                let alpha = slope(&log_sizes, &mean_log_p);
+                let f_alpha = slope(&log_sizes, &mean_log_mu);
+                // At q = 1 tau vanishes and D_1 is the information dimension, alpha(1)
+                let dimension = if (q - 1.0).abs() < 1e-6 { alpha } else { tau / (q as f64 - 1.0) };
+                SpectrumPoint {
+                    q,
+                    tau: tau as f32,
+                    dimension: dimension as f32,
+                    alpha: alpha as f32,
+                    f_alpha: f_alpha as f32,
+                }
+            })
+            .collect();
+
+        MultifractalSpectrum { box_sizes, points }
+    }
+
+    /// Side of the bounding box of the hit cells
+    fn extent(&self) -> Option<u32> {
+        let mut cells = self.hits.keys();
+        let &(x, y) = cells.next()?;
+        let (x0, y0, x1, y1) = cells.fold((x, y, x, y), |(x0, y0, x1, y1), &(x, y)| {
+            (x0.min(x), y0.min(y), x1.max(x), y1.max(y))
+        });
+        Some((x1 - x0).max(y1 - y0) as u32 + 1)
+    }
+
+    /// Write the hit counts and the spectrum at the default moments as JSON
+    pub fn save_to_file(&self, path: &Path) -> Result<(), String> {
+        let spectrum = self.spectrum(&default_moments());
+        let report = HarmonicReport {
+            probes: self.probes,
+            lost: self.lost,
+            sites: self.sites(),
+            spectrum: &spectrum,
+        };
+        let json = serde_json::to_string_pretty(&report).map_err(|e| format!("Failed to serialize harmonic measure: {}", e))?;
+        fs::write(path, json).map_err(|e| format!("Failed to write harmonic measure file: {}", e))
+    }
+}
+
+/// Least-squares slope of ys against xs
+fn slope(xs: &[f64], ys: &[f64]) -> f64 {
+    let n = xs.len() as f64;
+    let (mean_x, mean_y) = (xs.iter().sum::<f64>() / n, ys.iter().sum::<f64>() / n);
+    let cov: f64 = xs.iter().zip(ys).map(|(x, y)| (x - mean_x) * (y - mean_y)).sum();
+    let var: f64 = xs.iter().map(|x| (x - mean_x).powi(2)).sum();
+    if var > 0.0 {
+        cov / var
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uniform_measure_on_a_line_has_dimension_one() {
+        let mut measure = HarmonicMeasure::new();
+        for x in 0..256 {
+            for _ in 0..4 {
+                measure.record_hit(x, 10);
+            }
+        }
+        measure.record_lost();
+        assert_eq!((measure.probes, measure.total_hits(), measure.site_count()), (1025, 1024, 256));
+
+        let spectrum = measure.spectrum(&default_moments());
+        assert_eq!(spectrum.box_sizes, vec![1, 2, 4, 8, 16, 32, 64]);
+        for point in &spectrum.points {
+            assert!((point.dimension - 1.0).abs() < 1e-4, "D_{} = {}", point.q, point.dimension);
+            assert!((point.alpha - 1.0).abs() < 1e-4 && (point.f_alpha - 1.0).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_binomial_cascade_matches_its_known_spectrum() {
+        // Each halving of the line splits the measure 0.7 / 0.3, which gives
+        // D_q = log2(0.7^q + 0.3^q) / (1 - q)
+        let mut measure = HarmonicMeasure::new();
+        for x in 0..256 {
+            let heavy = (0..8).filter(|bit| x & (1 << bit) == 0).count() as i32;
+            let weight = 0.7f64.powi(heavy) * 0.3f64.powi(8 - heavy);
+            for _ in 0..(weight * 1e6).round() as u32 {
+                measure.record_hit(x, 0);
+            }
+        }
+        let spectrum = measure.spectrum(&default_moments());
+        for q in [0.0f32, 1.0, 2.0, 4.0] {
+            let expected = if q == 1.0 {
+                -(0.7 * 0.7f32.log2() + 0.3 * 0.3f32.log2())
+            } else {
+                (0.7f32.powf(q) + 0.3f32.powf(q)).log2() / (1.0 - q)
+            };
+            let dimension = spectrum.dimension(q).unwrap();
+            assert!((dimension - expected).abs() < 0.01, "D_{} = {}, expected {}", q, dimension, expected);
+        }
+        let (min, max) = spectrum.alpha_range().unwrap();
+        // alpha runs from near -log2(0.7) at large q to its q = 0 value
+        assert!(min < 0.55 && (max - 1.126).abs() < 0.01, "alpha {} - {}", min, max);
+    }
+}
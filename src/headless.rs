@@ -0,0 +1,223 @@
+//! Headless batch mode: run a simulation to completion without a terminal
+//! and write the resulting cluster to disk.
+
+use crate::app::App;
+use dla_sim_tui::config::AppConfig;
+use dla_sim_tui::simulation::ParticleData;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// Budget and output options for a headless run
+#[derive(Debug, Clone)]
+pub struct HeadlessOptions {
+    /// Stop after this many simulation steps (None = unlimited)
+    pub max_steps: Option<u64>,
+    /// Stop after this much wall-clock time (None = unlimited)
+    pub time_limit: Option<Duration>,
+    /// JSON result file
+    pub output: PathBuf,
+}
+
+/// A stuck particle as written to the result file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParticleRecord {
+    /// Cell coordinates (negative once an unbounded grid grows past the origin)
+    pub x: i32,
+    pub y: i32,
+    /// Depth of the voxel (3D mode only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub z: Option<i32>,
+    pub age: usize,
+    pub distance: f32,
+    pub direction: f32,
+    pub neighbor_count: u8,
+}
+
+/// Summary statistics of a finished run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunStats {
+    pub grid_width: usize,
+    pub grid_height: usize,
+    pub particles_stuck: usize,
+    pub num_particles: usize,
+    pub max_radius: f32,
+    pub fractal_dimension: f32,
+    pub r_squared: f32,
+    /// Walker launches performed (lockstep ticks in multi-walker mode)
+    pub steps: u64,
+    pub elapsed_secs: f64,
+    /// True if the particle target was reached (false = budget ran out)
+    pub completed: bool,
+    /// Clusters left (cluster-cluster aggregation runs only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub clusters: Option<usize>,
+}
+
+/// Everything written to disk by a headless run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeadlessReport {
+    pub config: AppConfig,
+    pub stats: RunStats,
+    pub particles: Vec<ParticleRecord>,
+}
+
+impl HeadlessReport {
+    /// One-line summary for stdout
+    pub fn summary(&self) -> String {
+        let s = &self.stats;
+        let summary = format!(
+            "{} {} / {} particles in {} steps ({:.2}s), D_f {:.2} (R²={:.2})",
+            if s.completed { "Completed" } else { "Stopped at" },
+            s.particles_stuck,
+            s.num_particles,
+            s.steps,
+            s.elapsed_secs,
+            s.fractal_dimension,
+            s.r_squared,
+        );
+        match s.clusters {
+            Some(clusters) => format!("{}, {} clusters", summary, clusters),
+            None => summary,
+        }
+    }
+}
+
+/// Step the simulation until complete or a budget runs out, then write the report
+pub fn run(app: &mut App, options: &HeadlessOptions) -> Result<HeadlessReport, String> {
+    let start = Instant::now();
+    let sim = &mut app.simulation;
+    sim.paused = false;
+
+    let mut steps: u64 = 0;
+    while !sim.is_complete() {
+        if options.max_steps.is_some_and(|max| steps >= max) {
+            break;
+        }
+        // Checking the clock is cheap relative to a walk, but no need to do it every step
+        if steps.is_multiple_of(256) && options.time_limit.is_some_and(|limit| start.elapsed() >= limit) {
+            break;
+        }
+        sim.step();
+        steps += 1;
+        if let Some((_, log)) = &mut app.metrics {
+            log.record(sim)?;
+        }
+    }
+    // Bring the 3D view up to date for the PNG export
+    sim.refresh_view();
+
+    let report = build_report(app, steps, start.elapsed());
+    let json = serde_json::to_string(&report)
+        .map_err(|e| format!("Failed to serialize result: {}", e))?;
+    fs::write(&options.output, json)
+        .map_err(|e| format!("Failed to write result file: {}", e))?;
+
+    Ok(report)
+}
+
+/// Collect stats and particles from the current simulation state
+fn build_report(app: &App, steps: u64, elapsed: Duration) -> HeadlessReport {
+    let sim = &app.simulation;
+    let (fractal_dimension, r_squared) = sim.calculate_fractal_dimension();
+
+    let record = |x, y, z, p: ParticleData| ParticleRecord {
+        x,
+        y,
+        z,
+        age: p.age,
+        distance: p.distance,
+        direction: p.direction,
+        neighbor_count: p.neighbor_count,
+    };
+    // In 3D mode the grid only holds the current view, so report the voxels
+    let particles = if sim.settings.voxels {
+        sim.voxels().iter().map(|v| record(v.x, v.y, Some(v.z), v.data)).collect()
+    } else {
+        sim.particles().map(|(x, y, p)| record(x, y, None, p)).collect()
+    };
+
+    HeadlessReport {
+        config: app.to_config(),
+        stats: RunStats {
+            grid_width: sim.grid_width,
+            grid_height: sim.grid_height,
+            particles_stuck: sim.particles_stuck,
+            num_particles: sim.num_particles,
+            max_radius: sim.max_radius,
+            fractal_dimension,
+            r_squared,
+            steps,
+            elapsed_secs: elapsed.as_secs_f64(),
+            completed: sim.is_complete(),
+            clusters: (!sim.aggregation().is_empty()).then(|| sim.aggregation().cluster_count()),
+        },
+        particles,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_headless_run_completes_and_writes_report() {
+        let mut app = App::with_simulation_size(80, 80);
+        app.simulation.num_particles = 200;
+        app.simulation.set_rng_seed(Some(5));
+        app.simulation.reset();
+
+        let temp_file = NamedTempFile::new().unwrap();
+        let options = HeadlessOptions {
+            max_steps: None,
+            time_limit: None,
+            output: temp_file.path().to_path_buf(),
+        };
+        let report = run(&mut app, &options).unwrap();
+        assert!(report.stats.completed);
+        assert_eq!(report.particles.len(), report.stats.particles_stuck);
+
+        let written: HeadlessReport =
+            serde_json::from_str(&fs::read_to_string(temp_file.path()).unwrap()).unwrap();
+        assert_eq!(written.stats.particles_stuck, report.stats.particles_stuck);
+        assert_eq!(written.config.rng_seed, Some(5));
+    }
+
+    #[test]
+    fn test_headless_voxel_run_reports_depth() {
+        let mut app = App::with_simulation_size(64, 64);
+        app.simulation.settings.voxels = true;
+        app.simulation.num_particles = 300;
+        app.simulation.set_rng_seed(Some(9));
+        app.simulation.reset();
+
+        let temp_file = NamedTempFile::new().unwrap();
+        let options = HeadlessOptions {
+            max_steps: None,
+            time_limit: None,
+            output: temp_file.path().to_path_buf(),
+        };
+        let report = run(&mut app, &options).unwrap();
+        assert!(report.stats.completed);
+        assert_eq!(report.particles.len(), report.stats.particles_stuck);
+        assert!(report.particles.iter().all(|p| p.z.is_some()));
+    }
+
+    #[test]
+    fn test_headless_step_budget() {
+        let mut app = App::with_simulation_size(80, 80);
+        app.simulation.num_particles = 5000;
+
+        let temp_file = NamedTempFile::new().unwrap();
+        let options = HeadlessOptions {
+            max_steps: Some(10),
+            time_limit: None,
+            output: temp_file.path().to_path_buf(),
+        };
+        let report = run(&mut app, &options).unwrap();
+        assert_eq!(report.stats.steps, 10);
+        assert!(!report.stats.completed);
+    }
+}
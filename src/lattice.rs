@@ -0,0 +1,194 @@
+//! Lattice geometry: how continuous walker positions map to grid cells.
+//!
+//! The sparse grid always stores cells by integer (col, row). On the square
+//! lattice a cell is the unit square at that index. Hexagonal cells use an
+//! "odd-r" offset layout (odd rows shifted right by half a cell) with a row
+//! pitch of 1, and triangular cells alternate pointing up and down along
+//! rows of height √3. Both are scaled so that there is at most one cell per
+//! unit of area, which keeps every cell index inside the walk domain.
+
+use crate::settings::NeighborhoodType;
+use serde::{Deserialize, Serialize};
+
+/// Horizontal pitch of hexagonal cells (distance between neighbor centers)
+const HEX_PITCH: f32 = 2.0 / 1.732_050_8;
+/// Row height of triangular cells (triangles have side 2)
+const TRI_HEIGHT: f32 = 1.732_050_8;
+
+/// Neighbors of a cell in an even hexagonal row
+const HEX_EVEN: [(i32, i32); 6] = [(1, 0), (-1, 0), (-1, -1), (0, -1), (-1, 1), (0, 1)];
+/// Neighbors of a cell in an odd hexagonal row (shifted right by half a cell)
+const HEX_ODD: [(i32, i32); 6] = [(1, 0), (-1, 0), (0, -1), (1, -1), (0, 1), (1, 1)];
+/// Edge neighbors of an upward triangle (base at the bottom)
+const TRI_UP: [(i32, i32); 3] = [(-1, 0), (1, 0), (0, 1)];
+/// Edge neighbors of a downward triangle (base at the top)
+const TRI_DOWN: [(i32, i32); 3] = [(-1, 0), (1, 0), (0, -1)];
+
+/// Cell shape of the simulation lattice
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum LatticeType {
+    /// Unit square cells (neighbors follow the neighborhood setting)
+    #[default]
+    Square,
+    /// Hexagonal cells with 6 neighbors - six-fold, snowflake-like growth
+    Hexagonal,
+    /// Triangular cells with 3 edge neighbors - three-fold growth
+    Triangular,
+}
+
+impl LatticeType {
+    pub fn name(&self) -> &str {
+        match self {
+            LatticeType::Square => "Square",
+            LatticeType::Hexagonal => "Hex",
+            LatticeType::Triangular => "Tri",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            LatticeType::Square => LatticeType::Hexagonal,
+            LatticeType::Hexagonal => LatticeType::Triangular,
+            LatticeType::Triangular => LatticeType::Square,
+        }
+    }
+
+    pub fn prev(&self) -> Self {
+        match self {
+            LatticeType::Square => LatticeType::Triangular,
+            LatticeType::Hexagonal => LatticeType::Square,
+            LatticeType::Triangular => LatticeType::Hexagonal,
+        }
+    }
+
+    /// Cell containing the point (x, y)
+    #[inline]
+    pub fn cell_at(&self, x: f32, y: f32) -> (i32, i32) {
+        match self {
+            LatticeType::Square => (x.floor() as i32, y.floor() as i32),
+            LatticeType::Hexagonal => {
+                // Axial coordinates, then cube rounding to the nearest center
+                let r = y - 0.5;
+                let q = x / HEX_PITCH - 0.5 - r / 2.0;
+                let s = -q - r;
+                let (mut rq, mut rr, rs) = (q.round(), r.round(), s.round());
+                let (dq, dr, ds) = ((rq - q).abs(), (rr - r).abs(), (rs - s).abs());
+                if dq > dr && dq > ds {
+                    rq = -rr - rs;
+                } else if dr > ds {
+                    rr = -rq - rs;
+                }
+                let row = rr as i32;
+                (rq as i32 + row.div_euclid(2), row)
+            }
+            LatticeType::Triangular => {
+                let fy = y / TRI_HEIGHT;
+                let row = fy.floor() as i32;
+                let fy = fy - row as f32;
+                // Columns are centered on integers of u; the diagonal edge between
+                // col and col + 1 runs one way for upward triangles, the other for downward
+                let u = x - 0.5;
+                let col = u.floor() as i32;
+                let f = u - col as f32;
+                let up = (col + row).rem_euclid(2) == 0;
+                let edge = if up { fy } else { 1.0 - fy };
+                (if f < edge { col } else { col + 1 }, row)
+            }
+        }
+    }
+
+    /// Center (centroid) of cell (col, row)
+    pub fn cell_center(&self, col: i32, row: i32) -> (f32, f32) {
+        match self {
+            LatticeType::Square => (col as f32 + 0.5, row as f32 + 0.5),
+            LatticeType::Hexagonal => {
+                let shift = row.rem_euclid(2) as f32 * 0.5;
+                ((col as f32 + shift + 0.5) * HEX_PITCH, row as f32 + 0.5)
+            }
+            LatticeType::Triangular => {
+                let up = (col + row).rem_euclid(2) == 0;
+                let fy = if up { 2.0 / 3.0 } else { 1.0 / 3.0 };
+                (col as f32 + 0.5, (row as f32 + fy) * TRI_HEIGHT)
+            }
+        }
+    }
+
+    /// Neighbor offsets of cell (col, row); `square` picks the square-lattice neighborhood
+    #[inline]
+    pub fn neighbor_offsets(&self, col: i32, row: i32, square: NeighborhoodType) -> &'static [(i32, i32)] {
+        match self {
+            LatticeType::Square => square.offsets(),
+            LatticeType::Hexagonal if row.rem_euclid(2) == 0 => &HEX_EVEN,
+            LatticeType::Hexagonal => &HEX_ODD,
+            LatticeType::Triangular if (col + row).rem_euclid(2) == 0 => &TRI_UP,
+            LatticeType::Triangular => &TRI_DOWN,
+        }
+    }
+
+    /// Number of neighbors of every cell
+    pub fn neighbor_count(&self, square: NeighborhoodType) -> usize {
+        self.neighbor_offsets(0, 0, square).len()
+    }
+
+    /// Distance between the centers of neighboring cells (1 for square cells)
+    pub fn spacing(&self) -> f32 {
+        match self {
+            LatticeType::Square => 1.0,
+            // Hex pitch; for triangles twice the inradius of a side-2 triangle
+            LatticeType::Hexagonal | LatticeType::Triangular => HEX_PITCH,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LATTICES: [LatticeType; 3] = [LatticeType::Square, LatticeType::Hexagonal, LatticeType::Triangular];
+
+    #[test]
+    fn test_cell_centers_map_back_to_their_cell() {
+        for lattice in LATTICES {
+            for row in -7..7 {
+                for col in -7..7 {
+                    let (x, y) = lattice.cell_center(col, row);
+                    assert_eq!(lattice.cell_at(x, y), (col, row), "{:?}", lattice);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_neighbors_are_mutual_and_equidistant() {
+        for lattice in [LatticeType::Hexagonal, LatticeType::Triangular] {
+            for row in -4..4 {
+                for col in -4..4 {
+                    let (x, y) = lattice.cell_center(col, row);
+                    for &(dx, dy) in lattice.neighbor_offsets(col, row, NeighborhoodType::Moore) {
+                        let (ncol, nrow) = (col + dx, row + dy);
+                        let back = lattice.neighbor_offsets(ncol, nrow, NeighborhoodType::Moore);
+                        assert!(back.contains(&(-dx, -dy)), "{:?} ({}, {})", lattice, col, row);
+                        let (nx, ny) = lattice.cell_center(ncol, nrow);
+                        let dist = ((nx - x).powi(2) + (ny - y).powi(2)).sqrt();
+                        assert!((dist - lattice.spacing()).abs() < 1e-4, "{:?}: {}", lattice, dist);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_hex_points_map_to_the_nearest_center() {
+        let lattice = LatticeType::Hexagonal;
+        for i in 0..400 {
+            let (x, y) = (i as f32 * 0.173 % 9.0, i as f32 * 0.311 % 7.0);
+            let (col, row) = lattice.cell_at(x, y);
+            let (cx, cy) = lattice.cell_center(col, row);
+            let own = (cx - x).powi(2) + (cy - y).powi(2);
+            for &(dx, dy) in lattice.neighbor_offsets(col, row, NeighborhoodType::Moore) {
+                let (nx, ny) = lattice.cell_center(col + dx, row + dy);
+                assert!(own <= (nx - x).powi(2) + (ny - y).powi(2) + 1e-4, "({}, {})", x, y);
+            }
+        }
+    }
+}
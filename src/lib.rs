@@ -0,0 +1,53 @@
+//! Diffusion-Limited Aggregation engine.
+//!
+//! The simulation, its settings, config/preset files, color schemes, video/PNG
+//! recording and snapshots live here so other tools can embed the engine. The
+//! `dla-sim-tui` binary is one consumer of this library.
+//!
+//! ```
+//! use dla_sim_tui::{DlaSimulation, SeedPattern};
+//!
+//! let mut sim = DlaSimulation::new(96, 96);
+//! sim.num_particles = 200;
+//! sim.set_rng_seed(Some(42));
+//! sim.reset_with_seed(SeedPattern::Point);
+//! while !sim.is_complete() {
+//!     sim.step();
+//! }
+//! assert_eq!(sim.particles_stuck, 200);
+//! ```
+
+pub mod analysis;
+pub mod color;
+pub mod composite;
+pub mod config;
+pub mod dbm;
+pub mod dlca;
+pub mod distance_map;
+pub mod eden;
+pub mod font;
+pub mod graph;
+pub mod harmonic;
+pub mod lattice;
+pub mod mask;
+pub mod metrics;
+pub mod offlattice;
+pub mod presets;
+pub mod recorder;
+pub mod settings;
+pub mod simulation;
+pub mod snapshot;
+pub mod sparse_grid;
+pub mod tree;
+pub mod viewport;
+pub mod voxel;
+
+pub use color::ColorScheme;
+pub use config::AppConfig;
+pub use lattice::LatticeType;
+pub use presets::{Preset, PresetManager};
+pub use recorder::Recorder;
+pub use settings::{BoundaryBehavior, ColorMode, GrowthModel, Neighborhood3d, NeighborhoodType, SimulationSettings, SpawnMode, VoxelView};
+pub use simulation::{DlaSimulation, ParticleData, SeedPattern, SimulationState};
+pub use snapshot::Snapshot;
+pub use viewport::Viewport;
@@ -1,25 +1,28 @@
 mod app;
 mod braille;
-mod color;
-mod config;
-mod presets;
-mod recorder;
-mod settings;
-mod simulation;
+mod headless;
 mod ui;
 
-use app::{App, Focus, ViewMode};
-use clap::{CommandFactory, FromArgMatches, Parser};
-use config::AppConfig;
+use app::{App, Focus, SnapshotAction, ViewMode};
+use clap::{ArgMatches, CommandFactory, FromArgMatches, Parser};
 use crossterm::{
     event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
+use dla_sim_tui::config::AppConfig;
+use dla_sim_tui::lattice::LatticeType;
+use dla_sim_tui::settings::{BoundaryBehavior, ColorMode, GrowthModel, Neighborhood3d, NeighborhoodType, Species, SpawnMode, VoxelView, MAX_SPECIES};
+use dla_sim_tui::composite::CompositeSeed;
+use dla_sim_tui::graph::GraphFormat;
+use dla_sim_tui::voxel::PointFormat;
+use dla_sim_tui::harmonic;
+use dla_sim_tui::metrics;
+use dla_sim_tui::simulation::{SeedParam, SeedParams, SeedPattern};
+use headless::HeadlessOptions;
 use ratatui::{backend::CrosstermBackend, Terminal};
-use settings::{BoundaryBehavior, ColorMode, NeighborhoodType, SpawnMode};
-use simulation::SeedPattern;
 use std::io;
+use std::path::PathBuf;
 use std::time::Duration;
 
 #[derive(Parser, Debug)]
@@ -30,6 +33,10 @@ struct Args {
     #[arg(long, value_name = "FILE")]
     config: Option<String>,
 
+    /// Start from a named preset, built-in or user (CLI args override preset values)
+    #[arg(long, value_name = "NAME")]
+    preset: Option<String>,
+
     /// Use classic DLA defaults (unit lattice steps, 4-neighbor, absorb boundary)
     #[arg(long)]
     classic: bool,
@@ -43,10 +50,27 @@ struct Args {
     #[arg(short = 's', long, default_value = "1.0")]
     stickiness: f32,
 
-    /// Initial seed pattern (point, line, cross, circle, ring, block, noise, scatter, multipoint, starburst)
+    /// Initial seed pattern (point, line, cross, circle, ring, block, noise, scatter, multipoint, starburst),
+    /// optionally with parameters: ring:radius=40,thickness=3
     #[arg(long, default_value = "point")]
     seed: String,
 
+    /// Seed from a PNG instead: dark (or opaque) pixels become the seed, fitted to the grid
+    #[arg(long = "seed-image", value_name = "FILE")]
+    seed_image: Option<PathBuf>,
+
+    /// Seed from a JSON seed file of points, lines, arcs, polygons, discs and text
+    #[arg(long = "seed-file", value_name = "FILE")]
+    seed_file: Option<PathBuf>,
+
+    /// Seed from text in the built-in bitmap font ("\n" starts a new line)
+    #[arg(long = "seed-text", value_name = "TEXT")]
+    seed_text: Option<String>,
+
+    /// Grid cells per font pixel of --seed-text (1-20)
+    #[arg(long = "text-scale", default_value = "3")]
+    text_scale: u32,
+
     /// Simulation speed (steps per frame, 1-100)
     #[arg(long, default_value = "5")]
     speed: usize,
@@ -68,11 +92,51 @@ struct Args {
     #[arg(long = "radial-bias", default_value = "0.0")]
     radial_bias: f32,
 
+    /// Circle jumping: walkers far from the cluster jump across empty space
+    #[arg(long = "circle-jump")]
+    circle_jump: bool,
+
+    /// Lattice cell shape (square, hex, tri)
+    #[arg(long, default_value = "square")]
+    lattice: String,
+
+    /// Off-lattice mode: disc particles with continuous positions
+    #[arg(long = "off-lattice")]
+    off_lattice: bool,
+
+    /// Radius of off-lattice discs in grid cells (0.25-4)
+    #[arg(long = "disc-radius", default_value = "0.5")]
+    disc_radius: f32,
+
+    /// 3D mode: grow the cluster from voxels in a cubic volume
+    #[arg(long)]
+    voxels: bool,
+
+    /// Growth model (dla, rla, ballistic, eden, dbm, dlca)
+    #[arg(long, default_value = "dla")]
+    growth: String,
+
+    /// Reaction-limited sticking probability per contact, relative to DLA (0.01-1)
+    #[arg(long = "rla-stickiness", default_value = "0.1")]
+    rla_stickiness: f32,
+
+    /// Dielectric breakdown exponent: 0 compact, 1 DLA-like, >1 lightning (0-5)
+    #[arg(long, default_value = "1.0")]
+    eta: f32,
+
+    /// Cluster-cluster mobility exponent: clusters of mass s move at rate s^-gamma (0-2)
+    #[arg(long, default_value = "0.5")]
+    mobility: f32,
+
     // === Sticking Parameters ===
     /// Neighborhood type for sticking checks (vonneumann, moore, extended)
     #[arg(long, default_value = "moore")]
     neighborhood: String,
 
+    /// 3D neighborhood for sticking checks in voxel mode (6, 18, 26)
+    #[arg(long = "neighborhood-3d", default_value = "6")]
+    neighborhood_3d: String,
+
     /// Minimum neighbors required to stick (1-4)
     #[arg(long = "multi-contact", default_value = "1")]
     multi_contact: u8,
@@ -94,7 +158,7 @@ struct Args {
     #[arg(long = "spawn-mode", default_value = "circle")]
     spawn_mode: String,
 
-    /// Boundary behavior (clamp, wrap, bounce, stick, absorb)
+    /// Boundary behavior (clamp, wrap, bounce, stick, absorb, unbounded)
     #[arg(long, default_value = "clamp")]
     boundary: String,
 
@@ -114,8 +178,26 @@ struct Args {
     #[arg(long = "max-iterations", default_value = "10000")]
     max_iterations: usize,
 
+    /// Concurrent walkers as a fraction of grid area (0-0.1, 0 = one walker at a time)
+    #[arg(long = "walker-density", default_value = "0.0")]
+    walker_density: f32,
+
+    /// PNG of non-sticky walls, stretched over the grid (dark or opaque pixels are walls)
+    #[arg(long, value_name = "FILE")]
+    obstacles: Option<PathBuf>,
+
+    // === Species Parameters ===
+    /// Add a particle species, e.g. "fraction=0.3,spawn=top,step=2,angle=90,force=0.2,radial=0.1"
+    /// (repeat for more species; unset keys use the global values)
+    #[arg(long = "species", value_name = "SPEC")]
+    species: Vec<String>,
+
+    /// Species stickiness matrix, rows separated by ';' (e.g. "1,0.2;0.8,1")
+    #[arg(long = "species-matrix", value_name = "ROWS")]
+    species_matrix: Option<String>,
+
     // === Visual Parameters ===
-    /// Color mode (age, distance, density, direction)
+    /// Color mode (age, distance, density, direction, species, generation, path, strahler)
     #[arg(long = "color-mode", default_value = "age")]
     color_mode: String,
 
@@ -126,6 +208,135 @@ struct Args {
     /// Invert color gradient
     #[arg(long, default_value = "false")]
     invert: bool,
+
+    /// 3D view (slice, projection, rotate)
+    #[arg(long = "voxel-view", default_value = "slice")]
+    voxel_view: String,
+
+    /// Slice depth relative to the middle of the volume (3D slice view)
+    #[arg(long, default_value = "0", allow_hyphen_values = true)]
+    slice: i32,
+
+    // === Reproducibility ===
+    /// Seed for the random number generator (same config + seed = identical cluster)
+    #[arg(long = "rng-seed", value_name = "U64")]
+    rng_seed: Option<u64>,
+
+    // === Headless Batch Mode ===
+    /// Run without the TUI and write the finished cluster to --output
+    #[arg(long)]
+    headless: bool,
+
+    /// Simulation grid width in headless mode
+    #[arg(long, default_value = "400")]
+    width: usize,
+
+    /// Simulation grid height in headless mode
+    #[arg(long, default_value = "400")]
+    height: usize,
+
+    /// Stop after this many walker launches (headless mode)
+    #[arg(long = "max-steps", value_name = "N")]
+    max_steps: Option<u64>,
+
+    /// Stop after this many seconds of wall-clock time (headless mode)
+    #[arg(long = "time-limit", value_name = "SECS", value_parser = parse_time_limit)]
+    time_limit: Option<Duration>,
+
+    /// Result file written in headless mode (JSON)
+    #[arg(short = 'o', long, default_value = "dla-result.json")]
+    output: String,
+
+    // === Image Export ===
+    /// Save the cluster as a PNG when the run finishes (headless) or on quit (TUI)
+    #[arg(long, value_name = "FILE")]
+    png: Option<String>,
+
+    /// Image pixels per simulation cell for PNG export and recording (1-16)
+    #[arg(long = "pixel-scale", default_value = "4")]
+    pixel_scale: u32,
+
+    /// Fixed PNG/recording size as WIDTHxHEIGHT (the grid is scaled to fit)
+    #[arg(long = "frame-size", value_name = "WxH")]
+    frame_size: Option<String>,
+
+    /// Background color for PNG export and recording (hex, e.g. 000000)
+    #[arg(long, default_value = "000000")]
+    background: String,
+
+    // === Snapshots ===
+    /// Resume from a saved simulation snapshot (overrides config and CLI settings)
+    #[arg(long, value_name = "FILE")]
+    resume: Option<String>,
+
+    /// Save a full snapshot when the run finishes (headless) or on quit (TUI)
+    #[arg(long, value_name = "FILE")]
+    snapshot: Option<String>,
+
+    // === Graph Export ===
+    /// Save the cluster as a graph of attachment links when the run finishes (headless)
+    /// or on quit (TUI): .graphml, .dot/.gv, or JSON for any other extension
+    #[arg(long, value_name = "FILE")]
+    graph: Option<String>,
+
+    /// Save the cluster as a point cloud (the voxels of a 3D run, z = 0 otherwise) when
+    /// the run finishes (headless) or on quit (TUI): .ply, or XYZ for any other extension
+    #[arg(long, value_name = "FILE")]
+    points: Option<String>,
+
+    // === Growth Probes ===
+    /// Probe the growth probabilities of the finished cluster (headless) or the
+    /// cluster on quit (TUI) and save the hits and multifractal spectrum as JSON
+    #[arg(long, value_name = "FILE")]
+    harmonic: Option<String>,
+
+    /// Probe walkers fired by --harmonic and the G key
+    #[arg(long, default_value_t = harmonic::DEFAULT_PROBES)]
+    probes: usize,
+
+    // === Morphology Analysis ===
+    /// Analyze the finished cluster (headless) or the cluster on quit (TUI) and
+    /// save every estimator: CSV curves for .csv, JSON otherwise
+    #[arg(long, value_name = "FILE")]
+    analysis: Option<String>,
+
+    /// Radii the radial dimension estimators are fitted over, in cells [default: 2 to half the cluster radius]
+    #[arg(long, value_name = "MIN:MAX")]
+    fit_range: Option<String>,
+
+    /// Particle counts the radius of gyration is fitted over [default: 1% of the particles, at least 20, to all]
+    #[arg(long, value_name = "MIN:MAX")]
+    mass_range: Option<String>,
+
+    /// Confidence level of the dimension intervals (0.5-0.999)
+    #[arg(long, default_value_t = 0.95)]
+    confidence: f32,
+
+    // === Metrics Logging ===
+    /// Log growth metrics during the run: NDJSON for .ndjson/.jsonl/.json, CSV otherwise
+    #[arg(long, value_name = "FILE")]
+    metrics: Option<String>,
+
+    /// Stuck particles between metric samples
+    #[arg(long, default_value_t = metrics::DEFAULT_INTERVAL)]
+    metrics_interval: usize,
+}
+
+/// Parse a fit range as `MIN:MAX` with 0 <= MIN < MAX
+fn parse_range(s: &str) -> Option<(f32, f32)> {
+    let (min, max) = s.split_once(':')?;
+    let (min, max) = (min.trim().parse::<f32>().ok()?, max.trim().parse::<f32>().ok()?);
+    (min >= 0.0 && min < max).then_some((min, max))
+}
+
+/// Graph format for --graph: named by the extension, JSON otherwise
+fn graph_format(path: &str) -> GraphFormat {
+    GraphFormat::from_filename(path).unwrap_or(GraphFormat::Json)
+}
+
+/// Point format for --points: named by the extension, XYZ otherwise
+fn point_format(path: &str) -> PointFormat {
+    PointFormat::from_filename(path).unwrap_or(PointFormat::Xyz)
 }
 
 fn parse_neighborhood(s: &str) -> NeighborhoodType {
@@ -136,6 +347,41 @@ fn parse_neighborhood(s: &str) -> NeighborhoodType {
     }
 }
 
+fn parse_lattice(s: &str) -> LatticeType {
+    match s.to_lowercase().as_str() {
+        "hex" | "hexagonal" => LatticeType::Hexagonal,
+        "tri" | "triangular" | "triangle" => LatticeType::Triangular,
+        _ => LatticeType::Square,
+    }
+}
+
+fn parse_growth_model(s: &str) -> GrowthModel {
+    match s.to_lowercase().as_str() {
+        "rla" | "reaction" => GrowthModel::Rla,
+        "ballistic" | "ba" => GrowthModel::Ballistic,
+        "eden" => GrowthModel::Eden,
+        "dbm" | "dielectric" | "laplacian" => GrowthModel::Dbm,
+        "dlca" | "cluster" | "cca" => GrowthModel::Dlca,
+        _ => GrowthModel::Dla,
+    }
+}
+
+fn parse_neighborhood_3d(s: &str) -> Neighborhood3d {
+    match s.to_lowercase().as_str() {
+        "18" | "edges" | "edge" => Neighborhood3d::Edges,
+        "26" | "corners" | "corner" => Neighborhood3d::Corners,
+        _ => Neighborhood3d::Faces,
+    }
+}
+
+fn parse_voxel_view(s: &str) -> VoxelView {
+    match s.to_lowercase().as_str() {
+        "projection" | "project" | "mip" => VoxelView::Projection,
+        "rotate" | "rotating" => VoxelView::Rotate,
+        _ => VoxelView::Slice,
+    }
+}
+
 fn parse_spawn_mode(s: &str) -> SpawnMode {
     match s.to_lowercase().as_str() {
         "edges" | "edge" => SpawnMode::Edges,
@@ -155,6 +401,7 @@ fn parse_boundary(s: &str) -> BoundaryBehavior {
         "bounce" | "reflect" => BoundaryBehavior::Bounce,
         "stick" => BoundaryBehavior::Stick,
         "absorb" | "respawn" => BoundaryBehavior::Absorb,
+        "unbounded" | "none" => BoundaryBehavior::Unbounded,
         _ => BoundaryBehavior::Clamp,
     }
 }
@@ -164,13 +411,91 @@ fn parse_color_mode(s: &str) -> ColorMode {
         "distance" | "dist" => ColorMode::Distance,
         "density" | "dens" => ColorMode::Density,
         "direction" | "dir" => ColorMode::Direction,
+        "species" => ColorMode::Species,
+        "generation" | "depth" => ColorMode::Generation,
+        "path" | "path-length" => ColorMode::PathLength,
+        "strahler" | "order" => ColorMode::Strahler,
         _ => ColorMode::Age,
     }
 }
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    use clap::parser::ValueSource;
+/// Parse a seed pattern name
+fn parse_seed_pattern(s: &str) -> SeedPattern {
+    match s.trim().to_lowercase().as_str() {
+        "line" => SeedPattern::Line,
+        "cross" => SeedPattern::Cross,
+        "circle" => SeedPattern::Circle,
+        "ring" => SeedPattern::Ring,
+        "block" | "filled" => SeedPattern::Block,
+        "noise" | "noise-patch" => SeedPattern::NoisePatch,
+        "scatter" => SeedPattern::Scatter,
+        "multipoint" | "multi-point" => SeedPattern::MultiPoint,
+        "starburst" | "spokes" | "star" => SeedPattern::Starburst,
+        _ => SeedPattern::Point,
+    }
+}
+
+/// Parse seed parameters of comma-separated key=value pairs
+fn parse_seed_params(s: &str) -> Option<SeedParams> {
+    let mut params = SeedParams::default();
+    for pair in s.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let (key, value) = pair.split_once('=')?;
+        let param = SeedParam::from_key(key.trim().to_lowercase().as_str())?;
+        params.set(param, Some(value.trim().parse::<f32>().ok()?));
+    }
+    Some(params)
+}
+
+/// Parse a species spec of comma-separated key=value pairs
+fn parse_species(s: &str) -> Option<Species> {
+    let mut species = Species::default();
+    for pair in s.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let (key, value) = pair.split_once('=')?;
+        let value = value.trim();
+        match key.trim().to_lowercase().as_str() {
+            "fraction" | "frac" => species.fraction = value.parse::<f32>().ok()?.max(0.0),
+            "spawn" | "spawn-mode" => species.spawn_mode = Some(parse_spawn_mode(value)),
+            "step" | "walk-step" => species.walk_step_size = Some(value.parse::<f32>().ok()?.clamp(0.5, 5.0)),
+            "angle" | "walk-angle" => species.walk_bias_angle = Some(value.parse::<f32>().ok()?.clamp(0.0, 360.0)),
+            "force" | "walk-force" => species.walk_bias_strength = Some(value.parse::<f32>().ok()?.clamp(0.0, 0.5)),
+            "radial" | "radial-bias" => species.radial_bias = Some(value.parse::<f32>().ok()?.clamp(-0.3, 0.3)),
+            _ => return None,
+        }
+    }
+    Some(species)
+}
+
+/// Parse a stickiness matrix: rows separated by ';', entries by ','
+fn parse_species_matrix(s: &str) -> Option<Vec<Vec<f32>>> {
+    s.split(';')
+        .map(|row| {
+            row.split(',')
+                .map(|p| p.trim().parse::<f32>().ok().map(|p| p.clamp(0.0, 1.0)))
+                .collect()
+        })
+        .collect()
+}
 
+fn parse_hex_color(s: &str) -> Option<(u8, u8, u8)> {
+    let hex = s.trim_start_matches('#');
+    if hex.len() != 6 {
+        return None;
+    }
+    let r = u8::from_str_radix(&hex[0..2], 16).ok()?;
+    let g = u8::from_str_radix(&hex[2..4], 16).ok()?;
+    let b = u8::from_str_radix(&hex[4..6], 16).ok()?;
+    Some((r, g, b))
+}
+
+fn parse_frame_size(s: &str) -> Option<(u32, u32)> {
+    let (w, h) = s.to_lowercase().split_once('x').map(|(w, h)| (w.trim().parse().ok(), h.trim().parse().ok()))?;
+    match (w?, h?) {
+        (w @ 2..=8192, h @ 2..=8192) => Some((w, h)),
+        _ => None,
+    }
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Parse args and get matches for value_source checking
     let matches = Args::command().get_matches();
     let args = Args::from_arg_matches(&matches)?;
@@ -189,11 +514,39 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         None
     };
 
-    // Helper to check if a CLI arg was explicitly provided
-    let is_explicit = |name: &str| -> bool {
-        matches.value_source(name) == Some(ValueSource::CommandLine)
-    };
-    let use_default_args = base_config.is_none() && !args.classic;
+    // Headless batch mode: no terminal, explicit grid size
+    if args.headless {
+        let mut app = App::with_simulation_size(args.width.max(16), args.height.max(16));
+        configure_app(&mut app, &args, &matches, base_config.as_ref());
+        if let Some(warning) = resume_snapshot(&mut app, &args, &matches)? {
+            eprintln!("Warning: {}.", warning);
+        }
+        if let Some(path) = &args.metrics {
+            app.start_metrics(path, args.metrics_interval)?;
+        }
+
+        let options = HeadlessOptions {
+            max_steps: args.max_steps,
+            time_limit: args.time_limit,
+            output: args.output.clone().into(),
+        };
+        let report = headless::run(&mut app, &options)?;
+        println!("{}", report.summary());
+        let mut failed = 0;
+        for result in run_exports(&mut app, &args) {
+            match result {
+                Ok(message) => println!("{}", message),
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    failed += 1;
+                }
+            }
+        }
+        if failed > 0 {
+            return Err(format!("{} of the requested exports failed", failed).into());
+        }
+        return Ok(());
+    }
 
     // Setup terminal
     enable_raw_mode()?;
@@ -212,13 +565,158 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     };
     let (canvas_width, canvas_height) = ui::get_canvas_size(frame_rect, ViewMode::Default);
     let mut app = App::new(canvas_width, canvas_height);
+    configure_app(&mut app, &args, &matches, base_config.as_ref());
+    match resume_snapshot(&mut app, &args, &matches) {
+        Ok(Some(warning)) => app.snapshot_result = Some(Ok(format!("Warning: {}", warning))),
+        Ok(None) => {}
+        Err(e) => app.snapshot_result = Some(Err(e)),
+    }
+    if let Some(path) = &args.metrics {
+        if let Err(e) = app.start_metrics(path, args.metrics_interval) {
+            app.metrics_result = Some(Err(e));
+        }
+    }
+
+    // Run the app
+    let res = run_app(&mut terminal, &mut app);
+
+    // Cleanup
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+
+    if let Err(err) = res {
+        eprintln!("Error: {:?}", err);
+    }
+
+    for result in run_exports(&mut app, &args) {
+        match result {
+            Ok(message) => println!("{}", message),
+            Err(e) => eprintln!("Error: {}", e),
+        }
+    }
+
+    Ok(())
+}
+
+/// Parse --time-limit seconds, refusing negative, NaN and out-of-range values
+fn parse_time_limit(s: &str) -> Result<Duration, String> {
+    let secs: f64 = s.parse().map_err(|_| format!("'{}' is not a number of seconds", s))?;
+    Duration::try_from_secs_f64(secs).map_err(|_| format!("'{}' is not a valid time limit", s))
+}
+
+/// Write every output file requested on the command line once a run ends.
+/// Each export is attempted even if an earlier one failed; an Ok holds the
+/// lines to print
+fn run_exports(app: &mut App, args: &Args) -> Vec<Result<String, String>> {
+    let mut results = Vec::new();
+    if let Some(result) = app.finish_metrics() {
+        results.push(result.map(|filename| format!("Saved metrics: {}", filename)));
+    }
+    if let Some(png) = &args.png {
+        results.push(app.export_png(png).map(|filename| format!("Saved: {}", filename)));
+    }
+    if let Some(path) = &args.snapshot {
+        results.push(app.save_snapshot(path).map(|filename| format!("Saved snapshot: {}", filename)));
+    }
+    if let Some(path) = &args.graph {
+        results.push(
+            app.export_graph(path, graph_format(path))
+                .map(|filename| format!("Saved graph: {}", filename)),
+        );
+    }
+    if let Some(path) = &args.points {
+        results.push(
+            app.export_points(path, point_format(path))
+                .map(|filename| format!("Saved points: {}", filename)),
+        );
+    }
+    if let Some(path) = &args.harmonic {
+        if let Err(reason) = app.simulation.check_probes() {
+            // Not an error: batch scripts pass --harmonic to every model
+            results.push(Ok(format!("Skipped harmonic measure: {}", reason)));
+        } else {
+            results.push(app.save_harmonic(path).map(|filename| {
+                let mut lines = Vec::new();
+                if let Some(summary) = app.harmonic_summary() {
+                    lines.push(format!("Growth probes: {}", summary));
+                }
+                lines.push(format!("Saved harmonic measure: {}", filename));
+                lines.join("\n")
+            }));
+        }
+    }
+    if let Some(path) = &args.analysis {
+        results.push(app.save_analysis(path).map(|filename| {
+            let mut lines = Vec::new();
+            if let Some(morphology) = &app.morphology {
+                lines.push(format!("Morphology ({}% confidence):", (morphology.confidence * 100.0).round()));
+                lines.extend(morphology.summary().into_iter().map(|line| format!("  {}", line)));
+            }
+            lines.push(format!("Saved analysis: {}", filename));
+            lines.join("\n")
+        }));
+    }
+    results
+}
+
+/// Load the --resume snapshot if given; an explicit --particles still raises the target.
+/// Ok holds a warning when the snapshot's seed or obstacle image is missing
+fn resume_snapshot(app: &mut App, args: &Args, matches: &ArgMatches) -> Result<Option<String>, String> {
+    use clap::parser::ValueSource;
+
+    let Some(path) = &args.resume else {
+        return Ok(None);
+    };
+    let warning = app.load_snapshot(path)?;
+    if matches.value_source("particles") == Some(ValueSource::CommandLine) {
+        app.simulation.num_particles = args.particles.clamp(100, app.simulation.max_particles());
+    }
+    Ok(warning)
+}
+
+/// Apply config file values and CLI overrides to the app, then reset with the chosen seed
+fn configure_app(app: &mut App, args: &Args, matches: &ArgMatches, base_config: Option<&AppConfig>) {
+    use clap::parser::ValueSource;
+
+    // Helper to check if a CLI arg was explicitly provided
+    let is_explicit = |name: &str| -> bool {
+        matches.value_source(name) == Some(ValueSource::CommandLine)
+    };
+    let mut use_default_args = base_config.is_none() && !args.classic;
 
     // Apply config file settings first (if loaded)
-    if let Some(cfg) = &base_config {
-        app.apply_config(cfg);
+    if let Some(cfg) = base_config {
+        if let Err(e) = app.apply_config(cfg) {
+            eprintln!("Warning: {}.", e);
+        }
+    }
+
+    // Then the named preset, which acts as the base for CLI overrides
+    if let Some(name) = &args.preset {
+        // A preset whose images fail to load still applies
+        if app.preset_manager.find(name).is_some() {
+            use_default_args = false;
+        }
+        if let Err(e) = app.apply_preset_by_name(name) {
+            eprintln!("Warning: {}", e);
+        }
     }
 
     // Apply CLI args - only if explicitly provided (override config) or no config loaded
+    // The mode and boundary decide the particle cap, so they are applied first
+    if is_explicit("voxels") || use_default_args {
+        app.simulation.settings.voxels = args.voxels;
+    }
+    if is_explicit("off_lattice") || use_default_args {
+        app.simulation.settings.off_lattice = args.off_lattice;
+    }
+    if is_explicit("growth") || use_default_args {
+        app.simulation.settings.growth_model = parse_growth_model(&args.growth);
+    }
+    if is_explicit("boundary") || use_default_args {
+        app.simulation.settings.boundary_behavior = parse_boundary(&args.boundary);
+    }
     let max_particles = app.simulation.max_particles();
 
     // Basic params
@@ -248,11 +746,32 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     if is_explicit("radial_bias") || use_default_args {
         app.simulation.settings.radial_bias = args.radial_bias.clamp(-0.3, 0.3);
     }
+    if is_explicit("circle_jump") || use_default_args {
+        app.simulation.settings.circle_jump = args.circle_jump;
+    }
+    if is_explicit("lattice") || use_default_args {
+        app.simulation.settings.lattice = parse_lattice(&args.lattice);
+    }
+    if is_explicit("disc_radius") || use_default_args {
+        app.simulation.settings.disc_radius = args.disc_radius.clamp(0.25, 4.0);
+    }
+    if is_explicit("rla_stickiness") || use_default_args {
+        app.simulation.settings.rla_stickiness = args.rla_stickiness.clamp(0.01, 1.0);
+    }
+    if is_explicit("eta") || use_default_args {
+        app.simulation.settings.dbm_eta = args.eta.clamp(0.0, 5.0);
+    }
+    if is_explicit("mobility") || use_default_args {
+        app.simulation.settings.cluster_mobility = args.mobility.clamp(0.0, 2.0);
+    }
 
     // Sticking settings
     if is_explicit("neighborhood") || use_default_args {
         app.simulation.settings.neighborhood = parse_neighborhood(&args.neighborhood);
     }
+    if is_explicit("neighborhood_3d") || use_default_args {
+        app.simulation.settings.neighborhood_3d = parse_neighborhood_3d(&args.neighborhood_3d);
+    }
     if is_explicit("multi_contact") || use_default_args {
         app.simulation.settings.multi_contact_min = args.multi_contact.clamp(1, 4);
     }
@@ -270,9 +789,6 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     if is_explicit("spawn_mode") || use_default_args {
         app.simulation.settings.spawn_mode = parse_spawn_mode(&args.spawn_mode);
     }
-    if is_explicit("boundary") || use_default_args {
-        app.simulation.settings.boundary_behavior = parse_boundary(&args.boundary);
-    }
     if is_explicit("spawn_offset") || use_default_args {
         app.simulation.settings.spawn_radius_offset = args.spawn_offset.clamp(5.0, 50.0);
     }
@@ -285,6 +801,37 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     if is_explicit("max_iterations") || use_default_args {
         app.simulation.settings.max_walk_iterations = args.max_iterations.clamp(1000, 50000);
     }
+    if is_explicit("walker_density") || use_default_args {
+        app.simulation.settings.walker_density = args.walker_density.clamp(0.0, 0.1);
+    }
+    if let Some(path) = &args.obstacles {
+        app.simulation.settings.obstacle_mask = Some(path.clone());
+        if let Err(e) = app.simulation.load_images() {
+            eprintln!("Warning: {}.", e);
+        }
+    }
+
+    // Species settings
+    if is_explicit("species") {
+        let mut species = Vec::new();
+        for spec in &args.species {
+            match parse_species(spec) {
+                Some(parsed) => species.push(parsed),
+                None => eprintln!("Warning: Invalid species '{}', skipping it.", spec),
+            }
+        }
+        if species.len() > MAX_SPECIES {
+            eprintln!("Warning: At most {} species are supported, ignoring the rest.", MAX_SPECIES);
+            species.truncate(MAX_SPECIES);
+        }
+        app.simulation.settings.species = species;
+    }
+    if let Some(matrix) = &args.species_matrix {
+        match parse_species_matrix(matrix) {
+            Some(rows) => app.simulation.settings.stickiness_matrix = rows,
+            None => eprintln!("Warning: Invalid species matrix '{}', every species sticks to every other.", matrix),
+        }
+    }
 
     // Visual settings
     if is_explicit("color_mode") || use_default_args {
@@ -296,41 +843,86 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     if is_explicit("invert") || use_default_args {
         app.simulation.settings.invert_colors = args.invert;
     }
+    if is_explicit("voxel_view") || use_default_args {
+        app.simulation.settings.voxel_view = parse_voxel_view(&args.voxel_view);
+    }
+    if is_explicit("slice") || use_default_args {
+        app.simulation.settings.slice_offset = 0;
+        app.simulation.adjust_slice_offset(args.slice);
+    }
+
+    // Image output settings (shared by PNG export and recording)
+    app.recorder.config.pixel_scale = args.pixel_scale.clamp(1, 16);
+    if let Some(size) = &args.frame_size {
+        match parse_frame_size(size) {
+            Some(size) => app.recorder.config.frame_size = Some(size),
+            None => eprintln!("Warning: Invalid frame size '{}', using the grid size.", size),
+        }
+    }
+    match parse_hex_color(&args.background) {
+        Some(color) => app.recorder.config.background_color = color,
+        None => eprintln!("Warning: Invalid background color '{}', using black.", args.background),
+    }
+
+    // Growth probes and analysis options aren't part of the config
+    app.probes = args.probes.max(1);
+    app.metrics_interval = args.metrics_interval.max(1);
+    if let Some(range) = &args.fit_range {
+        match parse_range(range) {
+            Some(range) => app.analysis_settings.radius_range = Some(range),
+            None => eprintln!("Warning: Invalid fit range '{}', using the default.", range),
+        }
+    }
+    if let Some(range) = &args.mass_range {
+        match parse_range(range) {
+            Some((min, max)) => app.analysis_settings.mass_range = Some((min as usize, max as usize)),
+            None => eprintln!("Warning: Invalid mass range '{}', using the default.", range),
+        }
+    }
+    app.analysis_settings.confidence = args.confidence.clamp(0.5, 0.999);
+
+    // RNG seed - CLI overrides config
+    if args.rng_seed.is_some() {
+        app.simulation.set_rng_seed(args.rng_seed);
+    }
 
     // Determine seed pattern - CLI overrides config
-    let seed_pattern = if is_explicit("seed") || use_default_args {
-        match args.seed.to_lowercase().as_str() {
-            "line" => SeedPattern::Line,
-            "cross" => SeedPattern::Cross,
-            "circle" => SeedPattern::Circle,
-            "ring" => SeedPattern::Ring,
-            "block" | "filled" => SeedPattern::Block,
-            "noise" | "noise-patch" => SeedPattern::NoisePatch,
-            "scatter" => SeedPattern::Scatter,
-            "multipoint" | "multi-point" => SeedPattern::MultiPoint,
-            "starburst" | "spokes" | "star" => SeedPattern::Starburst,
-            _ => SeedPattern::Point,
+    let seed_image = args.seed_image.as_ref().filter(|path| match app.simulation.load_seed_image(path) {
+        Ok(()) => true,
+        Err(e) => {
+            eprintln!("Warning: {}, using --seed instead.", e);
+            false
+        }
+    });
+    let seed_file = args.seed_file.as_ref().and_then(|path| match CompositeSeed::load_from_file(path) {
+        Ok(seed) => Some(seed),
+        Err(e) => {
+            eprintln!("Warning: {}, using --seed instead.", e);
+            None
+        }
+    });
+    let seed_pattern = if let Some(path) = seed_image {
+        SeedPattern::Image(path.clone())
+    } else if let Some(seed) = seed_file {
+        SeedPattern::Composite(seed)
+    } else if let Some(text) = &args.seed_text {
+        SeedPattern::Text {
+            string: text.replace("\\n", "\n"),
+            scale: args.text_scale.clamp(1, 20),
         }
+    } else if is_explicit("seed") || use_default_args {
+        let (name, params) = args.seed.split_once(':').unwrap_or((&args.seed, ""));
+        app.simulation.seed_params = parse_seed_params(params).unwrap_or_else(|| {
+            eprintln!("Warning: Invalid seed parameters '{}', using the defaults.", params);
+            SeedParams::default()
+        });
+        parse_seed_pattern(name)
     } else {
-        app.simulation.seed_pattern
+        app.simulation.seed_pattern.clone()
     };
 
     // Reset with seed pattern (must come after settings are applied)
     app.simulation.reset_with_seed(seed_pattern);
-
-    // Run the app
-    let res = run_app(&mut terminal, &mut app);
-
-    // Cleanup
-    disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
-    terminal.show_cursor()?;
-
-    if let Err(err) = res {
-        eprintln!("Error: {:?}", err);
-    }
-
-    Ok(())
 }
 
 fn run_app<B: ratatui::backend::Backend>(
@@ -406,6 +998,156 @@ fn run_app<B: ratatui::backend::Backend>(
                         continue;
                     }
 
+                    // === Handle PNG export popup keys (if PNG popup is open) ===
+                    if app.png_popup.is_some() {
+                        match key.code {
+                            KeyCode::Enter => app.confirm_png_export(),
+                            KeyCode::Esc => app.close_png_popup(),
+                            KeyCode::Backspace => {
+                                if let Some(popup) = &mut app.png_popup {
+                                    popup.delete_char();
+                                }
+                            }
+                            KeyCode::Left => {
+                                if let Some(popup) = &mut app.png_popup {
+                                    popup.move_cursor_left();
+                                }
+                            }
+                            KeyCode::Right => {
+                                if let Some(popup) = &mut app.png_popup {
+                                    popup.move_cursor_right();
+                                }
+                            }
+                            KeyCode::Char(c) => {
+                                if let Some(popup) = &mut app.png_popup {
+                                    popup.insert_char(c);
+                                }
+                            }
+                            _ => {}
+                        }
+                        continue;
+                    }
+
+                    // === Handle snapshot popup keys (if snapshot popup is open) ===
+                    if app.snapshot_popup.is_some() {
+                        match key.code {
+                            KeyCode::Enter => app.confirm_snapshot_popup(),
+                            KeyCode::Esc => app.close_snapshot_popup(),
+                            KeyCode::Backspace => {
+                                if let Some((_, popup)) = &mut app.snapshot_popup {
+                                    popup.delete_char();
+                                }
+                            }
+                            KeyCode::Left => {
+                                if let Some((_, popup)) = &mut app.snapshot_popup {
+                                    popup.move_cursor_left();
+                                }
+                            }
+                            KeyCode::Right => {
+                                if let Some((_, popup)) = &mut app.snapshot_popup {
+                                    popup.move_cursor_right();
+                                }
+                            }
+                            KeyCode::Char(c) => {
+                                if let Some((_, popup)) = &mut app.snapshot_popup {
+                                    popup.insert_char(c);
+                                }
+                            }
+                            _ => {}
+                        }
+                        continue;
+                    }
+
+                    // === Handle metrics popup keys (if metrics popup is open) ===
+                    if app.metrics_popup.is_some() {
+                        match key.code {
+                            KeyCode::Enter => app.confirm_metrics_popup(),
+                            KeyCode::Esc => app.close_metrics_popup(),
+                            KeyCode::Backspace => {
+                                if let Some(popup) = &mut app.metrics_popup {
+                                    popup.delete_char();
+                                }
+                            }
+                            KeyCode::Left => {
+                                if let Some(popup) = &mut app.metrics_popup {
+                                    popup.move_cursor_left();
+                                }
+                            }
+                            KeyCode::Right => {
+                                if let Some(popup) = &mut app.metrics_popup {
+                                    popup.move_cursor_right();
+                                }
+                            }
+                            KeyCode::Char(c) => {
+                                if let Some(popup) = &mut app.metrics_popup {
+                                    popup.insert_char(c);
+                                }
+                            }
+                            _ => {}
+                        }
+                        continue;
+                    }
+
+                    // === Handle preset browser keys (if preset browser is open) ===
+                    if app.preset_popup.is_some() {
+                        match key.code {
+                            KeyCode::Up | KeyCode::Char('w') => app.preset_nav_up(),
+                            KeyCode::Down | KeyCode::Char('s') => app.preset_nav_down(),
+                            KeyCode::Enter => app.confirm_preset_popup(),
+                            KeyCode::Esc => app.close_preset_popup(),
+                            KeyCode::Char('n') => app.open_preset_save_popup(),
+                            KeyCode::Char('d') => app.delete_selected_preset(),
+                            _ => {}
+                        }
+                        continue;
+                    }
+
+                    // === Handle seed panel keys (if seed panel is open) ===
+                    if app.seed_popup.is_some() {
+                        match key.code {
+                            KeyCode::Up | KeyCode::Char('w') => app.seed_nav_up(),
+                            KeyCode::Down | KeyCode::Char('s') => app.seed_nav_down(),
+                            KeyCode::Left | KeyCode::Char('j') => app.adjust_seed_param(-1.0),
+                            KeyCode::Right | KeyCode::Char('k') => app.adjust_seed_param(1.0),
+                            KeyCode::Char('x') | KeyCode::Char('X') | KeyCode::Backspace | KeyCode::Delete => {
+                                app.clear_seed_param()
+                            }
+                            KeyCode::Enter | KeyCode::Esc => app.close_seed_popup(),
+                            _ => {}
+                        }
+                        continue;
+                    }
+
+                    // === Handle preset save popup keys (if preset save popup is open) ===
+                    if app.preset_save_popup.is_some() {
+                        match key.code {
+                            KeyCode::Enter => app.confirm_preset_save(),
+                            KeyCode::Esc => app.close_preset_save_popup(),
+                            KeyCode::Backspace => {
+                                if let Some(popup) = &mut app.preset_save_popup {
+                                    popup.delete_char();
+                                }
+                            }
+                            KeyCode::Left => {
+                                if let Some(popup) = &mut app.preset_save_popup {
+                                    popup.move_cursor_left();
+                                }
+                            }
+                            KeyCode::Right => {
+                                if let Some(popup) = &mut app.preset_save_popup {
+                                    popup.move_cursor_right();
+                                }
+                            }
+                            KeyCode::Char(c) => {
+                                if let Some(popup) = &mut app.preset_save_popup {
+                                    popup.insert_char(c);
+                                }
+                            }
+                            _ => {}
+                        }
+                        continue;
+                    }
+
                     // === Handle recording popup keys (if recording popup is open) ===
                     if app.recording_popup.is_some() {
                         match key.code {
@@ -462,6 +1204,26 @@ fn run_app<B: ratatui::backend::Backend>(
                         app.clear_recording_result();
                     }
 
+                    // Clear snapshot result on any key press
+                    if app.snapshot_result.is_some() {
+                        app.clear_snapshot_result();
+                    }
+
+                    // Clear preset result on any key press
+                    if app.preset_result.is_some() {
+                        app.clear_preset_result();
+                    }
+
+                    // Clear growth probe result on any key press
+                    if app.probe_result.is_some() {
+                        app.clear_probe_result();
+                    }
+
+                    // Clear metrics log error on any key press
+                    if app.metrics_result.is_some() {
+                        app.clear_metrics_result();
+                    }
+
                     // === Handle Shift+letter to open popup ===
                     if key.modifiers.contains(KeyModifiers::SHIFT) {
                         if let KeyCode::Char(c) = key.code {
@@ -501,8 +1263,21 @@ fn run_app<B: ratatui::backend::Backend>(
                         KeyCode::Char('q') | KeyCode::Char('Q') => return Ok(()),
                         KeyCode::Char(' ') => app.toggle_pause(),
                         KeyCode::Char('r') | KeyCode::Char('R') => app.reset(),
-                        KeyCode::Char('v') | KeyCode::Char('V') => app.cycle_view_mode(),
+                        KeyCode::Char('v') | KeyCode::Char('V') => {
+                            app.cycle_view_mode();
+                            let term_size = terminal.size().unwrap_or_default();
+                            let (canvas_width, canvas_height) =
+                                ui::get_canvas_size(ratatui::layout::Rect::new(0, 0, term_size.width, term_size.height), app.view_mode);
+                            app.resize(canvas_width, canvas_height);
+                        }
                         KeyCode::Char('h') | KeyCode::Char('H') => app.toggle_help(),
+                        KeyCode::Char('p') => app.open_png_popup(),
+                        KeyCode::Char('o') => app.open_snapshot_popup(SnapshotAction::Save),
+                        KeyCode::Char('l') => app.open_snapshot_popup(SnapshotAction::Load),
+                        KeyCode::Char('t') => app.open_preset_popup(),
+                        KeyCode::Char('g') => app.toggle_harmonic(),
+                        KeyCode::Char('d') => app.toggle_morphology(),
+                        KeyCode::Char('e') => app.toggle_metrics(),
                         // Recording toggle (backtick)
                         KeyCode::Char('`') => {
                             if app.is_recording() {
@@ -593,6 +1368,9 @@ fn run_app<B: ratatui::backend::Backend>(
                             }
                         }
 
+                        // Enter on the seed parameter opens the seed panel
+                        KeyCode::Enter if app.focus == Focus::Seed => app.open_seed_popup(),
+
                         // j/k for adjusting focused parameter values
                         KeyCode::Char('j') if app.focus.is_param() => {
                             app.adjust_focused_down();
@@ -601,6 +1379,15 @@ fn run_app<B: ratatui::backend::Backend>(
                             app.adjust_focused_up();
                         }
 
+                        // View (zoom/pan never touch the simulation)
+                        KeyCode::Char('.') => app.zoom_view(1.5),
+                        KeyCode::Char(',') => app.zoom_view(1.0 / 1.5),
+                        KeyCode::Char('f') => app.fit_view(),
+                        KeyCode::Up if key.modifiers.contains(KeyModifiers::CONTROL) => app.pan_view(0.0, -0.1),
+                        KeyCode::Down if key.modifiers.contains(KeyModifiers::CONTROL) => app.pan_view(0.0, 0.1),
+                        KeyCode::Left if key.modifiers.contains(KeyModifiers::CONTROL) => app.pan_view(-0.1, 0.0),
+                        KeyCode::Right if key.modifiers.contains(KeyModifiers::CONTROL) => app.pan_view(0.1, 0.0),
+
                         // Navigation
                         KeyCode::Tab => app.next_focus(),
                         KeyCode::BackTab => app.prev_focus(),
@@ -656,3 +1443,88 @@ fn run_app<B: ratatui::backend::Backend>(
         app.tick();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn configured(cli: &[&str]) -> App {
+        let matches = Args::command().try_get_matches_from(cli).unwrap();
+        let args = Args::from_arg_matches(&matches).unwrap();
+        let mut app = App::with_simulation_size(64, 64);
+        configure_app(&mut app, &args, &matches, None);
+        app
+    }
+
+    #[test]
+    fn test_particle_cap_follows_cli_boundary() {
+        // A fixed domain caps the run at 75% of the grid
+        let app = configured(&["dla-sim-tui", "--boundary", "clamp", "-p", "5000"]);
+        assert_eq!(app.simulation.num_particles, 64 * 64 * 3 / 4);
+
+        // A preset with walls doesn't cap a run switched to an unbounded domain
+        let app = configured(&["dla-sim-tui", "--preset", "Edge Growth", "--boundary", "unbounded", "-p", "20000"]);
+        assert_eq!(app.simulation.settings.boundary_behavior, BoundaryBehavior::Unbounded);
+        assert_eq!(app.simulation.num_particles, 20000);
+    }
+
+    #[test]
+    fn test_rejected_snapshot_leaves_the_app_unchanged() {
+        use dla_sim_tui::snapshot::Snapshot;
+
+        let mut app = App::with_simulation_size(64, 64);
+        let particles = app.simulation.num_particles;
+        let mut state = app.simulation.state();
+        state.cells.push((64 * 64, Default::default()));
+        let config = AppConfig {
+            num_particles: 123,
+            settings: dla_sim_tui::settings::SimulationSettings {
+                voxels: true,
+                ..Default::default()
+            },
+            ..AppConfig::default()
+        };
+
+        assert!(app.apply_snapshot(Snapshot::new(config, state)).is_err());
+        assert!(!app.simulation.settings.voxels);
+        assert_eq!(app.simulation.num_particles, particles);
+    }
+
+    #[test]
+    fn test_resume_with_a_missing_image_warns_and_keeps_the_override() {
+        use dla_sim_tui::snapshot::Snapshot;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("run.dla");
+        let mut sim = dla_sim_tui::simulation::DlaSimulation::new(64, 64);
+        sim.num_particles = 150;
+        sim.reset();
+        while !sim.is_complete() {
+            sim.step();
+        }
+        let mut config = AppConfig {
+            num_particles: 150,
+            ..AppConfig::default()
+        };
+        config.settings.obstacle_mask = Some(dir.path().join("gone.png"));
+        Snapshot::new(config, sim.state()).save_to_file(&path).unwrap();
+
+        let cli = ["dla-sim-tui", "--resume", path.to_str().unwrap(), "-p", "700"];
+        let matches = Args::command().try_get_matches_from(cli).unwrap();
+        let args = Args::from_arg_matches(&matches).unwrap();
+        let mut app = App::with_simulation_size(64, 64);
+        let warning = resume_snapshot(&mut app, &args, &matches).unwrap();
+        assert!(warning.is_some());
+        assert_eq!(app.simulation.particles_stuck, 150);
+        assert_eq!(app.simulation.num_particles, 700);
+    }
+
+    #[test]
+    fn test_time_limit_rejects_bad_values() {
+        assert_eq!(parse_time_limit("1.5"), Ok(Duration::from_millis(1500)));
+        for bad in ["-1", "NaN", "1e300", "soon"] {
+            assert!(parse_time_limit(bad).is_err(), "{}", bad);
+        }
+        assert!(Args::command().try_get_matches_from(["dla-sim-tui", "--time-limit=-1"]).is_err());
+    }
+}
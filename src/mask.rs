@@ -0,0 +1,242 @@
+//! Cell masks thresholded from images, used for image seeds and obstacle walls.
+//!
+//! An image is scaled onto a raster of grid cells and a cell is set where the
+//! image has "ink": opaque pixels of images with transparency, and dark pixels
+//! of fully opaque ones. So both a black logo on white and a colored logo on a
+//! transparent background give the logo's shape.
+
+use image::imageops::FilterType;
+use image::GrayAlphaImage;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Ink coverage above which a cell is set
+const THRESHOLD: f32 = 0.5;
+
+/// How an image is fitted onto the raster
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Fit {
+    /// Keep the aspect ratio, as large as fits, centered
+    Contain,
+    /// Stretch over the whole raster
+    Stretch,
+}
+
+/// Set of cells on a `width` x `height` raster whose top-left cell is (0, 0)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Mask {
+    width: usize,
+    height: usize,
+    /// Row-major cell bits, 64 cells per word
+    bits: Vec<u64>,
+    /// Number of set cells
+    count: usize,
+}
+
+impl Mask {
+    /// Empty mask over a `width` x `height` raster
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            bits: vec![0; (width * height).div_ceil(64)],
+            count: 0,
+        }
+    }
+
+    /// Threshold the image at `path` onto a `width` x `height` raster
+    pub fn from_image(path: &Path, width: usize, height: usize, fit: Fit) -> Result<Self, String> {
+        let mut image = InkImage::default();
+        image.load(path)?;
+        Ok(image.rasterize(width, height, fit))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Set cell (x, y), which must lie on the raster
+    pub fn set(&mut self, x: usize, y: usize) {
+        let idx = y * self.width + x;
+        let bit = 1 << (idx % 64);
+        if self.bits[idx / 64] & bit == 0 {
+            self.bits[idx / 64] |= bit;
+            self.count += 1;
+        }
+    }
+
+    /// Clear cell (x, y) (no-op off the raster)
+    pub fn unset(&mut self, x: i32, y: i32) {
+        if self.contains(x, y) {
+            let idx = y as usize * self.width + x as usize;
+            self.bits[idx / 64] &= !(1 << (idx % 64));
+            self.count -= 1;
+        }
+    }
+
+    /// Whether cell (x, y) is set (false off the raster)
+    #[inline]
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        if x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height {
+            return false;
+        }
+        let idx = y as usize * self.width + x as usize;
+        self.bits[idx / 64] & (1 << (idx % 64)) != 0
+    }
+
+    /// Whether the cell containing point (x, y) is set
+    #[inline]
+    pub fn contains_point(&self, x: f32, y: f32) -> bool {
+        self.count > 0 && self.contains(x.floor() as i32, y.floor() as i32)
+    }
+
+    /// Number of set cells
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Set cells in row-major order
+    pub fn iter(&self) -> impl Iterator<Item = (i32, i32)> + '_ {
+        let width = self.width.max(1);
+        self.bits.iter().enumerate().flat_map(move |(word_idx, &word)| {
+            (0..64)
+                .filter(move |bit| word & (1 << bit) != 0)
+                .map(move |bit| {
+                    let idx = word_idx * 64 + bit;
+                    ((idx % width) as i32, (idx / width) as i32)
+                })
+        })
+    }
+}
+
+/// Decoded image whose ink is thresholded into masks, kept with its path so
+/// that a file is decoded once however often it is rasterized
+#[derive(Debug, Clone, Default)]
+pub struct InkImage {
+    path: Option<PathBuf>,
+    image: GrayAlphaImage,
+}
+
+impl InkImage {
+    /// Decode the image at `path`, unless it is the one already loaded. On
+    /// failure the previous image is kept.
+    pub fn load(&mut self, path: &Path) -> Result<(), String> {
+        if self.is_loaded(path) {
+            return Ok(());
+        }
+        let image = image::open(path).map_err(|e| format!("Failed to load image {}: {}", path.display(), e))?;
+        self.image = image.to_luma_alpha8();
+        self.path = Some(path.to_path_buf());
+        Ok(())
+    }
+
+    /// Whether the image at `path` is the one loaded
+    pub fn is_loaded(&self, path: &Path) -> bool {
+        self.path.as_deref() == Some(path)
+    }
+
+    /// Threshold the image onto a `width` x `height` raster
+    pub fn rasterize(&self, width: usize, height: usize, fit: Fit) -> Mask {
+        let image = &self.image;
+        let (image_w, image_h) = image.dimensions();
+        if image_w == 0 || image_h == 0 || width == 0 || height == 0 {
+            return Mask::new(width, height);
+        }
+        let transparent = image.pixels().any(|p| p[1] < u8::MAX);
+
+        let (fit_w, fit_h) = match fit {
+            Fit::Stretch => (width, height),
+            Fit::Contain => {
+                let scale = (width as f32 / image_w as f32).min(height as f32 / image_h as f32);
+                (
+                    ((image_w as f32 * scale).round() as usize).clamp(1, width),
+                    ((image_h as f32 * scale).round() as usize).clamp(1, height),
+                )
+            }
+        };
+        let (x0, y0) = ((width - fit_w) / 2, (height - fit_h) / 2);
+        let scaled = image::imageops::resize(image, fit_w as u32, fit_h as u32, FilterType::Triangle);
+
+        let mut mask = Mask::new(width, height);
+        for (x, y, pixel) in scaled.enumerate_pixels() {
+            let ink = if transparent {
+                pixel[1] as f32 / 255.0
+            } else {
+                1.0 - pixel[0] as f32 / 255.0
+            };
+            if ink > THRESHOLD {
+                mask.set(x0 + x as usize, y0 + y as usize);
+            }
+        }
+        mask
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{GrayImage, Luma, Rgba, RgbaImage};
+
+    #[test]
+    fn test_dark_pixels_of_opaque_images_are_ink() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bar.png");
+        // White 20x10 image with a black left half
+        let image = GrayImage::from_fn(20, 10, |x, _| Luma([if x < 10 { 0 } else { 255 }]));
+        image.save(&path).unwrap();
+
+        // Contain keeps the 2:1 aspect: 40x20 in the middle of a 40x40 raster
+        let mask = Mask::from_image(&path, 40, 40, Fit::Contain).unwrap();
+        assert_eq!(mask.count(), 20 * 20);
+        assert!(mask.contains(0, 10) && mask.contains(19, 29));
+        assert!(!mask.contains(20, 20) && !mask.contains(5, 9) && !mask.contains(5, 30));
+        assert_eq!(mask.iter().count(), mask.count());
+        assert!(mask.iter().all(|(x, y)| mask.contains(x, y)));
+
+        // Stretch covers the whole raster
+        let stretched = Mask::from_image(&path, 40, 40, Fit::Stretch).unwrap();
+        assert_eq!(stretched.count(), 20 * 40);
+    }
+
+    #[test]
+    fn test_opaque_pixels_of_transparent_images_are_ink() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("dot.png");
+        // A white opaque square on a transparent background
+        let image = RgbaImage::from_fn(8, 8, |x, y| {
+            let inside = (2..6).contains(&x) && (2..6).contains(&y);
+            Rgba([255, 255, 255, if inside { 255 } else { 0 }])
+        });
+        image.save(&path).unwrap();
+
+        let mask = Mask::from_image(&path, 8, 8, Fit::Stretch).unwrap();
+        assert_eq!(mask.count(), 16);
+        assert!(mask.contains(2, 2) && !mask.contains(1, 1));
+        assert!(Mask::from_image(&dir.path().join("missing.png"), 8, 8, Fit::Stretch).is_err());
+    }
+
+    #[test]
+    fn test_ink_image_keeps_the_last_good_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("dot.png");
+        GrayImage::from_fn(4, 4, |x, _| Luma([if x < 2 { 0 } else { 255 }])).save(&path).unwrap();
+
+        let mut image = InkImage::default();
+        image.load(&path).unwrap();
+        // Loading the same path again doesn't touch the file
+        std::fs::remove_file(&path).unwrap();
+        image.load(&path).unwrap();
+        assert_eq!(image.rasterize(4, 4, Fit::Stretch).count(), 8);
+
+        assert!(image.load(&dir.path().join("missing.png")).is_err());
+        assert!(image.is_loaded(&path));
+    }
+}
@@ -0,0 +1,307 @@
+//! Time-series metric logging: how a cluster evolves as it grows.
+//!
+//! A sample is taken every time the stuck particle count reaches the next
+//! multiple of the interval, and once more when logging finishes. Samples are
+//! streamed to CSV (a header row, then one row each) or NDJSON (one JSON object
+//! per line), flushed as they are written so a run can be followed live.
+//!
+//! A simulation reset shows up as a drop in the particle or walk count; the
+//! log then starts a new run, numbered in the `run` column, with its own clock.
+//! Models that launch no walkers (Eden, DBM, DLCA) leave the walk and escape
+//! columns empty (null in NDJSON).
+
+use crate::analysis::cluster_points;
+use crate::simulation::DlaSimulation;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::time::Instant;
+
+/// Stuck particles between samples by default
+pub const DEFAULT_INTERVAL: usize = 100;
+
+/// CSV column names, in `MetricSample` field order
+const CSV_HEADER: &str =
+    "run,particles_stuck,max_radius,radius_of_gyration,fractal_dimension,r_squared,walks,escapes,elapsed_secs";
+
+/// File format of a metrics log
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MetricsFormat {
+    Csv,
+    Ndjson,
+}
+
+impl MetricsFormat {
+    /// NDJSON for .ndjson, .jsonl and .json files, CSV otherwise
+    pub fn from_filename(filename: &str) -> Self {
+        let lower = filename.to_lowercase();
+        if lower.ends_with(".ndjson") || lower.ends_with(".jsonl") || lower.ends_with(".json") {
+            MetricsFormat::Ndjson
+        } else {
+            MetricsFormat::Csv
+        }
+    }
+}
+
+/// Metrics of the cluster at one point of a run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricSample {
+    /// Runs started since logging began, from 0
+    pub run: u32,
+    pub particles_stuck: usize,
+    pub max_radius: f32,
+    /// Radius of gyration around the particles' centroid
+    pub radius_of_gyration: f32,
+    /// Box-counting dimension, as in the status panel
+    pub fractal_dimension: f32,
+    pub r_squared: f32,
+    /// Finished random walks and those that didn't stick (None for models
+    /// without walkers)
+    pub walks: Option<u64>,
+    pub escapes: Option<u64>,
+    /// Wall-clock seconds since the run started
+    pub elapsed_secs: f64,
+}
+
+impl MetricSample {
+    /// Measure the current cluster
+    pub fn of(sim: &DlaSimulation, run: u32, elapsed_secs: f64) -> Self {
+        let (fractal_dimension, r_squared) = sim.calculate_fractal_dimension();
+        let walkers = sim.settings.active_growth_model().launches_walkers();
+        Self {
+            run,
+            particles_stuck: sim.particles_stuck,
+            max_radius: sim.max_radius,
+            radius_of_gyration: radius_of_gyration(sim),
+            fractal_dimension,
+            r_squared,
+            walks: walkers.then_some(sim.walks),
+            escapes: walkers.then_some(sim.escapes),
+            elapsed_secs,
+        }
+    }
+
+    fn to_csv_row(&self) -> String {
+        let count = |value: Option<u64>| value.map_or(String::new(), |v| v.to_string());
+        format!(
+            "{},{},{},{},{},{},{},{},{:.3}",
+            self.run,
+            self.particles_stuck,
+            self.max_radius,
+            self.radius_of_gyration,
+            self.fractal_dimension,
+            self.r_squared,
+            count(self.walks),
+            count(self.escapes),
+            self.elapsed_secs
+        )
+    }
+}
+
+/// Root-mean-square distance of the particles from their centroid
+fn radius_of_gyration(sim: &DlaSimulation) -> f32 {
+    let (points, _) = cluster_points(sim);
+    if points.is_empty() {
+        return 0.0;
+    }
+    let n = points.len() as f64;
+    let mut mean = [0.0f64; 3];
+    for (p, _) in &points {
+        for (m, v) in mean.iter_mut().zip(p) {
+            *m += *v as f64 / n;
+        }
+    }
+    let sum_sq: f64 = points
+        .iter()
+        .map(|(p, _)| p.iter().zip(&mean).map(|(v, m)| (*v as f64 - m).powi(2)).sum::<f64>())
+        .sum();
+    (sum_sq / n).sqrt() as f32
+}
+
+/// Open metrics file, sampled as the simulation grows
+pub struct MetricsLog {
+    writer: BufWriter<File>,
+    format: MetricsFormat,
+    interval: usize,
+    /// Particle count that triggers the next sample
+    next: usize,
+    /// Particle and walk counts of the last sample
+    last: Option<(usize, u64)>,
+    run: u32,
+    run_start: Instant,
+    samples: usize,
+}
+
+impl MetricsLog {
+    /// Create (or truncate) the file and write the CSV header
+    pub fn create(path: &Path, interval: usize) -> Result<Self, String> {
+        let file = File::create(path).map_err(|e| format!("Failed to create metrics file: {}", e))?;
+        let mut log = Self {
+            writer: BufWriter::new(file),
+            format: MetricsFormat::from_filename(&path.to_string_lossy()),
+            interval: interval.max(1),
+            next: 0,
+            last: None,
+            run: 0,
+            run_start: Instant::now(),
+            samples: 0,
+        };
+        if log.format == MetricsFormat::Csv {
+            log.write_line(CSV_HEADER)?;
+        }
+        Ok(log)
+    }
+
+    /// Samples written so far
+    pub fn samples(&self) -> usize {
+        self.samples
+    }
+
+    /// Sample the simulation if it has reached the next multiple of the
+    /// interval (cheap otherwise, so it can be called after every step)
+    pub fn record(&mut self, sim: &DlaSimulation) -> Result<(), String> {
+        if let Some((particles, walks)) = self.last {
+            if sim.particles_stuck < particles || sim.walks < walks {
+                // The simulation was reset or an earlier snapshot loaded
+                self.run += 1;
+                self.run_start = Instant::now();
+                self.last = None;
+            }
+        }
+        if self.last.is_none() || sim.particles_stuck >= self.next {
+            self.sample(sim)?;
+        }
+        Ok(())
+    }
+
+    /// Sample the final state (unless it was just sampled) and flush the file
+    pub fn finish(&mut self, sim: &DlaSimulation) -> Result<(), String> {
+        if self.last != Some((sim.particles_stuck, sim.walks)) {
+            self.sample(sim)?;
+        }
+        self.writer
+            .flush()
+            .map_err(|e| format!("Failed to write metrics file: {}", e))
+    }
+
+    fn sample(&mut self, sim: &DlaSimulation) -> Result<(), String> {
+        let sample = MetricSample::of(sim, self.run, self.run_start.elapsed().as_secs_f64());
+        let line = match self.format {
+            MetricsFormat::Csv => sample.to_csv_row(),
+            MetricsFormat::Ndjson => {
+                serde_json::to_string(&sample).map_err(|e| format!("Failed to serialize metrics: {}", e))?
+            }
+        };
+        self.write_line(&line)?;
+        self.samples += 1;
+        self.last = Some((sim.particles_stuck, sim.walks));
+        self.next = (sim.particles_stuck / self.interval + 1) * self.interval;
+        Ok(())
+    }
+
+    fn write_line(&mut self, line: &str) -> Result<(), String> {
+        writeln!(self.writer, "{}", line)
+            .and_then(|_| self.writer.flush())
+            .map_err(|e| format!("Failed to write metrics file: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::BoundaryBehavior;
+    use crate::simulation::SeedPattern;
+    use std::fs;
+
+    fn grow(sim: &mut DlaSimulation, log: &mut MetricsLog, particles: usize) {
+        sim.reset_with_seed(SeedPattern::Point);
+        sim.num_particles = particles;
+        log.record(sim).unwrap();
+        while !sim.is_complete() {
+            sim.step();
+            log.record(sim).unwrap();
+        }
+        log.finish(sim).unwrap();
+    }
+
+    fn seeded_sim() -> DlaSimulation {
+        let mut sim = DlaSimulation::new(80, 80);
+        sim.settings.boundary_behavior = BoundaryBehavior::Clamp;
+        sim.set_rng_seed(Some(3));
+        sim
+    }
+
+    #[test]
+    fn test_samples_every_interval_and_starts_a_run_on_reset() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("growth.csv");
+        let mut sim = seeded_sim();
+        let mut log = MetricsLog::create(&path, 100).unwrap();
+        grow(&mut sim, &mut log, 250);
+        grow(&mut sim, &mut log, 120);
+
+        let csv = fs::read_to_string(&path).unwrap();
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some(CSV_HEADER));
+        let rows: Vec<Vec<&str>> = lines.map(|l| l.split(',').collect()).collect();
+        let counts: Vec<(&str, &str)> = rows.iter().map(|r| (r[0], r[1])).collect();
+        assert_eq!(
+            counts,
+            [("0", "1"), ("0", "100"), ("0", "200"), ("0", "250"), ("1", "1"), ("1", "100"), ("1", "120")]
+        );
+        assert_eq!(log.samples(), rows.len());
+
+        let last: Vec<f64> = rows[3].iter().map(|v| v.parse().unwrap()).collect();
+        let (radius, gyration, walks, escapes) = (last[2], last[3], last[6], last[7]);
+        assert!(gyration > 1.0 && gyration < radius, "Rg {} vs max radius {}", gyration, radius);
+        // Every particle after the seed took one walk that stuck
+        assert_eq!(walks - escapes, 249.0);
+    }
+
+    #[test]
+    fn test_ndjson_lines_parse_back_into_samples() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("growth.ndjson");
+        let mut sim = seeded_sim();
+        let mut log = MetricsLog::create(&path, 50).unwrap();
+        grow(&mut sim, &mut log, 150);
+
+        let samples: Vec<MetricSample> = fs::read_to_string(&path)
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        let counts: Vec<usize> = samples.iter().map(|s| s.particles_stuck).collect();
+        assert_eq!(counts, [1, 50, 100, 150]);
+        assert!(samples.windows(2).all(|w| w[1].walks > w[0].walks && w[1].elapsed_secs >= w[0].elapsed_secs));
+        assert!(samples.iter().all(|s| s.walks.is_some()));
+        assert_eq!(MetricsFormat::from_filename("log.CSV"), MetricsFormat::Csv);
+        assert_eq!(MetricsFormat::from_filename("log.jsonl"), MetricsFormat::Ndjson);
+    }
+
+    #[test]
+    fn test_models_without_walkers_leave_walks_empty() {
+        use crate::settings::GrowthModel;
+
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["eden.csv", "eden.ndjson"] {
+            let path = dir.path().join(name);
+            let mut sim = seeded_sim();
+            sim.settings.growth_model = GrowthModel::Eden;
+            let mut log = MetricsLog::create(&path, 50).unwrap();
+            grow(&mut sim, &mut log, 100);
+
+            let content = fs::read_to_string(&path).unwrap();
+            let last = content.lines().last().unwrap();
+            if name.ends_with(".csv") {
+                let row: Vec<&str> = last.split(',').collect();
+                assert_eq!((row[1], row[6], row[7]), ("100", "", ""));
+            } else {
+                let sample: MetricSample = serde_json::from_str(last).unwrap();
+                assert_eq!((sample.particles_stuck, sample.walks, sample.escapes), (100, None, None));
+                assert!(last.contains("\"walks\":null"));
+            }
+        }
+    }
+}
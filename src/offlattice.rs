@@ -0,0 +1,231 @@
+//! Off-lattice (continuous) cluster of disc particles.
+//!
+//! Discs have f32 centers and a common radius. They are indexed in a spatial
+//! hash with cell size equal to the contact distance, so any disc touching a
+//! point lies in the 3x3 block of hash cells around it.
+
+use crate::simulation::ParticleData;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Default disc radius (one grid cell in diameter)
+pub const DEFAULT_DISC_RADIUS: f32 = 0.5;
+
+/// A stuck disc particle
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Disc {
+    pub x: f32,
+    pub y: f32,
+    pub data: ParticleData,
+}
+
+impl Disc {
+    /// Grid cells the disc covers part of, for rasterizing it into the grid
+    /// (always includes the cell holding its center)
+    pub fn cells(&self, radius: f32) -> impl Iterator<Item = (i32, i32)> + '_ {
+        let (x0, x1) = ((self.x - radius).floor() as i32, (self.x + radius).floor() as i32);
+        let (y0, y1) = ((self.y - radius).floor() as i32, (self.y + radius).floor() as i32);
+        let radius_sq = radius * radius;
+        (y0..=y1)
+            .flat_map(move |cy| (x0..=x1).map(move |cx| (cx, cy)))
+            .filter(move |&(cx, cy)| {
+                // Closest point of the cell to the center
+                let nx = self.x.clamp(cx as f32, cx as f32 + 1.0);
+                let ny = self.y.clamp(cy as f32, cy as f32 + 1.0);
+                (nx - self.x).powi(2) + (ny - self.y).powi(2) < radius_sq
+            })
+    }
+}
+
+/// Disc particles plus a spatial hash for contact queries
+#[derive(Debug, Clone)]
+pub struct DiscCluster {
+    pub radius: f32,
+    discs: Vec<Disc>,
+    buckets: HashMap<(i32, i32), Vec<usize>>,
+}
+
+impl Default for DiscCluster {
+    fn default() -> Self {
+        Self::new(DEFAULT_DISC_RADIUS)
+    }
+}
+
+impl DiscCluster {
+    pub fn new(radius: f32) -> Self {
+        Self {
+            radius,
+            discs: Vec::new(),
+            buckets: HashMap::new(),
+        }
+    }
+
+    /// Rebuild a cluster from a list of discs
+    pub fn from_discs(radius: f32, discs: Vec<Disc>) -> Self {
+        let mut cluster = Self::new(radius);
+        for disc in discs {
+            cluster.insert(disc);
+        }
+        cluster
+    }
+
+    /// Center-to-center distance at which two discs touch
+    pub fn contact_distance(&self) -> f32 {
+        2.0 * self.radius
+    }
+
+    fn bucket_of(&self, x: f32, y: f32) -> (i32, i32) {
+        let cell = self.contact_distance();
+        ((x / cell).floor() as i32, (y / cell).floor() as i32)
+    }
+
+    pub fn discs(&self) -> &[Disc] {
+        &self.discs
+    }
+
+    pub fn len(&self) -> usize {
+        self.discs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.discs.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.discs.clear();
+        self.buckets.clear();
+    }
+
+    pub fn insert(&mut self, disc: Disc) {
+        let bucket = self.bucket_of(disc.x, disc.y);
+        self.buckets.entry(bucket).or_default().push(self.discs.len());
+        self.discs.push(disc);
+    }
+
+    /// Number of discs touching a disc centered at (x, y)
+    pub fn contacts_at(&self, x: f32, y: f32) -> usize {
+        self.contacts(x, y).count()
+    }
+
+    /// Discs touching a disc centered at (x, y)
+    pub fn contacts(&self, x: f32, y: f32) -> impl Iterator<Item = &Disc> + '_ {
+        // Small tolerance so a disc placed exactly at contact counts its target
+        let reach = self.contact_distance() * 1.001;
+        let reach_sq = reach * reach;
+        let (bx, by) = self.bucket_of(x, y);
+        (by - 1..=by + 1)
+            .flat_map(move |cy| (bx - 1..=bx + 1).map(move |cx| (cx, cy)))
+            .filter_map(|bucket| self.buckets.get(&bucket))
+            .flatten()
+            .map(|&i| &self.discs[i])
+            .filter(move |d| (d.x - x).powi(2) + (d.y - y).powi(2) <= reach_sq)
+    }
+
+    /// First contact of a disc moving from (x0, y0) to (x1, y1).
+    ///
+    /// Solves |p0 + t·(p1 - p0) - c|² = (2r)² exactly for every nearby disc and
+    /// returns the smallest t in [0, 1], or None if the path is clear.
+    pub fn first_contact(&self, x0: f32, y0: f32, x1: f32, y1: f32) -> Option<f32> {
+        let dx = x1 - x0;
+        let dy = y1 - y0;
+        let a = dx * dx + dy * dy;
+        let contact_sq = self.contact_distance().powi(2);
+
+        let (bx0, by0) = self.bucket_of(x0.min(x1), y0.min(y1));
+        let (bx1, by1) = self.bucket_of(x0.max(x1), y0.max(y1));
+
+        let mut best: Option<f32> = None;
+        for cy in by0 - 1..=by1 + 1 {
+            for cx in bx0 - 1..=bx1 + 1 {
+                let Some(indices) = self.buckets.get(&(cx, cy)) else {
+                    continue;
+                };
+                for &i in indices {
+                    let disc = &self.discs[i];
+                    let fx = x0 - disc.x;
+                    let fy = y0 - disc.y;
+                    let b = 2.0 * (fx * dx + fy * dy);
+                    if b >= 0.0 {
+                        // Moving away (or sideways) from this disc
+                        continue;
+                    }
+                    let c = fx * fx + fy * fy - contact_sq;
+                    if c <= 0.0 {
+                        // Already touching and moving further in
+                        return Some(0.0);
+                    }
+                    let disc_sq = b * b - 4.0 * a * c;
+                    if disc_sq < 0.0 {
+                        // Passes by without touching
+                        continue;
+                    }
+                    let t = (-b - disc_sq.sqrt()) / (2.0 * a);
+                    if t <= 1.0 && best.is_none_or(|bt| t < bt) {
+                        best = Some(t.max(0.0));
+                    }
+                }
+            }
+        }
+        best
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn disc(x: f32, y: f32) -> Disc {
+        Disc {
+            x,
+            y,
+            data: ParticleData::default(),
+        }
+    }
+
+    #[test]
+    fn test_first_contact_head_on() {
+        let cluster = DiscCluster::from_discs(0.5, vec![disc(10.0, 10.0)]);
+        // Moving along +x toward the disc: touches when centers are 1.0 apart
+        let t = cluster.first_contact(5.0, 10.0, 12.0, 10.0).unwrap();
+        assert!((5.0 + t * 7.0 - 9.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_first_contact_grazing_and_miss() {
+        let cluster = DiscCluster::from_discs(0.5, vec![disc(10.0, 10.0)]);
+        // Offset by 0.9: intersects, contact at x = 10 - sqrt(1 - 0.81)
+        let t = cluster.first_contact(5.0, 10.9, 15.0, 10.9).unwrap();
+        let expected_x = 10.0 - (1.0f32 - 0.81).sqrt();
+        assert!((5.0 + t * 10.0 - expected_x).abs() < 1e-3);
+        // Offset by 1.1: clear
+        assert!(cluster.first_contact(5.0, 11.1, 15.0, 11.1).is_none());
+        // Stops short of the disc
+        assert!(cluster.first_contact(5.0, 10.0, 8.0, 10.0).is_none());
+    }
+
+    #[test]
+    fn test_touching_disc_can_move_away() {
+        let cluster = DiscCluster::from_discs(0.5, vec![disc(10.0, 10.0)]);
+        assert!(cluster.first_contact(9.0, 10.0, 7.0, 10.0).is_none());
+        assert_eq!(cluster.first_contact(9.0, 10.0, 11.0, 10.0), Some(0.0));
+    }
+
+    #[test]
+    fn test_cells_cover_the_disc_footprint() {
+        let mut cells: Vec<_> = disc(10.5, 10.5).cells(0.5).collect();
+        assert_eq!(cells, [(10, 10)]);
+        // Straddling a cell corner touches all four cells
+        cells = disc(11.0, 11.0).cells(0.5).collect();
+        assert_eq!(cells, [(10, 10), (11, 10), (10, 11), (11, 11)]);
+        // Radius 1.5 on a cell center reaches the far edge of the 3x3 block, not past it
+        assert_eq!(disc(10.5, 10.5).cells(1.5).count(), 9);
+    }
+
+    #[test]
+    fn test_contacts_at_counts_touching_discs() {
+        let cluster = DiscCluster::from_discs(0.5, vec![disc(0.0, 0.0), disc(2.0, 0.0), disc(5.0, 5.0)]);
+        assert_eq!(cluster.contacts_at(1.0, 0.0), 2);
+        assert_eq!(cluster.contacts_at(5.0, 6.0), 1);
+        assert_eq!(cluster.contacts_at(3.5, 3.5), 0);
+    }
+}
@@ -1,24 +1,26 @@
+use crate::lattice::LatticeType;
 use crate::settings::{
-    BoundaryBehavior, NeighborhoodType, SimulationSettings, SpawnMode,
+    BoundaryBehavior, ColorMode, NeighborhoodType, SimulationSettings, Species, SpawnMode,
 };
-use crate::simulation::SeedPattern;
+use crate::simulation::{SeedParams, SeedPattern};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
 
 /// A named preset containing simulation settings
-#[allow(dead_code)]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Preset {
     pub name: String,
     pub description: String,
     pub settings: SimulationSettings,
     pub seed_pattern: SeedPattern,
+    /// Geometry overrides of the seed pattern
+    #[serde(default)]
+    pub seed_params: SeedParams,
     pub base_stickiness: f32,
     pub num_particles: usize,
 }
 
-#[allow(dead_code)]
 impl Preset {
     pub fn new(
         name: impl Into<String>,
@@ -33,6 +35,7 @@ impl Preset {
             description: description.into(),
             settings,
             seed_pattern,
+            seed_params: SeedParams::default(),
             base_stickiness,
             num_particles,
         }
@@ -40,7 +43,6 @@ impl Preset {
 }
 
 /// Manager for loading and saving presets
-#[allow(dead_code)]
 pub struct PresetManager {
     /// Built-in presets that ship with the app
     pub builtin: Vec<Preset>,
@@ -48,14 +50,12 @@ pub struct PresetManager {
     pub user: Vec<Preset>,
 }
 
-#[allow(dead_code)]
 impl Default for PresetManager {
     fn default() -> Self {
         Self::new()
     }
 }
 
-#[allow(dead_code)]
 impl PresetManager {
     pub fn new() -> Self {
         let mut manager = Self {
@@ -107,16 +107,16 @@ impl PresetManager {
                 0.3,
                 5000,
             ),
-            // Snowflake - symmetric cross pattern
+            // Snowflake - six-fold growth on the hexagonal lattice
             Preset::new(
                 "Snowflake",
-                "Symmetric snowflake-like growth",
+                "Six-armed snowflake-like growth on hex cells",
                 SimulationSettings {
                     walk_step_size: 2.0,
-                    neighborhood: NeighborhoodType::VonNeumann,
+                    lattice: LatticeType::Hexagonal,
                     ..Default::default()
                 },
-                SeedPattern::Cross,
+                SeedPattern::Point,
                 0.8,
                 5000,
             ),
@@ -226,6 +226,28 @@ impl PresetManager {
                 0.8,
                 5000,
             ),
+            // Alloy - two species that prefer their own kind
+            Preset::new(
+                "Alloy",
+                "Two-species dendrite with segregated domains",
+                SimulationSettings {
+                    neighborhood: NeighborhoodType::Moore,
+                    species: vec![
+                        Species::default(),
+                        Species {
+                            fraction: 0.5,
+                            walk_step_size: Some(2.0),
+                            ..Species::default()
+                        },
+                    ],
+                    stickiness_matrix: vec![vec![1.0, 0.15], vec![0.15, 1.0]],
+                    color_mode: ColorMode::Species,
+                    ..Default::default()
+                },
+                SeedPattern::Point,
+                1.0,
+                5000,
+            ),
         ];
     }
 
@@ -243,6 +265,9 @@ impl PresetManager {
                         if entry.path().extension().is_some_and(|e| e == "json") {
                             if let Ok(content) = fs::read_to_string(entry.path()) {
                                 if let Ok(preset) = serde_json::from_str::<Preset>(&content) {
+                                    if preset.settings.validate().is_err() {
+                                        continue;
+                                    }
                                     self.user.push(preset);
                                 }
                             }
@@ -274,8 +299,10 @@ impl PresetManager {
 
         fs::write(&path, json).map_err(|e| format!("Failed to write preset file: {}", e))?;
 
-        // Add to user presets if not already present
-        if !self.user.iter().any(|p| p.name == preset.name) {
+        // Replace an existing user preset of the same name, otherwise add it
+        if let Some(existing) = self.user.iter_mut().find(|p| p.name == preset.name) {
+            *existing = preset;
+        } else {
             self.user.push(preset);
         }
 
@@ -320,3 +347,38 @@ impl PresetManager {
         self.all_presets().map(|p| p.name.as_str()).collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_preset_names_unique() {
+        let manager = PresetManager::new();
+        let mut names: Vec<String> = manager.builtin.iter().map(|p| p.name.to_lowercase()).collect();
+        let count = names.len();
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), count);
+    }
+
+    #[test]
+    fn test_find_is_case_insensitive() {
+        let manager = PresetManager::new();
+        let preset = manager.find("snowflake").unwrap();
+        assert_eq!(preset.name, "Snowflake");
+        assert_eq!(preset.settings.lattice, LatticeType::Hexagonal);
+        assert!(manager.find("no such preset").is_none());
+    }
+
+    #[test]
+    fn test_preset_serialization_roundtrip() {
+        let manager = PresetManager::new();
+        let preset = manager.find("Edge Growth").unwrap();
+        let json = serde_json::to_string(preset).unwrap();
+        let loaded: Preset = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.name, preset.name);
+        assert_eq!(loaded.settings.spawn_mode, SpawnMode::Edges);
+        assert_eq!(loaded.num_particles, preset.num_particles);
+    }
+}
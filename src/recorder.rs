@@ -3,11 +3,17 @@
 //! Supports two output modes:
 //! - MP4/WebM via FFmpeg (if installed)
 //! - GIF via native Rust (fallback)
+//!
+//! Still PNG snapshots of the current grid share the same render pipeline.
+//! Both sample the grid through a `Viewport`, so output size is independent of
+//! the grid size and a growing (unbounded) grid is scaled to fit the frame.
 
-use crate::color::ColorScheme;
+use crate::color::{ColorScheme, OBSTACLE_RGB};
 use crate::settings::ColorMode;
 use crate::simulation::DlaSimulation;
+use crate::viewport::Viewport;
 use std::io::Write;
+use std::path::Path;
 use std::process::{Child, ChildStdin, Command, Stdio};
 
 /// RGB frame buffer for video encoding
@@ -61,16 +67,29 @@ impl OutputFormat {
 pub struct RecordingConfig {
     /// Video pixels per simulation pixel (default: 4)
     pub pixel_scale: u32,
+    /// Fixed output size in pixels (None = grid size times `pixel_scale`)
+    pub frame_size: Option<(u32, u32)>,
     /// Target framerate (default: 30)
     pub framerate: u32,
     /// Background color RGB
     pub background_color: (u8, u8, u8),
 }
 
+impl RecordingConfig {
+    /// Output size in pixels for a grid of the given size
+    pub fn output_size(&self, sim_width: usize, sim_height: usize) -> (u32, u32) {
+        self.frame_size.unwrap_or((
+            sim_width as u32 * self.pixel_scale.max(1),
+            sim_height as u32 * self.pixel_scale.max(1),
+        ))
+    }
+}
+
 impl Default for RecordingConfig {
     fn default() -> Self {
         Self {
             pixel_scale: 4,
+            frame_size: None,
             framerate: 30,
             background_color: (0, 0, 0), // Black background
         }
@@ -218,7 +237,7 @@ impl GifEncoder {
 
         // Get most frequent colors (up to 256)
         let mut colors: Vec<_> = color_counts.into_iter().collect();
-        colors.sort_by(|a, b| b.1.cmp(&a.1));
+        colors.sort_by_key(|c| std::cmp::Reverse(c.1));
         colors.truncate(256);
 
         // Build palette
@@ -345,8 +364,7 @@ impl Recorder {
         }
 
         // Calculate video dimensions
-        self.video_width = sim_width as u32 * self.config.pixel_scale;
-        self.video_height = sim_height as u32 * self.config.pixel_scale;
+        (self.video_width, self.video_height) = self.config.output_size(sim_width, sim_height);
 
         // Ensure even dimensions for video codecs
         self.video_width = (self.video_width / 2) * 2;
@@ -449,15 +467,16 @@ impl Recorder {
         // Take frame buffer temporarily to avoid borrow conflicts
         let mut frame = self.frame_buffer.take().ok_or("No frame buffer")?;
 
-        // Render simulation to frame
+        // Render the whole walk domain, scaled to the locked frame size
+        let viewport = Viewport::fit(simulation.bounds(), frame.width as usize, frame.height as usize);
         Self::render_frame_static(
             &mut frame,
             simulation,
+            &viewport,
             color_scheme,
             color_by_age,
             color_mode,
             invert_colors,
-            self.config.pixel_scale,
             self.config.background_color,
         );
 
@@ -478,24 +497,78 @@ impl Recorder {
         Ok(())
     }
 
-    /// Render simulation state to RGB frame buffer (static version to avoid borrow issues)
+    /// Export the current simulation grid as a PNG still image
+    /// Uses the same pixel scale, background and color pipeline as video frames
+    pub fn export_png(
+        &self,
+        filename: &str,
+        simulation: &DlaSimulation,
+        color_scheme: &ColorScheme,
+        color_by_age: bool,
+        color_mode: ColorMode,
+        invert_colors: bool,
+    ) -> Result<String, String> {
+        // Ensure filename has .png extension
+        let filename = if !filename.to_lowercase().ends_with(".png") {
+            format!("{}.png", filename)
+        } else {
+            filename.to_string()
+        };
+
+        let (min_x, min_y, max_x, max_y) = simulation.bounds();
+        let (width, height) = self
+            .config
+            .output_size((max_x - min_x) as usize, (max_y - min_y) as usize);
+        let mut frame = RgbFrame::new(width, height);
+        let viewport = Viewport::fit(simulation.bounds(), width as usize, height as usize);
+
+        Self::render_frame_static(
+            &mut frame,
+            simulation,
+            &viewport,
+            color_scheme,
+            color_by_age,
+            color_mode,
+            invert_colors,
+            self.config.background_color,
+        );
+
+        image::save_buffer_with_format(
+            Path::new(&filename),
+            &frame.pixels,
+            frame.width,
+            frame.height,
+            image::ColorType::Rgb8,
+            image::ImageFormat::Png,
+        )
+        .map_err(|e| format!("Failed to write PNG: {}", e))?;
+
+        Ok(filename)
+    }
+
+    /// Render the part of the simulation under `viewport` to an RGB frame buffer
+    /// (static version to avoid borrow issues)
     #[allow(clippy::too_many_arguments)]
-    fn render_frame_static(
+    pub fn render_frame_static(
         frame: &mut RgbFrame,
         simulation: &DlaSimulation,
+        viewport: &Viewport,
         color_scheme: &ColorScheme,
         color_by_age: bool,
         color_mode: ColorMode,
         invert_colors: bool,
-        scale: u32,
         bg: (u8, u8, u8),
     ) {
-        let sim_width = simulation.grid_width;
-        let sim_height = simulation.grid_height;
+        let (width, height) = (frame.width as usize, frame.height as usize);
 
         // Pre-calculate for color mapping
         let inv_num_particles = 1.0 / simulation.num_particles.max(1) as f32;
         let max_radius = simulation.max_radius.max(1.0);
+        let inv_species = 1.0 / (simulation.settings.species_count() - 1).max(1) as f32;
+        let tree = simulation.tree_extent();
+        let inv_generation = 1.0 / tree.generation.max(1) as f32;
+        let inv_path_length = 1.0 / tree.path_length.max(1.0);
+        let inv_strahler = 1.0 / tree.strahler.saturating_sub(1).max(1) as f32;
 
         // Fill with background
         for chunk in frame.pixels.chunks_exact_mut(3) {
@@ -504,10 +577,14 @@ impl Recorder {
             chunk[2] = bg.2;
         }
 
-        // Render each simulation pixel
-        for sim_y in 0..sim_height {
-            for sim_x in 0..sim_width {
-                if let Some(particle) = simulation.get_particle(sim_x, sim_y) {
+        // Domain x sampled by each pixel column (same for every row)
+        let point_xs: Vec<f32> = (0..width).map(|px| viewport.point_at(px, 0, width, height).0).collect();
+
+        // Sample the lattice cell under each output pixel
+        for py in 0..height {
+            let sim_y = viewport.point_at(0, py, width, height).1;
+            for (px, &sim_x) in point_xs.iter().enumerate() {
+                if let Some(particle) = simulation.particle_at(sim_x, sim_y) {
                     // Calculate color value based on mode
                     let value = match color_mode {
                         ColorMode::Age => particle.age as f32 * inv_num_particles,
@@ -516,6 +593,10 @@ impl Recorder {
                         ColorMode::Direction => {
                             (particle.direction + std::f32::consts::PI) / std::f32::consts::TAU
                         }
+                        ColorMode::Species => particle.species as f32 * inv_species,
+                        ColorMode::Generation => particle.generation as f32 * inv_generation,
+                        ColorMode::PathLength => particle.path_length * inv_path_length,
+                        ColorMode::Strahler => particle.strahler.saturating_sub(1) as f32 * inv_strahler,
                     };
 
                     // Get RGB color
@@ -526,24 +607,76 @@ impl Recorder {
                         (255, 255, 255)
                     };
 
-                    // Write pixel block (scale x scale)
-                    for py in 0..scale {
-                        for px in 0..scale {
-                            let vx = sim_x as u32 * scale + px;
-                            let vy = sim_y as u32 * scale + py;
-
-                            if vx < frame.width && vy < frame.height {
-                                let idx = ((vy * frame.width + vx) * 3) as usize;
-                                if idx + 2 < frame.pixels.len() {
-                                    frame.pixels[idx] = color.0;
-                                    frame.pixels[idx + 1] = color.1;
-                                    frame.pixels[idx + 2] = color.2;
-                                }
-                            }
-                        }
-                    }
+                    let idx = (py * width + px) * 3;
+                    frame.pixels[idx] = color.0;
+                    frame.pixels[idx + 1] = color.1;
+                    frame.pixels[idx + 2] = color.2;
+                } else if simulation.obstacle_at(sim_x, sim_y) {
+                    let idx = (py * width + px) * 3;
+                    frame.pixels[idx] = OBSTACLE_RGB.0;
+                    frame.pixels[idx + 1] = OBSTACLE_RGB.1;
+                    frame.pixels[idx + 2] = OBSTACLE_RGB.2;
                 }
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::BoundaryBehavior;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_export_png_dimensions_and_colors() {
+        let mut sim = DlaSimulation::new(64, 48);
+        // Walls keep the frame at the grid size
+        sim.settings.boundary_behavior = BoundaryBehavior::Clamp;
+        sim.set_rng_seed(Some(1));
+        sim.reset();
+
+        let mut recorder = Recorder::new();
+        recorder.config.pixel_scale = 2;
+        recorder.config.background_color = (10, 20, 30);
+
+        let dir = TempDir::new().unwrap();
+        let base = dir.path().join("cluster");
+        let saved = recorder
+            .export_png(base.to_str().unwrap(), &sim, &ColorScheme::Fire, true, ColorMode::Age, false)
+            .unwrap();
+        assert!(saved.ends_with(".png"));
+
+        let img = image::open(&saved).unwrap().to_rgb8();
+        assert_eq!(img.dimensions(), (128, 96));
+        // Corner is empty space, so it must be the background color
+        assert_eq!(img.get_pixel(0, 0).0, [10, 20, 30]);
+        // Center holds the point seed (age 0 -> start of the Fire gradient)
+        let expected = ColorScheme::Fire.map_rgb(0.0);
+        assert_eq!(img.get_pixel(64, 48).0, [expected.0, expected.1, expected.2]);
+    }
+
+    #[test]
+    fn test_export_png_fixed_frame_size_fits_grid() {
+        let mut sim = DlaSimulation::new(64, 48);
+        sim.set_rng_seed(Some(1));
+        sim.reset();
+
+        let mut recorder = Recorder::new();
+        recorder.config.frame_size = Some((300, 300));
+        recorder.config.background_color = (10, 20, 30);
+
+        let dir = TempDir::new().unwrap();
+        let base = dir.path().join("fixed");
+        let saved = recorder
+            .export_png(base.to_str().unwrap(), &sim, &ColorScheme::Fire, true, ColorMode::Age, false)
+            .unwrap();
+
+        let img = image::open(&saved).unwrap().to_rgb8();
+        assert_eq!(img.dimensions(), (300, 300));
+        // The grid is letterboxed into the square frame with the seed still centered
+        let expected = ColorScheme::Fire.map_rgb(0.0);
+        assert_eq!(img.get_pixel(150, 150).0, [expected.0, expected.1, expected.2]);
+        assert_eq!(img.get_pixel(150, 0).0, [10, 20, 30]);
+    }
+}
@@ -1,4 +1,6 @@
+use crate::lattice::LatticeType;
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 
 /// Neighborhood type for sticking checks
 #[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
@@ -37,6 +39,15 @@ impl NeighborhoodType {
         }
     }
 
+    /// Distance to the farthest neighbor offset
+    pub fn reach(&self) -> f32 {
+        match self {
+            NeighborhoodType::VonNeumann => 1.0,
+            NeighborhoodType::Moore => std::f32::consts::SQRT_2,
+            NeighborhoodType::Extended => 2.0 * std::f32::consts::SQRT_2,
+        }
+    }
+
     /// Get the neighbor offsets for this neighborhood type
     pub fn offsets(&self) -> &'static [(i32, i32)] {
         match self {
@@ -57,6 +68,173 @@ impl NeighborhoodType {
     }
 }
 
+/// Neighborhood type for voxel sticking checks in 3D mode
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum Neighborhood3d {
+    /// 6 neighbors sharing a face (classic 3D lattice DLA)
+    #[default]
+    Faces,
+    /// 18 neighbors sharing a face or an edge
+    Edges,
+    /// 26 neighbors sharing a face, edge or corner
+    Corners,
+}
+
+impl Neighborhood3d {
+    pub fn short_name(&self) -> &str {
+        match self {
+            Neighborhood3d::Faces => "6-face",
+            Neighborhood3d::Edges => "18-edge",
+            Neighborhood3d::Corners => "26-corner",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            Neighborhood3d::Faces => Neighborhood3d::Edges,
+            Neighborhood3d::Edges => Neighborhood3d::Corners,
+            Neighborhood3d::Corners => Neighborhood3d::Faces,
+        }
+    }
+
+    pub fn prev(&self) -> Self {
+        match self {
+            Neighborhood3d::Faces => Neighborhood3d::Corners,
+            Neighborhood3d::Edges => Neighborhood3d::Faces,
+            Neighborhood3d::Corners => Neighborhood3d::Edges,
+        }
+    }
+
+    /// Distance to the farthest neighbor offset
+    pub fn reach(&self) -> f32 {
+        match self {
+            Neighborhood3d::Faces => 1.0,
+            Neighborhood3d::Edges => std::f32::consts::SQRT_2,
+            Neighborhood3d::Corners => 3.0_f32.sqrt(),
+        }
+    }
+
+    /// Get the neighbor offsets for this neighborhood type
+    pub fn offsets(&self) -> &'static [(i32, i32, i32)] {
+        match self {
+            Neighborhood3d::Faces => &[(-1, 0, 0), (1, 0, 0), (0, -1, 0), (0, 1, 0), (0, 0, -1), (0, 0, 1)],
+            Neighborhood3d::Edges => &[
+                (-1, 0, 0), (1, 0, 0), (0, -1, 0), (0, 1, 0), (0, 0, -1), (0, 0, 1),
+                (-1, -1, 0), (1, -1, 0), (-1, 1, 0), (1, 1, 0),
+                (-1, 0, -1), (1, 0, -1), (-1, 0, 1), (1, 0, 1),
+                (0, -1, -1), (0, 1, -1), (0, -1, 1), (0, 1, 1),
+            ],
+            Neighborhood3d::Corners => &[
+                (-1, 0, 0), (1, 0, 0), (0, -1, 0), (0, 1, 0), (0, 0, -1), (0, 0, 1),
+                (-1, -1, 0), (1, -1, 0), (-1, 1, 0), (1, 1, 0),
+                (-1, 0, -1), (1, 0, -1), (-1, 0, 1), (1, 0, 1),
+                (0, -1, -1), (0, 1, -1), (0, -1, 1), (0, 1, 1),
+                (-1, -1, -1), (1, -1, -1), (-1, 1, -1), (1, 1, -1),
+                (-1, -1, 1), (1, -1, 1), (-1, 1, 1), (1, 1, 1),
+            ],
+        }
+    }
+}
+
+/// How a 3D cluster is drawn on the 2D canvas
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum VoxelView {
+    /// One z-slice through the volume
+    #[default]
+    Slice,
+    /// Max-intensity projection along z (highest color value per column)
+    Projection,
+    /// Orthographic view turning around the vertical axis
+    Rotate,
+}
+
+impl VoxelView {
+    pub fn name(&self) -> &str {
+        match self {
+            VoxelView::Slice => "Slice",
+            VoxelView::Projection => "Projection",
+            VoxelView::Rotate => "Rotate",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            VoxelView::Slice => VoxelView::Projection,
+            VoxelView::Projection => VoxelView::Rotate,
+            VoxelView::Rotate => VoxelView::Slice,
+        }
+    }
+
+    pub fn prev(&self) -> Self {
+        match self {
+            VoxelView::Slice => VoxelView::Rotate,
+            VoxelView::Projection => VoxelView::Slice,
+            VoxelView::Rotate => VoxelView::Projection,
+        }
+    }
+}
+
+/// Default sticking probability per contact under reaction-limited aggregation, relative to DLA
+pub const DEFAULT_RLA_STICKINESS: f32 = 0.1;
+
+/// How the cluster grows
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum GrowthModel {
+    /// Random walkers diffuse in and stick (diffusion-limited aggregation)
+    #[default]
+    Dla,
+    /// Random walkers that rarely stick per contact (reaction-limited aggregation)
+    Rla,
+    /// Particles fly in on straight lines from the spawn source
+    Ballistic,
+    /// A uniformly random empty perimeter cell is filled each step
+    Eden,
+    /// Dielectric breakdown: attach where the Laplace field gradient is strongest
+    Dbm,
+    /// Cluster-cluster aggregation: scattered particles and clusters all diffuse and merge
+    Dlca,
+}
+
+impl GrowthModel {
+    /// Whether the model launches walkers, so walks and escapes mean anything
+    pub fn launches_walkers(&self) -> bool {
+        matches!(self, GrowthModel::Dla | GrowthModel::Rla | GrowthModel::Ballistic)
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            GrowthModel::Dla => "DLA",
+            GrowthModel::Rla => "RLA",
+            GrowthModel::Ballistic => "Ballistic",
+            GrowthModel::Eden => "Eden",
+            GrowthModel::Dbm => "DBM",
+            GrowthModel::Dlca => "DLCA",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            GrowthModel::Dla => GrowthModel::Rla,
+            GrowthModel::Rla => GrowthModel::Ballistic,
+            GrowthModel::Ballistic => GrowthModel::Eden,
+            GrowthModel::Eden => GrowthModel::Dbm,
+            GrowthModel::Dbm => GrowthModel::Dlca,
+            GrowthModel::Dlca => GrowthModel::Dla,
+        }
+    }
+
+    pub fn prev(&self) -> Self {
+        match self {
+            GrowthModel::Dla => GrowthModel::Dlca,
+            GrowthModel::Rla => GrowthModel::Dla,
+            GrowthModel::Ballistic => GrowthModel::Rla,
+            GrowthModel::Eden => GrowthModel::Ballistic,
+            GrowthModel::Dbm => GrowthModel::Eden,
+            GrowthModel::Dlca => GrowthModel::Dbm,
+        }
+    }
+}
+
 /// Spawn mode - where particles spawn from
 #[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
 pub enum SpawnMode {
@@ -134,6 +312,8 @@ pub enum BoundaryBehavior {
     /// Particles are removed/respawned at edges (canonical DLA)
     #[default]
     Absorb,
+    /// No edges: the grid grows with the cluster
+    Unbounded,
 }
 
 impl BoundaryBehavior {
@@ -144,6 +324,7 @@ impl BoundaryBehavior {
             BoundaryBehavior::Bounce => "Bounce",
             BoundaryBehavior::Stick => "Stick",
             BoundaryBehavior::Absorb => "Absorb",
+            BoundaryBehavior::Unbounded => "Unbounded",
         }
     }
 
@@ -153,19 +334,27 @@ impl BoundaryBehavior {
             BoundaryBehavior::Wrap => BoundaryBehavior::Bounce,
             BoundaryBehavior::Bounce => BoundaryBehavior::Stick,
             BoundaryBehavior::Stick => BoundaryBehavior::Absorb,
-            BoundaryBehavior::Absorb => BoundaryBehavior::Clamp,
+            BoundaryBehavior::Absorb => BoundaryBehavior::Unbounded,
+            BoundaryBehavior::Unbounded => BoundaryBehavior::Clamp,
         }
     }
 
     pub fn prev(&self) -> Self {
         match self {
-            BoundaryBehavior::Clamp => BoundaryBehavior::Absorb,
+            BoundaryBehavior::Clamp => BoundaryBehavior::Unbounded,
             BoundaryBehavior::Wrap => BoundaryBehavior::Clamp,
             BoundaryBehavior::Bounce => BoundaryBehavior::Wrap,
             BoundaryBehavior::Stick => BoundaryBehavior::Bounce,
             BoundaryBehavior::Absorb => BoundaryBehavior::Stick,
+            BoundaryBehavior::Unbounded => BoundaryBehavior::Absorb,
         }
     }
+
+    /// Whether the walk domain grows with the cluster (every other boundary
+    /// keeps the fixed grid)
+    pub fn grows(&self) -> bool {
+        *self == BoundaryBehavior::Unbounded
+    }
 }
 
 /// Color mode - what property determines particle color
@@ -180,6 +369,14 @@ pub enum ColorMode {
     Density,
     /// Color by approach direction (angle)
     Direction,
+    /// Color by particle species
+    Species,
+    /// Color by generation: attachment links to the root of the growth tree
+    Generation,
+    /// Color by path length along the growth tree to the root
+    PathLength,
+    /// Color by Strahler (branch) order in the growth tree
+    Strahler,
 }
 
 impl ColorMode {
@@ -189,6 +386,10 @@ impl ColorMode {
             ColorMode::Distance => "Distance",
             ColorMode::Density => "Density",
             ColorMode::Direction => "Direction",
+            ColorMode::Species => "Species",
+            ColorMode::Generation => "Generation",
+            ColorMode::PathLength => "Path Length",
+            ColorMode::Strahler => "Strahler",
         }
     }
 
@@ -197,20 +398,90 @@ impl ColorMode {
             ColorMode::Age => ColorMode::Distance,
             ColorMode::Distance => ColorMode::Density,
             ColorMode::Density => ColorMode::Direction,
-            ColorMode::Direction => ColorMode::Age,
+            ColorMode::Direction => ColorMode::Species,
+            ColorMode::Species => ColorMode::Generation,
+            ColorMode::Generation => ColorMode::PathLength,
+            ColorMode::PathLength => ColorMode::Strahler,
+            ColorMode::Strahler => ColorMode::Age,
         }
     }
 
     pub fn prev(&self) -> Self {
         match self {
-            ColorMode::Age => ColorMode::Direction,
+            ColorMode::Age => ColorMode::Strahler,
             ColorMode::Distance => ColorMode::Age,
             ColorMode::Density => ColorMode::Distance,
             ColorMode::Direction => ColorMode::Density,
+            ColorMode::Species => ColorMode::Direction,
+            ColorMode::Generation => ColorMode::Species,
+            ColorMode::PathLength => ColorMode::Generation,
+            ColorMode::Strahler => ColorMode::PathLength,
         }
     }
 }
 
+/// Most particle species a run can have
+pub const MAX_SPECIES: usize = 8;
+
+/// One particle species of a multi-species run.
+///
+/// Unset parameters fall back to the global settings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Species {
+    /// Share of launched walkers (relative to the other species' fractions)
+    #[serde(default = "default_species_fraction")]
+    pub fraction: f32,
+    /// Where walkers of this species spawn from
+    #[serde(default)]
+    pub spawn_mode: Option<SpawnMode>,
+    /// Walk step size of this species
+    #[serde(default)]
+    pub walk_step_size: Option<f32>,
+    /// Bias angle in degrees of this species
+    #[serde(default)]
+    pub walk_bias_angle: Option<f32>,
+    /// Directional bias strength of this species
+    #[serde(default)]
+    pub walk_bias_strength: Option<f32>,
+    /// Radial bias of this species
+    #[serde(default)]
+    pub radial_bias: Option<f32>,
+}
+
+impl Default for Species {
+    fn default() -> Self {
+        Self {
+            fraction: default_species_fraction(),
+            spawn_mode: None,
+            walk_step_size: None,
+            walk_bias_angle: None,
+            walk_bias_strength: None,
+            radial_bias: None,
+        }
+    }
+}
+
+fn default_species_fraction() -> f32 {
+    1.0
+}
+
+/// Spawn and walk parameters a walker actually uses
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Motion {
+    pub spawn_mode: SpawnMode,
+    pub walk_step_size: f32,
+    pub walk_bias_angle: f32,
+    pub walk_bias_strength: f32,
+    pub radial_bias: f32,
+}
+
+impl Motion {
+    /// Whether the walk drifts (biased walks can't jump across circles)
+    pub fn is_biased(&self) -> bool {
+        self.walk_bias_strength > 0.0 || self.radial_bias.abs() > 0.001
+    }
+}
+
 /// All simulation settings consolidated into one struct
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SimulationSettings {
@@ -229,10 +500,40 @@ pub struct SimulationSettings {
     pub adaptive_step_factor: f32,
     /// Use pure lattice walk (4 cardinal directions) instead of continuous angles
     pub lattice_walk: bool,
+    /// Cell shape: square, hexagonal or triangular
+    #[serde(default)]
+    pub lattice: LatticeType,
+    /// Jump walkers across empty space using the distance-to-cluster map
+    #[serde(default)]
+    pub circle_jump: bool,
+    /// Off-lattice mode: disc particles with continuous positions and exact contact
+    #[serde(default)]
+    pub off_lattice: bool,
+    /// Radius of off-lattice discs in grid cells (0.25-4.0)
+    #[serde(default = "default_disc_radius")]
+    pub disc_radius: f32,
+    /// 3D mode: lattice walkers on a voxel grid with spherical spawn shells
+    #[serde(default)]
+    pub voxels: bool,
+    /// Growth model: DLA, RLA, ballistic, Eden, dielectric breakdown or cluster-cluster
+    #[serde(default)]
+    pub growth_model: GrowthModel,
+    /// Reaction-limited sticking probability per contact, relative to DLA (0.01-1.0)
+    #[serde(default = "default_rla_stickiness")]
+    pub rla_stickiness: f32,
+    /// Dielectric breakdown exponent: growth probability ~ field^eta (0.0-5.0)
+    #[serde(default = "default_dbm_eta")]
+    pub dbm_eta: f32,
+    /// Cluster-cluster mobility exponent: a cluster of mass s moves at a rate ~ s^-gamma (0.0-2.0)
+    #[serde(default = "default_cluster_mobility")]
+    pub cluster_mobility: f32,
 
     // === Sticking Parameters ===
     /// Neighborhood type for checking adjacent particles
     pub neighborhood: NeighborhoodType,
+    /// Neighborhood type used instead in 3D mode
+    #[serde(default)]
+    pub neighborhood_3d: Neighborhood3d,
     /// Minimum neighbors required to stick (1-4)
     pub multi_contact_min: u8,
     /// Stickiness at branch tips (few neighbors) (0.1-1.0)
@@ -255,6 +556,22 @@ pub struct SimulationSettings {
     pub min_spawn_radius: f32,
     /// Maximum walk iterations before respawn (1000-50000)
     pub max_walk_iterations: usize,
+    /// Concurrent walkers as a fraction of grid area (0 = classic one-at-a-time, up to 0.1)
+    #[serde(default)]
+    pub walker_density: f32,
+    /// Image of non-sticky walls, stretched over the grid (None = no walls)
+    #[serde(default)]
+    pub obstacle_mask: Option<PathBuf>,
+
+    // === Species Parameters ===
+    /// Particle species (empty = a single species using the global parameters).
+    /// Eden, DBM and DLCA runs deposit only the first species
+    #[serde(default)]
+    pub species: Vec<Species>,
+    /// Stickiness matrix: row = walker species, column = species it touches
+    /// (missing entries stick with probability 1)
+    #[serde(default)]
+    pub stickiness_matrix: Vec<Vec<f32>>,
 
     // === Visual Parameters ===
     /// What property determines particle color
@@ -263,6 +580,12 @@ pub struct SimulationSettings {
     pub highlight_recent: usize,
     /// Invert color gradient
     pub invert_colors: bool,
+    /// How 3D clusters are drawn (slice, projection or rotating view)
+    #[serde(default)]
+    pub voxel_view: VoxelView,
+    /// Slice position relative to the middle of the volume (3D slice view)
+    #[serde(default)]
+    pub slice_offset: i32,
 }
 
 impl Default for SimulationSettings {
@@ -276,9 +599,19 @@ impl Default for SimulationSettings {
             adaptive_step: false, // Disabled by default for accurate DLA
             adaptive_step_factor: 3.0,
             lattice_walk: true, // Classic 4-direction lattice walk
+            lattice: LatticeType::default(), // Square cells
+            circle_jump: false,
+            off_lattice: false,
+            disc_radius: default_disc_radius(),
+            voxels: false,
+            growth_model: GrowthModel::default(), // Random-walk DLA
+            rla_stickiness: default_rla_stickiness(),
+            dbm_eta: default_dbm_eta(),
+            cluster_mobility: default_cluster_mobility(),
 
             // Sticking
             neighborhood: NeighborhoodType::default(), // VonNeumann (4-neighbor)
+            neighborhood_3d: Neighborhood3d::default(), // 6 face neighbors
             multi_contact_min: 1,
             tip_stickiness: 1.0,
             side_stickiness: 1.0,
@@ -291,15 +624,39 @@ impl Default for SimulationSettings {
             escape_multiplier: 3.0, // Higher multiplier reduces premature respawns
             min_spawn_radius: 15.0, // Lower for faster small-cluster convergence
             max_walk_iterations: 10000,
+            walker_density: 0.0, // One walker at a time (classic DLA)
+            obstacle_mask: None,
+
+            // Species - a single species
+            species: Vec::new(),
+            stickiness_matrix: Vec::new(),
 
             // Visual
             color_mode: ColorMode::default(),
             highlight_recent: 0,
             invert_colors: false,
+            voxel_view: VoxelView::default(),
+            slice_offset: 0,
         }
     }
 }
 
+fn default_disc_radius() -> f32 {
+    crate::offlattice::DEFAULT_DISC_RADIUS
+}
+
+fn default_rla_stickiness() -> f32 {
+    DEFAULT_RLA_STICKINESS
+}
+
+fn default_dbm_eta() -> f32 {
+    1.0 // eta = 1 reproduces DLA statistics
+}
+
+fn default_cluster_mobility() -> f32 {
+    0.5 // Roughly Stokes-Einstein: diffusion ~ 1 / radius for 2D fractal clusters
+}
+
 impl SimulationSettings {
     /// Adjust walk step size within bounds
     pub fn adjust_walk_step_size(&mut self, delta: f32) {
@@ -362,6 +719,11 @@ impl SimulationSettings {
         self.max_walk_iterations = new_val as usize;
     }
 
+    /// Adjust walker density within bounds
+    pub fn adjust_walker_density(&mut self, delta: f32) {
+        self.walker_density = ((self.walker_density + delta).clamp(0.0, 0.1) * 1000.0).round() / 1000.0;
+    }
+
     /// Adjust highlight recent within bounds
     pub fn adjust_highlight_recent(&mut self, delta: i32) {
         self.highlight_recent = (self.highlight_recent as i32 + delta).clamp(0, 50) as usize;
@@ -382,13 +744,141 @@ impl SimulationSettings {
         self.lattice_walk = !self.lattice_walk;
     }
 
+    /// Lattice the run actually uses (disc and voxel modes have their own geometry)
+    pub fn active_lattice(&self) -> LatticeType {
+        if self.off_lattice || self.voxels {
+            LatticeType::Square
+        } else {
+            self.lattice
+        }
+    }
+
+    /// Growth model the run actually uses (off-lattice and 3D runs only have
+    /// the random-walk models, DLA and RLA, and clusters only move on square cells)
+    pub fn active_growth_model(&self) -> GrowthModel {
+        match self.growth_model {
+            GrowthModel::Dla | GrowthModel::Rla => self.growth_model,
+            _ if self.off_lattice || self.voxels => GrowthModel::Dla,
+            GrowthModel::Dlca if self.lattice != LatticeType::Square => GrowthModel::Dla,
+            model => model,
+        }
+    }
+
+    /// Adjust the dielectric breakdown exponent within bounds
+    pub fn adjust_dbm_eta(&mut self, delta: f32) {
+        self.dbm_eta = (self.dbm_eta + delta).clamp(0.0, 5.0);
+    }
+
+    /// Adjust the cluster-cluster mobility exponent within bounds
+    pub fn adjust_cluster_mobility(&mut self, delta: f32) {
+        self.cluster_mobility = (self.cluster_mobility + delta).clamp(0.0, 2.0);
+    }
+
+    /// Toggle circle jumping on/off
+    pub fn toggle_circle_jump(&mut self) {
+        self.circle_jump = !self.circle_jump;
+    }
+
+    /// Toggle off-lattice disc mode on/off
+    pub fn toggle_off_lattice(&mut self) {
+        self.off_lattice = !self.off_lattice;
+    }
+
+    /// Toggle 3D voxel mode on/off
+    pub fn toggle_voxels(&mut self) {
+        self.voxels = !self.voxels;
+    }
+
+    /// Number of particle species (at least 1)
+    pub fn species_count(&self) -> usize {
+        self.species.len().max(1)
+    }
+
+    /// Check the settings read from a file for values the simulation can't
+    /// run with (the UI and CLI keep the others in range themselves)
+    pub fn validate(&self) -> Result<(), String> {
+        if self.species.len() > MAX_SPECIES {
+            return Err(format!("{} species, at most {} are supported", self.species.len(), MAX_SPECIES));
+        }
+        Ok(())
+    }
+
+    /// Pick a species for a new walker by the species fractions, given a
+    /// uniform `roll` in [0, 1)
+    pub fn pick_species(&self, roll: f32) -> u8 {
+        let total: f32 = self.species.iter().map(|s| s.fraction.max(0.0)).sum();
+        if total <= 0.0 {
+            return 0;
+        }
+        let mut target = roll * total;
+        for (index, species) in self.species.iter().enumerate() {
+            target -= species.fraction.max(0.0);
+            if target < 0.0 {
+                return u8::try_from(index).unwrap_or(0);
+            }
+        }
+        // Rounding left a sliver past the last fraction
+        self.species
+            .iter()
+            .rposition(|s| s.fraction > 0.0)
+            .and_then(|index| u8::try_from(index).ok())
+            .unwrap_or(0)
+    }
+
+    /// Spawn and walk parameters without any species overrides
+    pub fn motion(&self) -> Motion {
+        Motion {
+            spawn_mode: self.spawn_mode,
+            walk_step_size: self.walk_step_size,
+            walk_bias_angle: self.walk_bias_angle,
+            walk_bias_strength: self.walk_bias_strength,
+            radial_bias: self.radial_bias,
+        }
+    }
+
+    /// Spawn and walk parameters of a species
+    pub fn species_motion(&self, species: u8) -> Motion {
+        let global = self.motion();
+        match self.species.get(species as usize) {
+            Some(s) => Motion {
+                spawn_mode: s.spawn_mode.unwrap_or(global.spawn_mode),
+                walk_step_size: s.walk_step_size.unwrap_or(global.walk_step_size),
+                walk_bias_angle: s.walk_bias_angle.unwrap_or(global.walk_bias_angle),
+                walk_bias_strength: s.walk_bias_strength.unwrap_or(global.walk_bias_strength),
+                radial_bias: s.radial_bias.unwrap_or(global.radial_bias),
+            },
+            None => global,
+        }
+    }
+
+    /// Probability that a walker of species `walker` sticks to a particle of
+    /// species `neighbor` (1 where the matrix has no entry)
+    pub fn species_stickiness(&self, walker: u8, neighbor: u8) -> f32 {
+        self.stickiness_matrix
+            .get(walker as usize)
+            .and_then(|row| row.get(neighbor as usize))
+            .map_or(1.0, |p| p.clamp(0.0, 1.0))
+    }
+
+    /// Stickiness factor for a walker of species `walker` touching particles
+    /// of the `touching` species: the best matrix entry (1 without a matrix)
+    pub fn species_affinity(&self, walker: u8, touching: impl IntoIterator<Item = u8>) -> f32 {
+        if self.stickiness_matrix.is_empty() {
+            return 1.0;
+        }
+        touching
+            .into_iter()
+            .map(|neighbor| self.species_stickiness(walker, neighbor))
+            .fold(0.0, f32::max)
+    }
+
     /// Calculate effective stickiness based on neighbor count and distance
     pub fn effective_stickiness(&self, neighbor_count: usize, distance_from_center: f32, base_stickiness: f32) -> f32 {
         // Determine if this is a tip (few neighbors) or side (many neighbors)
-        let max_neighbors = match self.neighborhood {
-            NeighborhoodType::VonNeumann => 4,
-            NeighborhoodType::Moore => 8,
-            NeighborhoodType::Extended => 24,
+        let max_neighbors = if self.voxels {
+            self.neighborhood_3d.offsets().len()
+        } else {
+            self.active_lattice().neighbor_count(self.neighborhood)
         };
 
         let neighbor_ratio = neighbor_count as f32 / max_neighbors as f32;
@@ -400,7 +890,14 @@ impl SimulationSettings {
         // Apply distance gradient
         let gradient_factor = 1.0 + (distance_from_center / 100.0) * self.stickiness_gradient;
 
+        // Reaction-limited growth needs many contacts per attachment
+        let reaction_factor = if self.active_growth_model() == GrowthModel::Rla {
+            self.rla_stickiness
+        } else {
+            1.0
+        };
+
         // Combine with base stickiness
-        (base_stickiness * directional_stickiness * gradient_factor).clamp(0.0, 1.0)
+        (base_stickiness * directional_stickiness * gradient_factor * reaction_factor).clamp(0.0, 1.0)
     }
 }
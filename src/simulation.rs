@@ -1,12 +1,51 @@
-use crate::settings::{BoundaryBehavior, SimulationSettings, SpawnMode};
-use rand::rngs::ThreadRng;
-use rand::Rng;
+use crate::composite::CompositeSeed;
+use crate::dbm::PotentialField;
+use crate::distance_map::DistanceMap;
+use crate::font;
+use crate::harmonic::HarmonicMeasure;
+use crate::dlca::ClusterAggregation;
+use crate::eden::Perimeter;
+use crate::lattice::LatticeType;
+use crate::mask::{Fit, InkImage, Mask};
+use crate::offlattice::{Disc, DiscCluster};
+use crate::settings::{BoundaryBehavior, ColorMode, GrowthModel, Motion, SimulationSettings, SpawnMode, VoxelView};
+use crate::sparse_grid::{SparseGrid, TILE_SIZE};
+use crate::tree::{self, TreeExtent};
+use crate::voxel::{Voxel, VoxelGrid};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
 const BOUNDARY_MARGIN: f32 = 1.0;
 
+/// Particle cap when the grid is unbounded (no grid area to derive it from)
+const UNBOUNDED_MAX_PARTICLES: usize = 2_000_000;
+
+/// Slack subtracted from a circle jump for walker and particle offsets within their cells
+const JUMP_MARGIN: f32 = 1.5;
+
+/// Electrode radius as a multiple of the spawn radius, so the field is only rebuilt now and then
+const DBM_RADIUS_SLACK: f32 = 1.25;
+
+/// Share of the grid width and height an image seed is fitted into
+const IMAGE_SEED_FILL: f32 = 0.8;
+
+/// Spawn attempts before a walker is launched inside a wall anyway
+const SPAWN_ATTEMPTS: usize = 100;
+
+/// Odd constant mixing the particle count into the probe seed
+const PROBE_SEED_MIX: u64 = 0x9E37_79B9_7F4A_7C15;
+
+/// Launches of one growth probe before it counts as lost
+const PROBE_LAUNCHES: usize = 100;
+
+/// Unit lattice steps of a 3D walker
+const VOXEL_STEPS: [(i32, i32, i32); 6] = [(-1, 0, 0), (1, 0, 0), (0, -1, 0), (0, 1, 0), (0, 0, -1), (0, 0, 1)];
+
 /// Seed pattern types for initial structure
-#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
 pub enum SeedPattern {
     #[default]
     Point,
@@ -19,6 +58,12 @@ pub enum SeedPattern {
     Scatter,
     MultiPoint,
     Starburst,
+    /// Thresholded image, fitted to the middle of the grid
+    Image(PathBuf),
+    /// Several shapes from a seed file, placed around the middle of the grid
+    Composite(CompositeSeed),
+    /// Text in the built-in bitmap font, `scale` cells per font pixel
+    Text { string: String, scale: u32 },
 }
 
 impl SeedPattern {
@@ -34,6 +79,9 @@ impl SeedPattern {
             SeedPattern::Scatter => "Scatter",
             SeedPattern::MultiPoint => "Multi-Point",
             SeedPattern::Starburst => "Starburst",
+            SeedPattern::Image(_) => "Image",
+            SeedPattern::Composite(_) => "Composite",
+            SeedPattern::Text { .. } => "Text",
         }
     }
 
@@ -48,7 +96,9 @@ impl SeedPattern {
             SeedPattern::NoisePatch => SeedPattern::Scatter,
             SeedPattern::Scatter => SeedPattern::MultiPoint,
             SeedPattern::MultiPoint => SeedPattern::Starburst,
-            SeedPattern::Starburst => SeedPattern::Point,
+            SeedPattern::Starburst | SeedPattern::Image(_) | SeedPattern::Composite(_) | SeedPattern::Text { .. } => {
+                SeedPattern::Point
+            }
         }
     }
 
@@ -64,12 +114,159 @@ impl SeedPattern {
             SeedPattern::Scatter => SeedPattern::NoisePatch,
             SeedPattern::MultiPoint => SeedPattern::Scatter,
             SeedPattern::Starburst => SeedPattern::MultiPoint,
+            SeedPattern::Image(_) | SeedPattern::Composite(_) | SeedPattern::Text { .. } => SeedPattern::Starburst,
+        }
+    }
+
+    /// Parameters that shape this pattern, in the order the seed panel lists them
+    pub fn params(&self) -> &'static [SeedParam] {
+        use SeedParam::*;
+        match self {
+            SeedPattern::Point => &[OffsetX, OffsetY],
+            SeedPattern::Line | SeedPattern::Cross => &[Length, Thickness, Angle, OffsetX, OffsetY],
+            SeedPattern::Circle | SeedPattern::NoisePatch => &[Radius, OffsetX, OffsetY],
+            SeedPattern::Ring => &[Radius, Thickness, OffsetX, OffsetY],
+            SeedPattern::Block => &[Length, Angle, OffsetX, OffsetY],
+            SeedPattern::Scatter => &[Radius, Count, OffsetX, OffsetY],
+            SeedPattern::MultiPoint => &[Radius, Count, Angle, OffsetX, OffsetY],
+            SeedPattern::Starburst => &[Length, Spokes, Angle, OffsetX, OffsetY],
+            SeedPattern::Image(_) => &[],
+            SeedPattern::Composite(_) | SeedPattern::Text { .. } => &[Angle, OffsetX, OffsetY],
+        }
+    }
+}
+
+/// Geometry parameter of a seed pattern
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SeedParam {
+    /// Circle and ring radius, scatter radius, spread of multi-point seeds
+    Radius,
+    /// Width of lines and rings
+    Thickness,
+    /// Number of scatter or multi-point seeds
+    Count,
+    /// Number of starburst spokes
+    Spokes,
+    /// Line length, cross arm, block side or spoke length
+    Length,
+    /// Rotation in degrees
+    Angle,
+    /// Shift of the pattern from the middle of the grid, in cells
+    OffsetX,
+    OffsetY,
+}
+
+impl SeedParam {
+    pub fn name(&self) -> &str {
+        match self {
+            SeedParam::Radius => "radius",
+            SeedParam::Thickness => "thickness",
+            SeedParam::Count => "count",
+            SeedParam::Spokes => "spokes",
+            SeedParam::Length => "length",
+            SeedParam::Angle => "angle",
+            SeedParam::OffsetX => "offset x",
+            SeedParam::OffsetY => "offset y",
+        }
+    }
+
+    /// Parameter named by a `--seed` option key
+    pub fn from_key(key: &str) -> Option<SeedParam> {
+        match key {
+            "radius" | "r" => Some(SeedParam::Radius),
+            "thickness" | "width" => Some(SeedParam::Thickness),
+            "count" | "n" => Some(SeedParam::Count),
+            "spokes" => Some(SeedParam::Spokes),
+            "length" | "len" | "size" => Some(SeedParam::Length),
+            "angle" => Some(SeedParam::Angle),
+            "dx" | "offset-x" => Some(SeedParam::OffsetX),
+            "dy" | "offset-y" => Some(SeedParam::OffsetY),
+            _ => None,
+        }
+    }
+
+    /// Increment of one adjustment step in the seed panel
+    pub fn step(&self) -> f32 {
+        match self {
+            SeedParam::Radius | SeedParam::Length => 2.0,
+            SeedParam::Thickness | SeedParam::Count | SeedParam::Spokes => 1.0,
+            SeedParam::Angle => 15.0,
+            SeedParam::OffsetX | SeedParam::OffsetY => 5.0,
+        }
+    }
+
+    /// Valid range of the parameter
+    fn range(&self) -> (f32, f32) {
+        match self {
+            SeedParam::Radius => (1.0, 1000.0),
+            SeedParam::Thickness => (1.0, 100.0),
+            SeedParam::Count => (1.0, 500.0),
+            SeedParam::Spokes => (1.0, 64.0),
+            SeedParam::Length => (1.0, 2000.0),
+            SeedParam::Angle => (0.0, 360.0),
+            SeedParam::OffsetX | SeedParam::OffsetY => (-2000.0, 2000.0),
+        }
+    }
+}
+
+/// Seed geometry overrides. Unset parameters use the pattern's default,
+/// scaled to the grid; set ones are shared by every pattern that uses them.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct SeedParams {
+    #[serde(default)]
+    pub radius: Option<f32>,
+    #[serde(default)]
+    pub thickness: Option<f32>,
+    #[serde(default)]
+    pub count: Option<u32>,
+    #[serde(default)]
+    pub spokes: Option<u32>,
+    #[serde(default)]
+    pub length: Option<f32>,
+    #[serde(default)]
+    pub angle: Option<f32>,
+    #[serde(default)]
+    pub offset_x: Option<f32>,
+    #[serde(default)]
+    pub offset_y: Option<f32>,
+}
+
+impl SeedParams {
+    pub fn get(&self, param: SeedParam) -> Option<f32> {
+        match param {
+            SeedParam::Radius => self.radius,
+            SeedParam::Thickness => self.thickness,
+            SeedParam::Count => self.count.map(|c| c as f32),
+            SeedParam::Spokes => self.spokes.map(|c| c as f32),
+            SeedParam::Length => self.length,
+            SeedParam::Angle => self.angle,
+            SeedParam::OffsetX => self.offset_x,
+            SeedParam::OffsetY => self.offset_y,
+        }
+    }
+
+    /// Override a parameter (clamped to its range; angles wrap), or unset it with None
+    pub fn set(&mut self, param: SeedParam, value: Option<f32>) {
+        let (min, max) = param.range();
+        let value = value.map(|v| match param {
+            SeedParam::Angle => v.rem_euclid(360.0),
+            _ => v.clamp(min, max),
+        });
+        match param {
+            SeedParam::Radius => self.radius = value,
+            SeedParam::Thickness => self.thickness = value,
+            SeedParam::Count => self.count = value.map(|v| v.round() as u32),
+            SeedParam::Spokes => self.spokes = value.map(|v| v.round() as u32),
+            SeedParam::Length => self.length = value,
+            SeedParam::Angle => self.angle = value,
+            SeedParam::OffsetX => self.offset_x = value,
+            SeedParam::OffsetY => self.offset_y = value,
         }
     }
 }
 
 /// Additional data stored per particle for advanced color modes
-#[derive(Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
 pub struct ParticleData {
     /// Order in which particle was stuck (age)
     pub age: usize,
@@ -79,23 +276,213 @@ pub struct ParticleData {
     pub direction: f32,
     /// Number of neighbors when stuck
     pub neighbor_count: u8,
+    /// Particle species (0 in single-species runs)
+    #[serde(default)]
+    pub species: u8,
+    /// Cell of the particle this one attached to (None for seeds, and in
+    /// off-lattice, 3D and cluster-cluster runs, which don't track the tree)
+    #[serde(default)]
+    pub parent: Option<(i32, i32)>,
+    /// Attachment links between this particle and the root of its tree
+    #[serde(default)]
+    pub generation: u32,
+    /// Length of the attachment path to the root (sum of link lengths)
+    #[serde(default)]
+    pub path_length: f32,
+    /// Strahler order of the branch this particle roots (0 = not tracked)
+    #[serde(default)]
+    pub strahler: u8,
+}
+
+/// Value a particle is colored by in the given color mode (before normalization)
+fn intensity(particle: &ParticleData, mode: ColorMode) -> f32 {
+    match mode {
+        ColorMode::Age => particle.age as f32,
+        ColorMode::Distance => particle.distance,
+        ColorMode::Density => particle.neighbor_count as f32,
+        ColorMode::Direction => particle.direction,
+        ColorMode::Species => particle.species as f32,
+        ColorMode::Generation => particle.generation as f32,
+        ColorMode::PathLength => particle.path_length,
+        ColorMode::Strahler => particle.strahler as f32,
+    }
+}
+
+/// Walker position plus the offset used for its approach direction
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct WalkerPosition {
+    x: f32,
+    y: f32,
+    last_dx: f32,
+    last_dy: f32,
+    #[serde(default)]
+    species: u8,
+}
+
+/// A concurrent walker in the multi-walker engine
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Walker {
+    position: WalkerPosition,
+    /// Iterations since launch (respawned after `max_walk_iterations`)
+    iterations: usize,
+    /// Per-walker RNG stream so parallel updates stay deterministic
+    rng: ChaCha8Rng,
+}
+
+impl Walker {
+    /// Current walker position in grid coordinates
+    pub fn position(&self) -> (f32, f32) {
+        (self.position.x, self.position.y)
+    }
+}
+
+/// Walk parameters shared by every walker during one step
+struct WalkParams {
+    center_x: f32,
+    center_y: f32,
+    spawn_radius: f32,
+    escape_dist_sq: f32,
+    x_min: f32,
+    y_min: f32,
+    x_max: f32,
+    y_max: f32,
+}
+
+/// Empty cell where a walker decided to stick
+#[derive(Debug, Clone, Copy)]
+struct StickSite {
+    x: i32,
+    y: i32,
+    distance: f32,
+    direction: f32,
+    neighbor_count: usize,
+    species: u8,
+}
+
+/// Result of advancing a walker by one iteration
+#[derive(Debug, Clone, Copy)]
+enum WalkOutcome {
+    /// Still walking
+    Continue,
+    /// Sticks to the cluster at this site
+    Stick(StickSite),
+    /// Escaped, absorbed, or hit the cluster without sticking
+    Respawn,
+}
+
+/// Serializable copy of everything needed to continue a run exactly
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SimulationState {
+    pub grid_width: usize,
+    pub grid_height: usize,
+    /// Occupied cells as (row-major index into the walk domain, particle data)
+    pub cells: Vec<(usize, ParticleData)>,
+    /// Cells the walk domain has grown by on every side (unbounded boundary)
+    #[serde(default)]
+    pub expansion: usize,
+    pub particles_stuck: usize,
+    pub max_radius: f32,
+    /// Walk counters, so logged metrics continue across a resume
+    #[serde(default)]
+    pub walks: u64,
+    #[serde(default)]
+    pub escapes: u64,
+    pub seed_pattern: SeedPattern,
+    pub rng: ChaCha8Rng,
+    /// In-flight walkers of the multi-walker engine
+    #[serde(default)]
+    pub walkers: Vec<Walker>,
+    /// Disc particles of an off-lattice run
+    #[serde(default)]
+    pub discs: Vec<Disc>,
+    /// Voxels of a 3D run (the cells then only hold the 2D view)
+    #[serde(default)]
+    pub voxels: Vec<Voxel>,
+    /// Relaxed potential of a dielectric breakdown run
+    #[serde(default)]
+    pub potential: Option<PotentialField>,
+    /// Clusters of a cluster-cluster aggregation run
+    #[serde(default)]
+    pub aggregation: Option<ClusterAggregation>,
+    /// Obstacle walls over the base grid
+    #[serde(default)]
+    pub obstacles: Mask,
+    /// Geometry overrides of the seed pattern
+    #[serde(default)]
+    pub seed_params: SeedParams,
+}
+
+impl SimulationState {
+    /// Check that every saved cell lies inside the saved walk domain, so a
+    /// bad snapshot can be refused before anything is overwritten
+    pub fn validate(&self) -> Result<(), String> {
+        let domain_width = self.grid_width + 2 * self.expansion;
+        let size = domain_width * (self.grid_height + 2 * self.expansion);
+        match self.cells.iter().find(|(idx, _)| *idx >= size) {
+            Some((idx, _)) => Err(format!("Snapshot cell index {} outside {}x{} grid", idx, self.grid_width, self.grid_height)),
+            None => Ok(()),
+        }
+    }
 }
 
 /// DLA simulation state
 pub struct DlaSimulation {
+    /// Size of the initial walk domain (the grid itself is unbounded)
     pub grid_width: usize,
     pub grid_height: usize,
-    /// Grid stores particle data (None = empty, Some = particle)
-    grid: Vec<Option<ParticleData>>,
+    /// Sparse grid of stuck particles
+    grid: SparseGrid,
+    /// Cells the walk domain has grown by on every side (unbounded boundary only)
+    expansion: usize,
     pub num_particles: usize,
     pub stickiness: f32,
     pub particles_stuck: usize,
     pub max_radius: f32,
+    /// Random walks finished since the last reset (DLA, RLA, off-lattice, 3D and ballistic runs)
+    pub walks: u64,
+    /// Finished walks that didn't stick: escaped, absorbed, refused by the
+    /// cluster or out of iterations
+    pub escapes: u64,
     pub paused: bool,
     pub seed_pattern: SeedPattern,
+    /// Geometry overrides of the seed pattern
+    pub seed_params: SeedParams,
     /// Advanced simulation settings
     pub settings: SimulationSettings,
-    rng: ThreadRng,
+    /// Fixed RNG seed (None = fresh entropy on every reset)
+    pub rng_seed: Option<u64>,
+    rng: ChaCha8Rng,
+    /// Concurrent walkers (only used when walker_density > 0)
+    walkers: Vec<Walker>,
+    /// Disc particles (only used when off_lattice is on; the grid holds their raster)
+    discs: DiscCluster,
+    /// Distance from each cell to the cluster, kept in sync with the grid
+    distance_map: DistanceMap,
+    /// Voxel cluster (only used when voxels is on; the grid holds its 2D view)
+    volume: VoxelGrid,
+    /// Rotation of the 3D rotating view around the vertical axis (radians)
+    pub view_angle: f32,
+    /// View settings the grid was last drawn with (None = redraw needed)
+    view_key: Option<(VoxelView, i32, ColorMode, u32)>,
+    /// Laplace field of the dielectric breakdown model (built on its first step)
+    potential: PotentialField,
+    /// Set when the model has nowhere left to attach a particle (a ballistic
+    /// source inside the deposit, or an Eden cluster with no free perimeter),
+    /// which ends the run
+    stalled: bool,
+    /// Free cells next to the cluster for Eden growth (built on its first
+    /// step, dropped when another model grows the cluster or the domain grows)
+    perimeter: Option<Perimeter>,
+    /// Diffusing clusters of the cluster-cluster model (the grid mirrors them)
+    aggregation: ClusterAggregation,
+    /// Non-sticky walls over the base grid, rasterized from `obstacle_image`
+    obstacles: Mask,
+    /// Decoded `settings.obstacle_mask` (see `load_images`)
+    obstacle_image: InkImage,
+    /// Decoded image of the last image seed (see `load_images`)
+    seed_image: InkImage,
+    /// Largest growth-tree values on the grid
+    tree_extent: TreeExtent,
 }
 
 impl DlaSimulation {
@@ -103,15 +490,34 @@ impl DlaSimulation {
         let mut sim = Self {
             grid_width: width,
             grid_height: height,
-            grid: vec![None; width * height],
+            grid: SparseGrid::new(),
+            expansion: 0,
             num_particles: 5000,
             stickiness: 1.0,
             particles_stuck: 0,
             max_radius: 1.0,
+            walks: 0,
+            escapes: 0,
             paused: false,
             seed_pattern: SeedPattern::Point,
+            seed_params: SeedParams::default(),
             settings: SimulationSettings::default(),
-            rng: rand::thread_rng(),
+            rng_seed: None,
+            rng: ChaCha8Rng::from_entropy(),
+            walkers: Vec::new(),
+            discs: DiscCluster::default(),
+            distance_map: DistanceMap::default(),
+            volume: VoxelGrid::default(),
+            view_angle: 0.0,
+            view_key: None,
+            potential: PotentialField::default(),
+            stalled: false,
+            perimeter: None,
+            aggregation: ClusterAggregation::default(),
+            obstacles: Mask::default(),
+            obstacle_image: InkImage::default(),
+            seed_image: InkImage::default(),
+            tree_extent: TreeExtent::default(),
         };
         sim.reset();
         sim
@@ -125,399 +531,1336 @@ impl DlaSimulation {
     /// Execute one particle simulation step
     /// Returns true if simulation should continue, false if complete
     pub fn step(&mut self) -> bool {
-        if self.paused || self.particles_stuck >= self.num_particles {
+        if self.paused || self.is_complete() {
             return false;
         }
 
-        let (center_x, center_y) = self.center();
+        let stuck = self.particles_stuck;
+        if self.settings.active_growth_model() != GrowthModel::Eden {
+            // Other models grow the cluster behind the perimeter's back
+            self.perimeter = None;
+        }
+        if self.settings.voxels {
+            self.step_voxels();
+            self.record_walk(stuck);
+            return true;
+        }
 
-        // Get settings values
-        let spawn_radius_offset = self.settings.spawn_radius_offset;
-        let min_spawn_radius = self.settings.min_spawn_radius;
-        let escape_mult = self.settings.escape_multiplier;
-        let max_iterations = self.settings.max_walk_iterations;
-        let base_walk_step = self.settings.walk_step_size;
-        let adaptive_step = self.settings.adaptive_step;
-        let adaptive_factor = self.settings.adaptive_step_factor;
-        let lattice_walk = self.settings.lattice_walk;
+        if self.settings.off_lattice {
+            self.step_off_lattice();
+            self.record_walk(stuck);
+            return true;
+        }
 
-        // Spawn radius - outside the structure with proportional buffer
-        // Uses 20% extra beyond max_radius plus fixed offset for better scaling
-        let spawn_radius = (self.max_radius * 1.2 + spawn_radius_offset).max(min_spawn_radius);
+        let model = self.settings.active_growth_model();
+        if model == GrowthModel::Dbm {
+            self.step_dbm();
+            return true;
+        }
+        // Other models grow the cluster behind the field's back, so drop it
+        // when switching away from DBM
+        if !self.potential.is_empty() {
+            self.potential = PotentialField::default();
+        }
 
-        // Pre-calculate squared escape distance (avoids sqrt in hot loop)
-        let escape_dist_sq = spawn_radius * spawn_radius * escape_mult * escape_mult;
+        if model == GrowthModel::Dlca {
+            self.step_dlca();
+            return true;
+        }
+        if !self.aggregation.is_empty() {
+            // The clusters freeze where they are and the new model grows on
+            // them; they moved behind the distance map's back
+            self.aggregation = ClusterAggregation::default();
+            self.particles_stuck = self.grid.len();
+            self.rebuild_distance_map();
+        }
 
-        // Pre-calculate boundary limits
-        let x_max = self.grid_width as f32 - BOUNDARY_MARGIN - 1.0;
-        let y_max = self.grid_height as f32 - BOUNDARY_MARGIN - 1.0;
+        match model {
+            GrowthModel::Eden => {
+                self.step_eden();
+                return true;
+            }
+            GrowthModel::Ballistic => {
+                self.step_ballistic();
+                self.record_walk(stuck);
+                return true;
+            }
+            GrowthModel::Dla | GrowthModel::Rla | GrowthModel::Dbm | GrowthModel::Dlca => {}
+        }
 
-        // Spawn particle based on spawn mode
-        let (mut x, mut y) = self.spawn_particle(center_x, center_y, spawn_radius);
+        if self.settings.walker_density > 0.0 {
+            self.step_walkers();
+            return true;
+        }
 
-        // Track the approach direction for color mode
-        let mut last_dx = x - center_x;
-        let mut last_dy = y - center_y;
+        let params = self.walk_params();
 
-        // Random walk until it sticks or escapes
-        for _ in 0..max_iterations {
-            // Check if we've gone too far (using squared distance to avoid sqrt)
-            let dx = x - center_x;
-            let dy = y - center_y;
-            let dist_sq = dx * dx + dy * dy;
+        // Spawn particle based on its species' spawn mode
+        let mut walker = self.launch_walker(&params);
 
-            if dist_sq > escape_dist_sq {
-                // Escaped, restart
-                return true;
+        // Random walk until it sticks or escapes
+        let outcome = self.with_rng(|sim, rng| {
+            for _ in 0..sim.settings.max_walk_iterations {
+                match sim.advance_walker(&params, &mut walker, rng) {
+                    WalkOutcome::Continue => {}
+                    outcome => return outcome,
+                }
             }
+            WalkOutcome::Respawn
+        });
 
-            // Check if next to a stuck particle
-            let ix = x as usize;
-            let iy = y as usize;
+        if let WalkOutcome::Stick(stick) = outcome {
+            self.stick_particle(stick);
+        }
+        self.record_walk(stuck);
 
-            if ix > 0 && ix < self.grid_width - 1 && iy > 0 && iy < self.grid_height - 1 {
-                // Count neighbors using the configured neighborhood type
-                let (neighbor_count, has_neighbor) = self.count_neighbors(ix, iy);
+        true
+    }
 
-                if has_neighbor && neighbor_count >= self.settings.multi_contact_min as usize {
-                    // Calculate distance from center for stickiness gradient
-                    let distance = dist_sq.sqrt();
+    /// Run `f` with the simulation RNG borrowed out of the simulation, so a
+    /// walk can read the simulation while drawing from its RNG
+    fn with_rng<T>(&mut self, f: impl FnOnce(&Self, &mut ChaCha8Rng) -> T) -> T {
+        let mut rng = std::mem::replace(&mut self.rng, ChaCha8Rng::seed_from_u64(0));
+        let result = f(self, &mut rng);
+        self.rng = rng;
+        result
+    }
 
-                    // Calculate effective stickiness
-                    let effective_stickiness = self.settings.effective_stickiness(
-                        neighbor_count,
-                        distance,
-                        self.stickiness,
-                    );
-
-                    // Check if we should stick
-                    if self.rng.gen::<f32>() < effective_stickiness {
-                        let idx = iy * self.grid_width + ix;
-
-                        // Only stick if cell is empty - if occupied, continue walking
-                        if self.grid[idx].is_none() {
-                            // Calculate approach direction
-                            let direction = last_dy.atan2(last_dx);
-
-                            // Stick here with particle data
-                            self.grid[idx] = Some(ParticleData {
-                                age: self.particles_stuck,
-                                distance,
-                                direction,
-                                neighbor_count: neighbor_count as u8,
-                            });
-                            self.particles_stuck += 1;
-
-                            // Update max radius
-                            self.max_radius = self.max_radius.max(distance);
-
-                            return true;
-                        }
-                        // Cell occupied - particle continues walking (accurate DLA behavior)
-                    }
-                }
+    /// Count a finished walk, as an escape unless a particle stuck since `stuck`
+    fn record_walk(&mut self, stuck: usize) {
+        self.walks += 1;
+        if self.particles_stuck == stuck {
+            self.escapes += 1;
+        }
+    }
+
+    /// Launch one off-lattice disc and walk it until it sticks or escapes.
+    ///
+    /// Contact is found by exact circle intersection along each step segment;
+    /// a disc that touches the cluster without sticking rests at the contact
+    /// point and keeps walking.
+    fn step_off_lattice(&mut self) {
+        let params = self.walk_params();
+        let (center_x, center_y) = (params.center_x, params.center_y);
+        let species = self.pick_walker_species();
+        let motion = self.settings.species_motion(species);
+        let (mut x, mut y) = self.spawn_particle(motion.spawn_mode, center_x, center_y, params.spawn_radius);
+        let base_walk_step = motion.walk_step_size;
+
+        for _ in 0..self.settings.max_walk_iterations {
+            let dx = x - center_x;
+            let dy = y - center_y;
+            let dist_sq = dx * dx + dy * dy;
+            if dist_sq > params.escape_dist_sq {
+                return;
             }
 
-            // Store previous position for direction tracking
-            last_dx = x - center_x;
-            last_dy = y - center_y;
+            if let Some(radius) = self.jump_radius(&params, &motion, x, y, self.discs.contact_distance()) {
+                let angle = self.rng.gen_range(0.0..std::f32::consts::TAU);
+                x += radius * angle.cos();
+                y += radius * angle.sin();
+                continue;
+            }
 
-            // Calculate adaptive step size based on distance from cluster
-            let walk_step = if adaptive_step {
-                let dist = dist_sq.sqrt();
-                let safe_dist = (dist - self.max_radius - 2.0).max(0.0);
-                // Use large steps when far, small steps when close
-                // Minimum step is base step, maximum is base * factor
-                let adaptive = base_walk_step
-                    + (safe_dist / 10.0).min(adaptive_factor - 1.0) * base_walk_step;
-                adaptive.min(base_walk_step * adaptive_factor)
+            // Same adaptive step heuristic as the lattice engine
+            let walk_step = if self.settings.adaptive_step {
+                let adaptive_factor = self.settings.adaptive_step_factor;
+                let safe_dist = (dist_sq.sqrt() - self.max_radius - 2.0).max(0.0);
+                (base_walk_step + (safe_dist / 10.0).min(adaptive_factor - 1.0) * base_walk_step)
+                    .min(base_walk_step * adaptive_factor)
             } else {
                 base_walk_step
             };
 
-            // Calculate walk angle - either lattice (4 directions) or continuous
-            let walk_angle = if lattice_walk {
-                // Pure lattice: 4 cardinal directions only (classic Witten-Sander DLA)
-                match self.rng.gen_range(0..4) {
-                    0 => 0.0,                                    // Right
-                    1 => std::f32::consts::FRAC_PI_2,            // Up
-                    2 => std::f32::consts::PI,                   // Left
-                    _ => 3.0 * std::f32::consts::FRAC_PI_2,      // Down
-                }
-            } else {
-                // Continuous random angle with optional bias
-                let base_angle = self.rng.gen_range(0.0..std::f32::consts::TAU);
-                self.apply_walk_bias(base_angle, x, y, center_x, center_y)
-            };
-
-            // Calculate new position
-            let new_x = x + walk_step * walk_angle.cos();
-            let new_y = y + walk_step * walk_angle.sin();
-
-            // Path sampling for large steps to prevent tunneling through the cluster
-            if walk_step > 1.5 {
-                // Sample along the path using Bresenham-style stepping
-                if let Some((stick_x, stick_y, neighbor_count)) =
-                    self.sample_path_for_collision(x, y, new_x, new_y)
+            let base_angle = self.rng.gen_range(0.0..std::f32::consts::TAU);
+            let angle = Self::apply_walk_bias(&motion, base_angle, x, y, center_x, center_y);
+            let new_x = x + walk_step * angle.cos();
+            let new_y = y + walk_step * angle.sin();
+
+            if let Some(t) = self.discs.first_contact(x, y, new_x, new_y) {
+                let contact_x = x + t * (new_x - x);
+                let contact_y = y + t * (new_y - y);
+                let neighbor_count = self.discs.contacts_at(contact_x, contact_y).max(1);
+                let distance = ((contact_x - center_x).powi(2) + (contact_y - center_y).powi(2)).sqrt();
+                let effective_stickiness = self.settings.effective_stickiness(neighbor_count, distance, self.stickiness)
+                    * self
+                        .settings
+                        .species_affinity(species, self.discs.contacts(contact_x, contact_y).map(|d| d.data.species));
+
+                if neighbor_count >= self.settings.multi_contact_min as usize
+                    && self.rng.gen::<f32>() < effective_stickiness
                 {
-                    // Found occupied cell along path - try to stick at the last empty position
-                    let distance = ((stick_x - center_x).powi(2) + (stick_y - center_y).powi(2)).sqrt();
-                    let effective_stickiness = self.settings.effective_stickiness(
-                        neighbor_count,
+                    let data = ParticleData {
+                        age: self.particles_stuck,
                         distance,
-                        self.stickiness,
-                    );
-
-                    if self.rng.gen::<f32>() < effective_stickiness {
-                        let ix = stick_x as usize;
-                        let iy = stick_y as usize;
-                        if ix > 0 && ix < self.grid_width - 1 && iy > 0 && iy < self.grid_height - 1 {
-                            let idx = iy * self.grid_width + ix;
-                            if self.grid[idx].is_none() {
-                                let direction = last_dy.atan2(last_dx);
-                                self.grid[idx] = Some(ParticleData {
-                                    age: self.particles_stuck,
-                                    distance,
-                                    direction,
-                                    neighbor_count: neighbor_count as u8,
-                                });
-                                self.particles_stuck += 1;
-                                self.max_radius = self.max_radius.max(distance);
-                                return true;
-                            }
-                        }
-                    }
-                    // Didn't stick - respawn particle (don't let it continue through cluster)
-                    return true;
+                        // Approach direction, as on the lattice
+                        direction: angle,
+                        neighbor_count: neighbor_count as u8,
+                        species,
+                        ..ParticleData::default()
+                    };
+                    self.stick_disc(contact_x, contact_y, data);
+                    return;
                 }
-            }
 
-            // Check if landing position is occupied - respawn instead of walking through
-            let land_ix = new_x as usize;
-            let land_iy = new_y as usize;
-            if land_ix < self.grid_width && land_iy < self.grid_height {
-                let land_idx = land_iy * self.grid_width + land_ix;
-                if self.grid[land_idx].is_some() {
-                    // Landing on occupied cell - respawn particle
-                    return true;
-                }
+                // Rest against the cluster and keep walking
+                x = contact_x;
+                y = contact_y;
+                continue;
             }
 
-            // Take walk step
-            x = new_x;
-            y = new_y;
-
-            // Apply boundary behavior
-            (x, y) = self.apply_boundary(x, y, x_max, y_max);
-
-            // Handle absorb boundary - if we hit edge, respawn
+            (x, y) = self.apply_boundary(new_x, new_y, &params);
             if self.settings.boundary_behavior == BoundaryBehavior::Absorb
-                && (x <= BOUNDARY_MARGIN || x >= x_max || y <= BOUNDARY_MARGIN || y >= y_max)
+                && (x <= params.x_min || x >= params.x_max || y <= params.y_min || y >= params.y_max)
             {
-                return true; // Respawn
+                return;
             }
         }
-
-        true
     }
 
-    /// Spawn a particle based on the configured spawn mode
-    fn spawn_particle(&mut self, center_x: f32, center_y: f32, spawn_radius: f32) -> (f32, f32) {
-        let w = self.grid_width as f32;
-        let h = self.grid_height as f32;
+    /// Launch one 3D walker and walk it on the voxel lattice until it sticks.
+    ///
+    /// Walkers start on a spherical shell around the cluster and take unit
+    /// steps along the six axes. Outside the cluster's bounding sphere they
+    /// jump to a random point on the largest sphere that stays clear of it
+    /// (walk-on-spheres), and a walker that escapes is relaunched from the
+    /// shell, which is where a walker returning from infinity would re-enter.
+    /// Walk bias, boundary behavior, multiple walkers and the spawn and walk
+    /// settings of species don't apply in 3D.
+    fn step_voxels(&mut self) {
+        let (cx, cy, cz) = self.voxel_center();
+        let half_box = self.voxel_depth().min(self.grid_width).min(self.grid_height) as f32 / 2.0;
+        let spawn_radius = (self.max_radius * 1.2 + self.settings.spawn_radius_offset)
+            .max(self.settings.min_spawn_radius)
+            .min(half_box - 2.0);
+        let escape_radius = (spawn_radius * self.settings.escape_multiplier).min(half_box - 1.0);
+        let offsets = self.settings.neighborhood_3d.offsets();
+        let reach = self.settings.neighborhood_3d.reach();
+
+        let species = self.pick_walker_species();
+        let stuck = self.with_rng(|sim, rng| {
+            let (mut x, mut y, mut z) = Self::sphere_point(rng, (cx, cy, cz), spawn_radius);
+            for _ in 0..sim.settings.max_walk_iterations {
+                let (fx, fy, fz) = (x as f32 + 0.5 - cx, y as f32 + 0.5 - cy, z as f32 + 0.5 - cz);
+                let dist = (fx * fx + fy * fy + fz * fz).sqrt();
+                if dist > escape_radius || !sim.volume.contains(x, y, z) {
+                    (x, y, z) = Self::sphere_point(rng, (cx, cy, cz), spawn_radius);
+                    continue;
+                }
 
-        match self.settings.spawn_mode {
-            SpawnMode::Circle => {
-                let angle = self.rng.gen_range(0.0..std::f32::consts::TAU);
-                (
-                    (center_x + spawn_radius * angle.cos()).clamp(1.0, w - 2.0),
-                    (center_y + spawn_radius * angle.sin()).clamp(1.0, h - 2.0),
-                )
-            }
-            SpawnMode::Edges => {
-                // Random edge
-                match self.rng.gen_range(0..4) {
-                    0 => (self.rng.gen_range(1.0..w - 1.0), 1.0), // Top
-                    1 => (self.rng.gen_range(1.0..w - 1.0), h - 2.0), // Bottom
-                    2 => (1.0, self.rng.gen_range(1.0..h - 1.0)), // Left
-                    _ => (w - 2.0, self.rng.gen_range(1.0..h - 1.0)), // Right
+                // Clear of the cluster: jump across the free sphere (rounding costs < 1 cell)
+                let jump = dist - sim.max_radius - reach - 2.0;
+                if jump >= 2.0 {
+                    let center = (x as f32 + 0.5, y as f32 + 0.5, z as f32 + 0.5);
+                    (x, y, z) = Self::sphere_point(rng, center, jump);
+                    continue;
                 }
-            }
-            SpawnMode::Corners => {
-                match self.rng.gen_range(0..4) {
-                    0 => (1.0, 1.0),
-                    1 => (w - 2.0, 1.0),
-                    2 => (1.0, h - 2.0),
-                    _ => (w - 2.0, h - 2.0),
+
+                let neighbor_count = sim.volume.count_neighbors(x, y, z, offsets);
+                if neighbor_count > 0
+                    && neighbor_count >= sim.settings.multi_contact_min as usize
+                    && rng.gen::<f32>()
+                        < sim.settings.effective_stickiness(neighbor_count, dist, sim.stickiness)
+                            * sim.settings.species_affinity(
+                                species,
+                                offsets.iter().filter_map(|&(dx, dy, dz)| sim.volume.species_at(x + dx, y + dy, z + dz)),
+                            )
+                {
+                    return Some(Voxel {
+                        x,
+                        y,
+                        z,
+                        data: ParticleData {
+                            age: sim.particles_stuck,
+                            distance: dist,
+                            direction: fy.atan2(fx),
+                            neighbor_count: neighbor_count as u8,
+                            species,
+                            ..ParticleData::default()
+                        },
+                    });
                 }
-            }
-            SpawnMode::Random => {
-                // Random position outside spawn radius
-                loop {
-                    let x = self.rng.gen_range(1.0..w - 1.0);
-                    let y = self.rng.gen_range(1.0..h - 1.0);
-                    let dx = x - center_x;
-                    let dy = y - center_y;
-                    if dx * dx + dy * dy > spawn_radius * spawn_radius * 0.5 {
-                        return (x, y);
-                    }
+
+                // Unit lattice step; moves into the cluster are refused
+                let (dx, dy, dz) = VOXEL_STEPS[rng.gen_range(0..VOXEL_STEPS.len())];
+                if !sim.volume.is_occupied(x + dx, y + dy, z + dz) {
+                    (x, y, z) = (x + dx, y + dy, z + dz);
                 }
             }
-            SpawnMode::Top => (self.rng.gen_range(1.0..w - 1.0), 1.0),
-            SpawnMode::Bottom => (self.rng.gen_range(1.0..w - 1.0), h - 2.0),
-            SpawnMode::Left => (1.0, self.rng.gen_range(1.0..h - 1.0)),
-            SpawnMode::Right => (w - 2.0, self.rng.gen_range(1.0..h - 1.0)),
+            None
+        });
+
+        if let Some(voxel) = stuck {
+            self.stick_voxel(voxel);
         }
     }
 
-    /// Count neighbors at position using configured neighborhood type
-    fn count_neighbors(&self, ix: usize, iy: usize) -> (usize, bool) {
-        let offsets = self.settings.neighborhood.offsets();
-        let mut count = 0;
-        let mut has_any = false;
+    /// Voxel nearest to a uniformly random point on a sphere
+    fn sphere_point<R: Rng>(rng: &mut R, center: (f32, f32, f32), radius: f32) -> (i32, i32, i32) {
+        let cos_theta: f32 = rng.gen_range(-1.0..1.0);
+        let sin_theta = (1.0 - cos_theta * cos_theta).sqrt();
+        let phi = rng.gen_range(0.0..std::f32::consts::TAU);
+        (
+            (center.0 + radius * sin_theta * phi.cos()).floor() as i32,
+            (center.1 + radius * sin_theta * phi.sin()).floor() as i32,
+            (center.2 + radius * cos_theta).floor() as i32,
+        )
+    }
 
-        for &(ndx, ndy) in offsets {
-            let nx = ix as i32 + ndx;
-            let ny = iy as i32 + ndy;
-
-            if nx >= 0 && nx < self.grid_width as i32 && ny >= 0 && ny < self.grid_height as i32 {
-                let nidx = ny as usize * self.grid_width + nx as usize;
-                if self.grid[nidx].is_some() {
-                    count += 1;
-                    has_any = true;
-                }
+    /// Add a voxel to the 3D cluster and update the 2D view
+    fn stick_voxel(&mut self, voxel: Voxel) {
+        if !self.volume.insert(voxel) {
+            return;
+        }
+        self.particles_stuck += 1;
+        self.max_radius = self.max_radius.max(voxel.data.distance);
+        // Slices and projections update in place; the rotating view is redrawn
+        if self.view_key.is_some() {
+            match self.settings.voxel_view {
+                VoxelView::Slice | VoxelView::Projection => self.draw_voxel(voxel),
+                VoxelView::Rotate => self.view_key = None,
             }
         }
-
-        (count, has_any)
     }
 
-    /// Apply walk bias (directional and radial)
-    fn apply_walk_bias(&self, base_angle: f32, x: f32, y: f32, center_x: f32, center_y: f32) -> f32 {
-        let mut angle = base_angle;
+    /// Depth of the voxel box (width and height follow the grid)
+    pub fn voxel_depth(&self) -> usize {
+        self.grid_width.min(self.grid_height)
+    }
 
-        // Apply directional bias
-        if self.settings.walk_bias_strength > 0.0 {
-            let bias_angle_rad = self.settings.walk_bias_angle.to_radians();
-            let diff = (bias_angle_rad - base_angle).sin();
-            angle += self.settings.walk_bias_strength * diff;
-        }
+    fn voxel_center(&self) -> (f32, f32, f32) {
+        let (cx, cy) = self.center();
+        (cx, cy, (self.voxel_depth() / 2) as f32)
+    }
 
-        // Apply radial bias
-        if self.settings.radial_bias.abs() > 0.001 {
-            let dx = x - center_x;
-            let dy = y - center_y;
-            let radial_angle = dy.atan2(dx);
+    /// Voxels of the 3D cluster in sticking order (empty in 2D mode)
+    pub fn voxels(&self) -> &[Voxel] {
+        self.volume.voxels()
+    }
 
-            // Positive radial_bias = toward center, negative = away
-            let target_angle = if self.settings.radial_bias > 0.0 {
-                radial_angle + std::f32::consts::PI // Toward center
-            } else {
-                radial_angle // Away from center
-            };
+    /// z index of the slice shown by the slice view
+    pub fn slice_z(&self) -> i32 {
+        let depth = self.voxel_depth() as i32;
+        (depth / 2 + self.settings.slice_offset).clamp(0, depth - 1)
+    }
 
-            let diff = (target_angle - angle).sin();
-            angle += self.settings.radial_bias.abs() * diff;
-        }
+    /// Move the 3D slice (clamped to the volume)
+    pub fn adjust_slice_offset(&mut self, delta: i32) {
+        let half = self.voxel_depth() as i32 / 2;
+        self.settings.slice_offset = (self.settings.slice_offset + delta).clamp(-half, half - 1);
+    }
 
-        angle
+    /// Turn the rotating 3D view
+    pub fn rotate_view(&mut self, radians: f32) {
+        self.view_angle = (self.view_angle + radians).rem_euclid(std::f32::consts::TAU);
     }
 
-    /// Apply boundary behavior
-    fn apply_boundary(&self, mut x: f32, mut y: f32, x_max: f32, y_max: f32) -> (f32, f32) {
-        match self.settings.boundary_behavior {
-            BoundaryBehavior::Clamp => {
-                x = x.clamp(BOUNDARY_MARGIN, x_max);
-                y = y.clamp(BOUNDARY_MARGIN, y_max);
-            }
-            BoundaryBehavior::Wrap => {
-                let width = x_max - BOUNDARY_MARGIN;
-                let height = y_max - BOUNDARY_MARGIN;
-                if x < BOUNDARY_MARGIN {
-                    x += width;
-                } else if x > x_max {
-                    x -= width;
-                }
-                if y < BOUNDARY_MARGIN {
-                    y += height;
-                } else if y > y_max {
-                    y -= height;
+    /// Redraw the 2D view of a 3D cluster if the view settings changed.
+    ///
+    /// In 3D mode the grid holds what the canvas, PNG export and recorder
+    /// show: a slice, a max-intensity projection or the rotating view.
+    pub fn refresh_view(&mut self) {
+        if !self.settings.voxels {
+            return;
+        }
+        let settings = &self.settings;
+        let key = Some((settings.voxel_view, self.slice_z(), settings.color_mode, self.view_angle.to_bits()));
+        if key == self.view_key {
+            return;
+        }
+        self.view_key = key;
+        self.grid.clear();
+        match self.settings.voxel_view {
+            VoxelView::Slice | VoxelView::Projection => {
+                for i in 0..self.volume.len() {
+                    self.draw_voxel(self.volume.voxels()[i]);
                 }
             }
-            BoundaryBehavior::Bounce => {
-                if x < BOUNDARY_MARGIN {
-                    x = BOUNDARY_MARGIN + (BOUNDARY_MARGIN - x);
-                } else if x > x_max {
-                    x = x_max - (x - x_max);
-                }
-                if y < BOUNDARY_MARGIN {
-                    y = BOUNDARY_MARGIN + (BOUNDARY_MARGIN - y);
-                } else if y > y_max {
-                    y = y_max - (y - y_max);
+            VoxelView::Rotate => self.draw_rotated(),
+        }
+    }
+
+    /// Draw one voxel into the slice or projection view
+    fn draw_voxel(&mut self, voxel: Voxel) {
+        match self.settings.voxel_view {
+            VoxelView::Slice => {
+                if voxel.z == self.slice_z() {
+                    self.grid.set(voxel.x, voxel.y, voxel.data);
                 }
             }
-            BoundaryBehavior::Stick | BoundaryBehavior::Absorb => {
-                // These are handled elsewhere; just clamp for safety
-                x = x.clamp(BOUNDARY_MARGIN, x_max);
-                y = y.clamp(BOUNDARY_MARGIN, y_max);
+            VoxelView::Projection => {
+                let mode = self.settings.color_mode;
+                let brighter = self
+                    .grid
+                    .get(voxel.x, voxel.y)
+                    .is_none_or(|shown| intensity(&voxel.data, mode) >= intensity(&shown, mode));
+                if brighter {
+                    self.grid.set(voxel.x, voxel.y, voxel.data);
+                }
             }
+            VoxelView::Rotate => {}
         }
-        (x, y)
     }
 
-    /// Sample path from (x0, y0) to (x1, y1) for collisions with occupied cells.
-    /// Returns Some((last_empty_x, last_empty_y, neighbor_count)) if an occupied cell is found,
-    /// where the coordinates are the last empty cell before the collision.
-    /// Returns None if the path is clear.
-    fn sample_path_for_collision(
-        &self,
-        x0: f32,
-        y0: f32,
-        x1: f32,
-        y1: f32,
-    ) -> Option<(f32, f32, usize)> {
-        let dx = x1 - x0;
-        let dy = y1 - y0;
-        let dist = (dx * dx + dy * dy).sqrt();
-
-        if dist < 1.0 {
-            return None;
+    /// Orthographic view rotated by `view_angle` around the vertical axis,
+    /// keeping the voxel nearest to the viewer in each cell
+    fn draw_rotated(&mut self) {
+        let (cx, _, cz) = self.voxel_center();
+        let (sin, cos) = self.view_angle.sin_cos();
+        let mut nearest: HashMap<(i32, i32), (f32, ParticleData)> = HashMap::new();
+        for voxel in self.volume.voxels() {
+            let (rx, rz) = (voxel.x as f32 + 0.5 - cx, voxel.z as f32 + 0.5 - cz);
+            let screen_x = (cx + rx * cos + rz * sin).floor() as i32;
+            let depth = rz * cos - rx * sin;
+            nearest
+                .entry((screen_x, voxel.y))
+                .and_modify(|entry| {
+                    if depth < entry.0 {
+                        *entry = (depth, voxel.data);
+                    }
+                })
+                .or_insert((depth, voxel.data));
+        }
+        for ((x, y), (_, data)) in nearest {
+            self.grid.set(x, y, data);
         }
+    }
 
-        // Number of samples along the path (at least 1 per unit distance)
-        let num_samples = dist.ceil() as usize;
-        let mut last_empty_x = x0;
-        let mut last_empty_y = y0;
+    /// Add a disc to the off-lattice cluster and rasterize it into the grid
+    fn stick_disc(&mut self, x: f32, y: f32, data: ParticleData) {
+        let disc = Disc { x, y, data };
+        // Cells shared with touching discs keep the disc that got there first;
+        // a disc hanging over the domain edge is only drawn inside it
+        let (x0, y0, x1, y1) = self.bounds();
+        for (ix, iy) in disc.cells(self.discs.radius) {
+            if ix >= x0 && iy >= y0 && ix < x1 && iy < y1 && !self.grid.is_occupied(ix, iy) {
+                self.grid.set(ix, iy, data);
+                self.distance_map.insert(ix, iy);
+            }
+        }
+        self.discs.insert(disc);
+        self.particles_stuck += 1;
+        self.max_radius = self.max_radius.max(data.distance);
+        self.grow_domain();
+    }
 
-        for i in 1..=num_samples {
-            let t = i as f32 / num_samples as f32;
-            let sample_x = x0 + t * dx;
-            let sample_y = y0 + t * dy;
+    /// Disc particles of the off-lattice cluster (empty in lattice mode)
+    pub fn discs(&self) -> &[Disc] {
+        self.discs.discs()
+    }
 
-            let ix = sample_x as usize;
-            let iy = sample_y as usize;
+    /// Per-step walk parameters derived from settings and the current cluster radius
+    fn walk_params(&self) -> WalkParams {
+        let (center_x, center_y) = self.center();
 
-            // Bounds check
-            if ix >= self.grid_width || iy >= self.grid_height {
-                continue;
+        // Spawn radius - outside the structure with proportional buffer
+        // Uses 20% extra beyond max_radius plus fixed offset for better scaling
+        let spawn_radius = (self.max_radius * 1.2 + self.settings.spawn_radius_offset)
+            .max(self.settings.min_spawn_radius);
+
+        // Pre-calculate squared escape distance (avoids sqrt in hot loop)
+        let escape_mult = self.settings.escape_multiplier;
+        let escape_dist_sq = spawn_radius * spawn_radius * escape_mult * escape_mult;
+
+        // Pre-calculate boundary limits
+        let (min_x, min_y, max_x, max_y) = self.bounds();
+        WalkParams {
+            center_x,
+            center_y,
+            spawn_radius,
+            escape_dist_sq,
+            x_min: min_x as f32 + BOUNDARY_MARGIN,
+            y_min: min_y as f32 + BOUNDARY_MARGIN,
+            x_max: max_x as f32 - BOUNDARY_MARGIN - 1.0,
+            y_max: max_y as f32 - BOUNDARY_MARGIN - 1.0,
+        }
+    }
+
+    /// Attach one cell by the dielectric breakdown model: relax the Laplace field
+    /// around the cluster, then pick a perimeter cell with probability
+    /// proportional to its potential (the local field strength) to the power eta
+    fn step_dbm(&mut self) {
+        let lattice = self.settings.active_lattice();
+        let (center_x, center_y) = self.center();
+        let bounds = self.bounds();
+        let spawn_radius = self.walk_params().spawn_radius;
+        if !self.potential.matches(lattice, (center_x, center_y), bounds) || spawn_radius > self.potential.radius() {
+            self.potential
+                .rebuild(lattice, (center_x, center_y), spawn_radius * DBM_RADIUS_SLACK, bounds, &self.grid);
+        }
+
+        let mut sites = self.potential.growth_sites(self.settings.neighborhood);
+        sites.retain(|site| !self.is_obstacle(site.col, site.row));
+        let eta = self.settings.dbm_eta as f64;
+        let weights: Vec<f64> = sites.iter().map(|s| (s.potential as f64).powf(eta)).collect();
+        let total: f64 = weights.iter().sum();
+        if total <= 0.0 {
+            // Walled in: no site the field reaches can attach any more
+            self.stalled = true;
+            return;
+        }
+        let mut pick = self.rng.gen::<f64>() * total;
+        let mut chosen = sites[sites.len() - 1];
+        for (site, weight) in sites.iter().zip(&weights) {
+            pick -= weight;
+            if pick < 0.0 {
+                chosen = *site;
+                break;
             }
+        }
+
+        let (x, y) = lattice.cell_center(chosen.col, chosen.row);
+        let (dx, dy) = (x - center_x, y - center_y);
+        self.stick_particle(StickSite {
+            x: chosen.col,
+            y: chosen.row,
+            distance: (dx * dx + dy * dy).sqrt(),
+            direction: dy.atan2(dx),
+            neighbor_count: chosen.neighbors,
+            species: 0,
+        });
+        self.potential.insert(chosen.col, chosen.row);
+    }
+
+    /// Attach one cell by Eden growth: a uniformly random empty cell next to the cluster
+    fn step_eden(&mut self) {
+        let mut perimeter = self.perimeter.take().unwrap_or_else(|| self.scan_perimeter());
+        if perimeter.is_empty() {
+            // Walled in or filled up: nothing can attach any more
+            self.stalled = true;
+            self.perimeter = Some(perimeter);
+            return;
+        }
+
+        let (ix, iy) = perimeter.get(self.rng.gen_range(0..perimeter.len()));
+        let (neighbor_count, _) = self.count_neighbors(ix, iy);
+        let (center_x, center_y) = self.center();
+        let (x, y) = self.settings.active_lattice().cell_center(ix, iy);
+        let (dx, dy) = (x - center_x, y - center_y);
+        let expansion = self.expansion;
+        self.stick_particle(StickSite {
+            x: ix,
+            y: iy,
+            distance: (dx * dx + dy * dy).sqrt(),
+            direction: dy.atan2(dx),
+            neighbor_count,
+            species: 0,
+        });
+        if self.expansion != expansion {
+            // Cells of the new domain edge join on the next scan
+            return;
+        }
+
+        // The new cell leaves the perimeter and its free neighbors join it
+        perimeter.remove(ix, iy);
+        let offsets = self
+            .settings
+            .active_lattice()
+            .neighbor_offsets(ix, iy, self.settings.neighborhood);
+        for &(dx, dy) in offsets {
+            if self.is_growth_site(ix + dx, iy + dy) {
+                perimeter.insert(ix + dx, iy + dy);
+            }
+        }
+        self.perimeter = Some(perimeter);
+    }
+
+    /// Find every free cell next to the cluster
+    fn scan_perimeter(&self) -> Perimeter {
+        let mut perimeter = Perimeter::new();
+        let Some((min_x, min_y, max_x, max_y)) = self.grid.bounds() else {
+            return perimeter;
+        };
+        // Every neighborhood reaches at most two cells past the cluster's bounding box
+        for iy in min_y - 2..=max_y + 2 {
+            for ix in min_x - 2..=max_x + 2 {
+                if self.is_growth_site(ix, iy) && self.count_neighbors(ix, iy).1 {
+                    perimeter.insert(ix, iy);
+                }
+            }
+        }
+        perimeter
+    }
+
+    /// Whether an Eden cell may attach at lattice cell (ix, iy): free, clear
+    /// of walls and inside the walk domain
+    fn is_growth_site(&self, ix: i32, iy: i32) -> bool {
+        let (x0, y0, x1, y1) = self.bounds();
+        let (x, y) = self.settings.active_lattice().cell_center(ix, iy);
+        !self.grid.is_occupied(ix, iy)
+            && !self.is_obstacle(ix, iy)
+            && x >= x0 as f32
+            && y >= y0 as f32
+            && x < x1 as f32
+            && y < y1 as f32
+    }
+
+    /// Launch one particle on a straight line from the spawn source and stick
+    /// it where it first touches the cluster (ballistic aggregation).
+    ///
+    /// Edge sources rain particles straight across the grid. Other sources aim
+    /// at the cluster with a uniformly random miss distance, so the flux onto
+    /// the cluster is uniform across its width.
+    fn step_ballistic(&mut self) {
+        let params = self.walk_params();
+        let (center_x, center_y) = (params.center_x, params.center_y);
+        let species = self.pick_walker_species();
+        let spawn_mode = self.settings.species_motion(species).spawn_mode;
+        let (mut x, mut y) = self.spawn_particle(spawn_mode, center_x, center_y, params.spawn_radius);
+        let (dir_x, dir_y) = match spawn_mode {
+            SpawnMode::Top => (0.0, 1.0),
+            SpawnMode::Bottom => (0.0, -1.0),
+            SpawnMode::Left => (1.0, 0.0),
+            SpawnMode::Right => (-1.0, 0.0),
+            _ => {
+                let (dx, dy) = (center_x - x, center_y - y);
+                let len = (dx * dx + dy * dy).sqrt().max(f32::EPSILON);
+                let miss_range = self.max_radius + 2.0;
+                let miss = self.rng.gen_range(-miss_range..=miss_range);
+                let (tx, ty) = (center_x - dy / len * miss, center_y + dx / len * miss);
+                let (dx, dy) = (tx - x, ty - y);
+                let len = (dx * dx + dy * dy).sqrt().max(f32::EPSILON);
+                (dx / len, dy / len)
+            }
+        };
+
+        // Half-cell samples along the line, checking each new cell once
+        let lattice = self.settings.active_lattice();
+        let sample = 0.5 * lattice.spacing();
+        let (spawn_x, spawn_y) = lattice.cell_at(x, y);
+        if self.grid.is_occupied(spawn_x, spawn_y) {
+            // The deposit has grown up to the spawn edge
+            self.stalled = true;
+            return;
+        }
+        let mut last_cell = None;
+        while x >= params.x_min && x <= params.x_max && y >= params.y_min && y <= params.y_max {
+            let (ix, iy) = lattice.cell_at(x, y);
+            if self.grid.is_occupied(ix, iy) || self.obstacles.contains_point(x, y) {
+                // Hit the cluster without sticking, or a wall: the particle is lost
+                return;
+            }
+            if last_cell != Some((ix, iy)) {
+                last_cell = Some((ix, iy));
+                let (neighbor_count, has_neighbor) = self.count_neighbors(ix, iy);
+                if has_neighbor && neighbor_count >= self.settings.multi_contact_min as usize {
+                    let (dx, dy) = (x - center_x, y - center_y);
+                    let distance = (dx * dx + dy * dy).sqrt();
+                    let stickiness = self.settings.effective_stickiness(neighbor_count, distance, self.stickiness)
+                        * self.species_affinity(species, ix, iy);
+                    if self.rng.gen::<f32>() < stickiness {
+                        self.stick_particle(StickSite {
+                            x: ix,
+                            y: iy,
+                            distance,
+                            direction: dy.atan2(dx),
+                            neighbor_count,
+                            species,
+                        });
+                        return;
+                    }
+                }
+            }
+            x += dir_x * sample;
+            y += dir_y * sample;
+        }
+    }
 
-            let idx = iy * self.grid_width + ix;
+    /// Let every cluster of a cluster-cluster run take about one step
+    fn step_dlca(&mut self) {
+        let periodic = self.settings.boundary_behavior == BoundaryBehavior::Wrap;
+        if !self.aggregation.matches(self.grid_width, self.grid_height, periodic) {
+            self.start_aggregation();
+        }
+        self.aggregation.sweep(
+            &mut self.grid,
+            &mut self.rng,
+            self.settings.cluster_mobility,
+            self.settings.neighborhood,
+        );
+        self.particles_stuck = self.aggregated();
+    }
+
+    /// Start a cluster-cluster run: the cells already on the grid become the
+    /// first clusters and monomers are scattered over the free cells of the
+    /// base grid until there are `num_particles` particles in all.
+    ///
+    /// The grid's edges are walls, or wrap around with the Wrap boundary.
+    fn start_aggregation(&mut self) {
+        let (width, height) = (self.grid_width, self.grid_height);
+        let periodic = self.settings.boundary_behavior == BoundaryBehavior::Wrap;
+        let neighborhood = self.settings.neighborhood;
+        let mut aggregation = ClusterAggregation::new(width, height, periodic);
+        let existing: Vec<_> = self.grid.iter().collect();
+        for (x, y, data) in existing {
+            if !aggregation.add(x, y, data, neighborhood) {
+                // Outside the box (an unbounded run that has grown)
+                self.grid.remove(x, y);
+            }
+        }
+
+        // Leave room to move: at most half the cells start occupied
+        let target = self.num_particles.min(width * height / 2);
+        let (center_x, center_y) = self.center();
+        let mut attempts = 0;
+        while aggregation.particle_count() < target && attempts < 20 * target {
+            attempts += 1;
+            let (x, y) = (self.rng.gen_range(0..width as i32), self.rng.gen_range(0..height as i32));
+            let (dx, dy) = (x as f32 + 0.5 - center_x, y as f32 + 0.5 - center_y);
+            let data = ParticleData {
+                distance: (dx * dx + dy * dy).sqrt(),
+                ..ParticleData::default()
+            };
+            if aggregation.add(x, y, data, neighborhood) {
+                self.grid.set(x, y, data);
+            }
+        }
+        self.aggregation = aggregation;
+        self.particles_stuck = self.aggregated();
+    }
+
+    /// Progress of a cluster-cluster run: each merge adds one, so this reaches
+    /// the particle count when a single cluster is left
+    fn aggregated(&self) -> usize {
+        self.aggregation.particle_count() + 1 - self.aggregation.cluster_count()
+    }
+
+    /// Clusters of a cluster-cluster run (empty for other models)
+    pub fn aggregation(&self) -> &ClusterAggregation {
+        &self.aggregation
+    }
+
+    /// Advance a walker by one random-walk iteration without modifying the grid
+    fn advance_walker<R: Rng>(&self, params: &WalkParams, walker: &mut WalkerPosition, rng: &mut R) -> WalkOutcome {
+        let (center_x, center_y) = (params.center_x, params.center_y);
+        let (x, y) = (walker.x, walker.y);
+        let motion = self.settings.species_motion(walker.species);
+
+        // Check if we've gone too far (using squared distance to avoid sqrt)
+        let dx = x - center_x;
+        let dy = y - center_y;
+        let dist_sq = dx * dx + dy * dy;
+
+        if dist_sq > params.escape_dist_sq {
+            // Escaped, restart
+            return WalkOutcome::Respawn;
+        }
+
+        // Check if next to a stuck particle
+        let lattice = self.settings.active_lattice();
+        let (ix, iy) = lattice.cell_at(x, y);
+
+        // Count neighbors using the configured neighborhood type
+        let (neighbor_count, has_neighbor) = self.count_neighbors(ix, iy);
+
+        if has_neighbor && neighbor_count >= self.settings.multi_contact_min as usize {
+            // Calculate distance from center for stickiness gradient
+            let distance = dist_sq.sqrt();
+
+            // Calculate effective stickiness
+            let effective_stickiness = self.settings.effective_stickiness(
+                neighbor_count,
+                distance,
+                self.stickiness,
+            ) * self.species_affinity(walker.species, ix, iy);
+
+            // Check if we should stick
+            // Only stick if cell is empty - if occupied, continue walking (accurate DLA behavior)
+            if rng.gen::<f32>() < effective_stickiness && !self.grid.is_occupied(ix, iy) {
+                return WalkOutcome::Stick(StickSite {
+                    x: ix,
+                    y: iy,
+                    distance,
+                    // Calculate approach direction
+                    direction: walker.last_dy.atan2(walker.last_dx),
+                    neighbor_count,
+                    species: walker.species,
+                });
+            }
+        }
+
+        // Store previous position for direction tracking
+        walker.last_dx = dx;
+        walker.last_dy = dy;
+
+        // Far from the cluster: jump straight to the edge of the free circle.
+        // Lattice walkers land on the nearest lattice site, which can be up to
+        // half a cell diagonal closer to the cluster
+        let snap = if self.settings.lattice_walk { std::f32::consts::FRAC_1_SQRT_2 } else { 0.0 };
+        if let Some(radius) = self.jump_radius(params, &motion, x, y, self.settings.neighborhood.reach() + snap) {
+            let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+            (walker.x, walker.y) = (x + radius * angle.cos(), y + radius * angle.sin());
+            if self.settings.lattice_walk {
+                let (col, row) = lattice.cell_at(walker.x, walker.y);
+                (walker.x, walker.y) = lattice.cell_center(col, row);
+            }
+            return WalkOutcome::Continue;
+        }
+
+        // Calculate adaptive step size based on distance from cluster
+        let base_walk_step = motion.walk_step_size;
+        let walk_step = if self.settings.adaptive_step {
+            let adaptive_factor = self.settings.adaptive_step_factor;
+            let dist = dist_sq.sqrt();
+            let safe_dist = (dist - self.max_radius - 2.0).max(0.0);
+            // Use large steps when far, small steps when close
+            // Minimum step is base step, maximum is base * factor
+            let adaptive = base_walk_step
+                + (safe_dist / 10.0).min(adaptive_factor - 1.0) * base_walk_step;
+            adaptive.min(base_walk_step * adaptive_factor)
+        } else {
+            base_walk_step
+        };
+
+        // Calculate the next position - a lattice move or a step at some angle
+        let (new_x, new_y, step_len) = if self.settings.lattice_walk && lattice != LatticeType::Square {
+            Self::lattice_move(lattice, ix, iy, walk_step, rng)
+        } else {
+            let walk_angle = if self.settings.lattice_walk {
+                // Pure lattice: 4 cardinal directions only (classic Witten-Sander DLA)
+                match rng.gen_range(0..4) {
+                    0 => 0.0,                                    // Right
+                    1 => std::f32::consts::FRAC_PI_2,            // Up
+                    2 => std::f32::consts::PI,                   // Left
+                    _ => 3.0 * std::f32::consts::FRAC_PI_2,      // Down
+                }
+            } else {
+                // Continuous random angle with optional bias
+                let base_angle = rng.gen_range(0.0..std::f32::consts::TAU);
+                Self::apply_walk_bias(&motion, base_angle, x, y, center_x, center_y)
+            };
+            (x + walk_step * walk_angle.cos(), y + walk_step * walk_angle.sin(), walk_step)
+        };
+
+        // Walls reflect the walker: a move into or across one is undone
+        if self.hits_obstacle(x, y, new_x, new_y) {
+            return WalkOutcome::Continue;
+        }
+
+        // Path sampling for large steps to prevent tunneling through the cluster
+        if step_len > 1.5 {
+            // Sample along the path using Bresenham-style stepping
+            if let Some((stick_x, stick_y, neighbor_count)) =
+                self.sample_path_for_collision(x, y, new_x, new_y)
+            {
+                // Found occupied cell along path - try to stick at the last empty position
+                let distance = ((stick_x - center_x).powi(2) + (stick_y - center_y).powi(2)).sqrt();
+                let (ix, iy) = lattice.cell_at(stick_x, stick_y);
+                let effective_stickiness = self.settings.effective_stickiness(
+                    neighbor_count,
+                    distance,
+                    self.stickiness,
+                ) * self.species_affinity(walker.species, ix, iy);
+
+                if rng.gen::<f32>() < effective_stickiness {
+                    // The last empty sample can sit diagonally off the cluster
+                    if neighbor_count > 0 && !self.grid.is_occupied(ix, iy) {
+                        return WalkOutcome::Stick(StickSite {
+                            x: ix,
+                            y: iy,
+                            distance,
+                            direction: walker.last_dy.atan2(walker.last_dx),
+                            neighbor_count,
+                            species: walker.species,
+                        });
+                    }
+                }
+                // Didn't stick - respawn particle (don't let it continue through cluster)
+                return WalkOutcome::Respawn;
+            }
+        }
 
-            if self.grid[idx].is_some() {
+        // Check if landing position is occupied - respawn instead of walking through
+        let (new_ix, new_iy) = lattice.cell_at(new_x, new_y);
+        if self.grid.is_occupied(new_ix, new_iy) {
+            // Landing on occupied cell - respawn particle
+            return WalkOutcome::Respawn;
+        }
+
+        // Take walk step and apply boundary behavior (wrapping can land in a wall)
+        let (bx, by) = self.apply_boundary(new_x, new_y, params);
+        if self.obstacles.contains_point(bx, by) {
+            return WalkOutcome::Continue;
+        }
+        (walker.x, walker.y) = (bx, by);
+
+        // Handle absorb boundary - if we hit edge, respawn
+        if self.settings.boundary_behavior == BoundaryBehavior::Absorb
+            && (walker.x <= params.x_min
+                || walker.x >= params.x_max
+                || walker.y <= params.y_min
+                || walker.y >= params.y_max)
+        {
+            return WalkOutcome::Respawn;
+        }
+
+        WalkOutcome::Continue
+    }
+
+    /// Lattice walk on hexagonal or triangular cells, as (x, y, step length).
+    ///
+    /// Hex walkers move from their cell center `walk_step` cells in one of six
+    /// directions, which lands on another center. Triangle centers don't line
+    /// up that way, so triangular walkers always hop to an edge neighbor.
+    fn lattice_move<R: Rng>(lattice: LatticeType, col: i32, row: i32, walk_step: f32, rng: &mut R) -> (f32, f32, f32) {
+        let (cx, cy) = lattice.cell_center(col, row);
+        if lattice == LatticeType::Hexagonal {
+            let angle = rng.gen_range(0..6) as f32 * std::f32::consts::FRAC_PI_3;
+            let len = walk_step.round().max(1.0) * lattice.spacing();
+            return (cx + len * angle.cos(), cy + len * angle.sin(), len);
+        }
+        let offsets = lattice.neighbor_offsets(col, row, Default::default());
+        let (dx, dy) = offsets[rng.gen_range(0..offsets.len())];
+        let (nx, ny) = lattice.cell_center(col + dx, row + dy);
+        (nx, ny, lattice.spacing())
+    }
+
+    /// Radius of a safe circle jump from (x, y), or None to take a normal step.
+    ///
+    /// An unbiased walk started at the center of an empty circle leaves it at a
+    /// uniformly random point, so jumping there directly samples the same
+    /// process. The circle must stay clear of anything within `reach` of the
+    /// cluster, of the grid edges and of the escape radius.
+    fn jump_radius(&self, params: &WalkParams, motion: &Motion, x: f32, y: f32, reach: f32) -> Option<f32> {
+        // Biased walks don't exit circles uniformly, and the distance map
+        // measures square cells
+        if !self.settings.circle_jump || motion.is_biased() || self.settings.active_lattice() != LatticeType::Square
+        {
+            return None;
+        }
+
+        let dist = ((x - params.center_x).powi(2) + (y - params.center_y).powi(2)).sqrt();
+        let mut radius = (self.cluster_distance(x, y) - reach - JUMP_MARGIN).min(params.escape_dist_sq.sqrt() - dist);
+        if self.settings.boundary_behavior != BoundaryBehavior::Unbounded {
+            radius = radius
+                .min(x - params.x_min)
+                .min(params.x_max - x)
+                .min(y - params.y_min)
+                .min(params.y_max - y);
+        }
+
+        // Short jumps aren't worth it; plain steps are just as cheap
+        (radius >= 2.0 * motion.walk_step_size).then_some(radius)
+    }
+
+    /// Lower bound on the distance from (x, y) to the nearest particle or wall center
+    fn cluster_distance(&self, x: f32, y: f32) -> f32 {
+        let mapped = self.distance_map.distance_at(x.floor() as i32, y.floor() as i32);
+        // Outside the map (or beyond its cap) the cluster's bounding box still bounds it
+        let boxed = match self.grid.bounds() {
+            Some((x0, y0, x1, y1)) => {
+                let dx = (x0 as f32 + 0.5 - x).max(x - x1 as f32 - 0.5).max(0.0);
+                let dy = (y0 as f32 + 0.5 - y).max(y - y1 as f32 - 0.5).max(0.0);
+                (dx * dx + dy * dy).sqrt()
+            }
+            None => f32::MAX,
+        };
+        // The box says nothing about walls, which the map includes
+        if self.obstacles.is_empty() {
+            mapped.max(boxed)
+        } else {
+            mapped
+        }
+    }
+
+    /// Add a particle to the cluster at a site found by `advance_walker`
+    fn stick_particle(&mut self, site: StickSite) {
+        let mut data = ParticleData {
+            age: self.particles_stuck,
+            distance: site.distance,
+            direction: site.direction,
+            neighbor_count: site.neighbor_count as u8,
+            species: site.species,
+            ..ParticleData::default()
+        };
+        let lattice = self.settings.active_lattice();
+        tree::link(&self.grid, lattice, self.settings.neighborhood, site.x, site.y, &mut data);
+        self.grid.set(site.x, site.y, data);
+        self.tree_extent.include(&data);
+        if let Some((px, py)) = data.parent {
+            let order = tree::update_strahler(&mut self.grid, lattice, px, py);
+            self.tree_extent.strahler = self.tree_extent.strahler.max(order);
+        }
+        self.distance_map.insert(site.x, site.y);
+        self.particles_stuck += 1;
+
+        // Update max radius
+        self.max_radius = self.max_radius.max(site.distance);
+        self.grow_domain();
+    }
+
+    /// Half-open bounds (min_x, min_y, max_x, max_y) of the current walk domain
+    pub fn bounds(&self) -> (i32, i32, i32, i32) {
+        let e = self.expansion as i32;
+        (-e, -e, self.grid_width as i32 + e, self.grid_height as i32 + e)
+    }
+
+    /// Grow the walk domain so it keeps containing the spawn circle.
+    ///
+    /// Only the unbounded boundary grows; the domain expands by whole tiles on
+    /// every side (at least half again each time, so growth is amortized) and
+    /// existing particles and walkers keep their coordinates.
+    fn grow_domain(&mut self) {
+        if !self.settings.boundary_behavior.grows() {
+            return;
+        }
+        let spawn_radius = self.walk_params().spawn_radius;
+        let half_extent = self.grid_width.min(self.grid_height) as f32 / 2.0;
+        let needed = (spawn_radius + TILE_SIZE as f32 / 2.0 - half_extent).ceil().max(0.0) as usize;
+        if needed <= self.expansion {
+            return;
+        }
+        let tile = TILE_SIZE as usize;
+        let grown = needed.max(self.expansion + self.expansion / 2);
+        self.expansion = grown.div_ceil(tile) * tile;
+    }
+
+    fn rebuild_distance_map(&mut self) {
+        self.distance_map.rebuild(&self.grid, self.obstacles.iter());
+    }
+
+    /// Number of concurrent walkers for the current walker density
+    pub fn walker_count(&self) -> usize {
+        let area = (self.grid_width * self.grid_height) as f32;
+        ((area * self.settings.walker_density).round() as usize).max(1)
+    }
+
+    /// Active walkers of the multi-walker engine (empty in single-walker mode)
+    pub fn walkers(&self) -> &[Walker] {
+        &self.walkers
+    }
+
+    /// Pick a species for a new particle by the species fractions
+    fn pick_walker_species(&mut self) -> u8 {
+        // Single-species runs leave the RNG stream as it always was
+        if self.settings.species.is_empty() {
+            0
+        } else {
+            self.settings.pick_species(self.rng.gen())
+        }
+    }
+
+    /// Pick a species for a new walker and place it at that species' spawn source
+    fn launch_walker(&mut self, params: &WalkParams) -> WalkerPosition {
+        let species = self.pick_walker_species();
+        let spawn_mode = self.settings.species_motion(species).spawn_mode;
+        let (mut x, mut y) = self.spawn_particle(spawn_mode, params.center_x, params.center_y, params.spawn_radius);
+        // Spawn sources may cross walls; try again outside them
+        for _ in 1..SPAWN_ATTEMPTS {
+            if !self.obstacles.contains_point(x, y) {
+                break;
+            }
+            (x, y) = self.spawn_particle(spawn_mode, params.center_x, params.center_y, params.spawn_radius);
+        }
+        WalkerPosition {
+            x,
+            y,
+            // Track the approach direction for color mode
+            last_dx: x - params.center_x,
+            last_dy: y - params.center_y,
+            species,
+        }
+    }
+
+    /// Spawn a walker with its own RNG stream split off the simulation RNG
+    fn spawn_walker(&mut self, params: &WalkParams) -> Walker {
+        let position = self.launch_walker(params);
+        Walker {
+            position,
+            iterations: 0,
+            rng: ChaCha8Rng::seed_from_u64(self.rng.gen()),
+        }
+    }
+
+    /// Re-launch a walker from the spawn source, keeping its RNG stream
+    fn respawn_walker(&mut self, walker: &mut Walker) {
+        let params = self.walk_params();
+        walker.position = self.launch_walker(&params);
+        walker.iterations = 0;
+    }
+
+    /// Advance every walker by one iteration in lockstep.
+    ///
+    /// Moves are computed against the grid as it was at the start of the tick
+    /// (in parallel with the `parallel` feature), then committed in walker order.
+    /// Conflicts are resolved deterministically: the lower walker index wins a
+    /// contested cell and the loser is respawned, so results do not depend on
+    /// thread scheduling.
+    fn step_walkers(&mut self) {
+        let params = self.walk_params();
+
+        // Match the pool to the current density (density can change live)
+        let target = self.walker_count();
+        let mut walkers = std::mem::take(&mut self.walkers);
+        walkers.truncate(target);
+        while walkers.len() < target {
+            let walker = self.spawn_walker(&params);
+            walkers.push(walker);
+        }
+
+        let max_iterations = self.settings.max_walk_iterations;
+        let advance = |walker: &mut Walker| {
+            walker.iterations += 1;
+            if walker.iterations > max_iterations {
+                return WalkOutcome::Respawn;
+            }
+            self.advance_walker(&params, &mut walker.position, &mut walker.rng)
+        };
+
+        #[cfg(feature = "parallel")]
+        let outcomes: Vec<WalkOutcome> = {
+            use rayon::prelude::*;
+            walkers.par_iter_mut().map(advance).collect()
+        };
+        #[cfg(not(feature = "parallel"))]
+        let outcomes: Vec<WalkOutcome> = walkers.iter_mut().map(advance).collect();
+
+        for (walker, outcome) in walkers.iter_mut().zip(outcomes) {
+            match outcome {
+                WalkOutcome::Continue => {
+                    // Cell filled by an earlier walker this tick - respawn instead of overlapping
+                    let (ix, iy) = self.settings.active_lattice().cell_at(walker.position.x, walker.position.y);
+                    if self.grid.is_occupied(ix, iy) {
+                        self.record_walk(self.particles_stuck);
+                        self.respawn_walker(walker);
+                    }
+                }
+                WalkOutcome::Stick(site) => {
+                    let stuck = self.particles_stuck;
+                    if !self.grid.is_occupied(site.x, site.y) && !self.is_complete() {
+                        // Neighbor count may have grown this tick; record the current one
+                        let (neighbor_count, _) = self.count_neighbors(site.x, site.y);
+                        self.stick_particle(StickSite { neighbor_count, ..site });
+                    }
+                    self.record_walk(stuck);
+                    self.respawn_walker(walker);
+                }
+                WalkOutcome::Respawn => {
+                    self.record_walk(self.particles_stuck);
+                    self.respawn_walker(walker);
+                }
+            }
+        }
+
+        self.walkers = walkers;
+    }
+
+    /// Spawn a particle based on a spawn mode
+    fn spawn_particle(&mut self, mode: SpawnMode, center_x: f32, center_y: f32, spawn_radius: f32) -> (f32, f32) {
+        // Domain edges: (x0, y0) is the first cell inside the margin, (w, h) one past the last cell
+        let (min_x, min_y, max_x, max_y) = self.bounds();
+        let (x0, y0) = (min_x as f32 + 1.0, min_y as f32 + 1.0);
+        let (w, h) = (max_x as f32, max_y as f32);
+
+        match mode {
+            SpawnMode::Circle => {
+                let angle = self.rng.gen_range(0.0..std::f32::consts::TAU);
+                (
+                    (center_x + spawn_radius * angle.cos()).clamp(x0, w - 2.0),
+                    (center_y + spawn_radius * angle.sin()).clamp(y0, h - 2.0),
+                )
+            }
+            SpawnMode::Edges => {
+                // Random edge
+                match self.rng.gen_range(0..4) {
+                    0 => (self.rng.gen_range(x0..w - 1.0), y0), // Top
+                    1 => (self.rng.gen_range(x0..w - 1.0), h - 2.0), // Bottom
+                    2 => (x0, self.rng.gen_range(y0..h - 1.0)), // Left
+                    _ => (w - 2.0, self.rng.gen_range(y0..h - 1.0)), // Right
+                }
+            }
+            SpawnMode::Corners => {
+                match self.rng.gen_range(0..4) {
+                    0 => (x0, y0),
+                    1 => (w - 2.0, y0),
+                    2 => (x0, h - 2.0),
+                    _ => (w - 2.0, h - 2.0),
+                }
+            }
+            SpawnMode::Random => {
+                // Random position outside spawn radius
+                loop {
+                    let x = self.rng.gen_range(x0..w - 1.0);
+                    let y = self.rng.gen_range(y0..h - 1.0);
+                    let dx = x - center_x;
+                    let dy = y - center_y;
+                    if dx * dx + dy * dy > spawn_radius * spawn_radius * 0.5 {
+                        return (x, y);
+                    }
+                }
+            }
+            SpawnMode::Top => (self.rng.gen_range(x0..w - 1.0), y0),
+            SpawnMode::Bottom => (self.rng.gen_range(x0..w - 1.0), h - 2.0),
+            SpawnMode::Left => (x0, self.rng.gen_range(y0..h - 1.0)),
+            SpawnMode::Right => (w - 2.0, self.rng.gen_range(y0..h - 1.0)),
+        }
+    }
+
+    /// Count neighbors at position using configured neighborhood type
+    fn count_neighbors(&self, ix: i32, iy: i32) -> (usize, bool) {
+        let offsets = self
+            .settings
+            .active_lattice()
+            .neighbor_offsets(ix, iy, self.settings.neighborhood);
+        let mut count = 0;
+        let mut has_any = false;
+
+        for &(ndx, ndy) in offsets {
+            if self.grid.is_occupied(ix + ndx, iy + ndy) {
+                count += 1;
+                has_any = true;
+            }
+        }
+
+        (count, has_any)
+    }
+
+    /// Whether lattice cell (ix, iy) lies in a wall
+    fn is_obstacle(&self, ix: i32, iy: i32) -> bool {
+        if self.obstacles.is_empty() {
+            return false;
+        }
+        let (x, y) = self.settings.active_lattice().cell_center(ix, iy);
+        self.obstacles.contains_point(x, y)
+    }
+
+    /// Whether the straight move from (x0, y0) to (x1, y1) enters a wall
+    fn hits_obstacle(&self, x0: f32, y0: f32, x1: f32, y1: f32) -> bool {
+        if self.obstacles.is_empty() {
+            return false;
+        }
+        // Half-cell samples, ending on the destination
+        let samples = ((x1 - x0).hypot(y1 - y0) * 2.0).ceil().max(1.0) as usize;
+        (1..=samples).any(|i| {
+            let t = i as f32 / samples as f32;
+            self.obstacles.contains_point(x0 + t * (x1 - x0), y0 + t * (y1 - y0))
+        })
+    }
+
+    /// Stickiness factor for a walker of `species` joining the cluster at
+    /// (ix, iy): the best matrix entry over the species of its neighbors
+    fn species_affinity(&self, species: u8, ix: i32, iy: i32) -> f32 {
+        let neighbors = self.settings.active_lattice().neighbor_offsets(ix, iy, self.settings.neighborhood);
+        self.settings.species_affinity(
+            species,
+            neighbors.iter().filter_map(|&(dx, dy)| self.grid.get(ix + dx, iy + dy)).map(|n| n.species),
+        )
+    }
+
+    /// Apply walk bias (directional and radial)
+    fn apply_walk_bias(motion: &Motion, base_angle: f32, x: f32, y: f32, center_x: f32, center_y: f32) -> f32 {
+        let mut angle = base_angle;
+
+        // Apply directional bias
+        if motion.walk_bias_strength > 0.0 {
+            let bias_angle_rad = motion.walk_bias_angle.to_radians();
+            let diff = (bias_angle_rad - base_angle).sin();
+            angle += motion.walk_bias_strength * diff;
+        }
+
+        // Apply radial bias
+        if motion.radial_bias.abs() > 0.001 {
+            let dx = x - center_x;
+            let dy = y - center_y;
+            let radial_angle = dy.atan2(dx);
+
+            // Positive radial_bias = toward center, negative = away
+            let target_angle = if motion.radial_bias > 0.0 {
+                radial_angle + std::f32::consts::PI // Toward center
+            } else {
+                radial_angle // Away from center
+            };
+
+            let diff = (target_angle - angle).sin();
+            angle += motion.radial_bias.abs() * diff;
+        }
+
+        angle
+    }
+
+    /// Apply boundary behavior
+    fn apply_boundary(&self, mut x: f32, mut y: f32, params: &WalkParams) -> (f32, f32) {
+        let (x_min, y_min, x_max, y_max) = (params.x_min, params.y_min, params.x_max, params.y_max);
+        match self.settings.boundary_behavior {
+            BoundaryBehavior::Clamp => {
+                x = x.clamp(x_min, x_max);
+                y = y.clamp(y_min, y_max);
+            }
+            BoundaryBehavior::Wrap => {
+                let width = x_max - x_min;
+                let height = y_max - y_min;
+                if x < x_min {
+                    x += width;
+                } else if x > x_max {
+                    x -= width;
+                }
+                if y < y_min {
+                    y += height;
+                } else if y > y_max {
+                    y -= height;
+                }
+            }
+            BoundaryBehavior::Bounce => {
+                if x < x_min {
+                    x = x_min + (x_min - x);
+                } else if x > x_max {
+                    x = x_max - (x - x_max);
+                }
+                if y < y_min {
+                    y = y_min + (y_min - y);
+                } else if y > y_max {
+                    y = y_max - (y - y_max);
+                }
+            }
+            BoundaryBehavior::Stick | BoundaryBehavior::Absorb => {
+                // These are handled elsewhere; just clamp for safety
+                x = x.clamp(x_min, x_max);
+                y = y.clamp(y_min, y_max);
+            }
+            // No edges: walkers are only limited by the escape radius
+            BoundaryBehavior::Unbounded => {}
+        }
+        (x, y)
+    }
+
+    /// Sample path from (x0, y0) to (x1, y1) for collisions with occupied cells.
+    /// Returns Some((last_empty_x, last_empty_y, neighbor_count)) if an occupied cell is found,
+    /// where the coordinates are the last empty cell before the collision.
+    /// Returns None if the path is clear.
+    fn sample_path_for_collision(
+        &self,
+        x0: f32,
+        y0: f32,
+        x1: f32,
+        y1: f32,
+    ) -> Option<(f32, f32, usize)> {
+        let dx = x1 - x0;
+        let dy = y1 - y0;
+        let dist = (dx * dx + dy * dy).sqrt();
+
+        if dist < 1.0 {
+            return None;
+        }
+
+        // Number of samples along the path (at least 1 per unit distance;
+        // hex and triangle cells are narrower across their corners)
+        let lattice = self.settings.active_lattice();
+        let per_unit = if lattice == LatticeType::Square { 1.0 } else { 2.0 };
+        let num_samples = (dist * per_unit).ceil() as usize;
+        let mut last_empty_x = x0;
+        let mut last_empty_y = y0;
+
+        for i in 1..=num_samples {
+            let t = i as f32 / num_samples as f32;
+            let sample_x = x0 + t * dx;
+            let sample_y = y0 + t * dy;
+
+            let (sample_ix, sample_iy) = lattice.cell_at(sample_x, sample_y);
+            if self.grid.is_occupied(sample_ix, sample_iy) {
                 // Found occupied cell - return the last empty position
                 // Also count neighbors at that position for stickiness calculation
-                let last_ix = last_empty_x as usize;
-                let last_iy = last_empty_y as usize;
-                let (neighbor_count, _) = if last_ix > 0
-                    && last_ix < self.grid_width - 1
-                    && last_iy > 0
-                    && last_iy < self.grid_height - 1
-                {
-                    self.count_neighbors(last_ix, last_iy)
-                } else {
-                    (1, true) // Edge case - assume 1 neighbor
-                };
+                let (last_ix, last_iy) = lattice.cell_at(last_empty_x, last_empty_y);
+                let (neighbor_count, _) = self.count_neighbors(last_ix, last_iy);
                 return Some((last_empty_x, last_empty_y, neighbor_count));
             }
 
@@ -530,22 +1873,78 @@ impl DlaSimulation {
 
     /// Reset the simulation with the current seed pattern
     pub fn reset(&mut self) {
-        self.reset_with_seed(self.seed_pattern);
+        self.reset_with_seed(self.seed_pattern.clone());
     }
 
-    /// Reset with a specific seed pattern
-    pub fn reset_with_seed(&mut self, pattern: SeedPattern) {
-        // Resize grid if dimensions changed
-        let required_size = self.grid_width * self.grid_height;
-        if self.grid.len() != required_size {
-            self.grid = vec![None; required_size];
+    /// Decode the image behind an image seed, so that resets with
+    /// `SeedPattern::Image(path)` can rasterize it
+    pub fn load_seed_image(&mut self, path: &Path) -> Result<(), String> {
+        self.seed_image.load(path)
+    }
+
+    /// Decode the obstacle mask named by the settings and the image of an
+    /// image seed pattern, unless they are loaded already. Resets only
+    /// rasterize loaded images, so call this whenever either changes. A file
+    /// that fails to load is dropped (no walls, or a point seed) and its
+    /// error returned.
+    pub fn load_images(&mut self) -> Result<(), String> {
+        let mut errors = Vec::new();
+        if let Some(path) = self.settings.obstacle_mask.clone() {
+            if let Err(e) = self.obstacle_image.load(&path) {
+                self.settings.obstacle_mask = None;
+                errors.push(format!("{}, running without obstacles", e));
+            }
+        }
+        if let SeedPattern::Image(path) = self.seed_pattern.clone() {
+            if let Err(e) = self.load_seed_image(&path) {
+                self.seed_pattern = SeedPattern::Point;
+                errors.push(format!("{}, using a point seed", e));
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
         } else {
-            self.grid.fill(None);
+            Err(errors.join("; "))
         }
+    }
 
-        self.seed_pattern = pattern;
+    /// Set the RNG seed used on the next reset (None = random each run)
+    pub fn set_rng_seed(&mut self, seed: Option<u64>) {
+        self.rng_seed = seed;
+    }
+
+    /// Re-create the RNG from the configured seed (or entropy if unset)
+    fn reseed_rng(&mut self) {
+        self.rng = match self.rng_seed {
+            Some(seed) => ChaCha8Rng::seed_from_u64(seed),
+            None => ChaCha8Rng::from_entropy(),
+        };
+    }
+
+    /// Reset with a specific seed pattern
+    pub fn reset_with_seed(&mut self, pattern: SeedPattern) {
+        // Reseed first so random seed patterns are reproducible too
+        self.reseed_rng();
+
+        self.grid.clear();
+        self.expansion = 0;
+
+        self.walkers.clear();
 
-        match pattern {
+        // Walls go in first so that the seed can clear the cells it covers
+        self.obstacles = match &self.settings.obstacle_mask {
+            Some(path) if self.obstacle_image.is_loaded(path) => {
+                self.obstacle_image.rasterize(self.grid_width, self.grid_height, Fit::Stretch)
+            }
+            _ => Mask::default(),
+        };
+
+        self.particles_stuck = 0;
+        self.max_radius = 1.0;
+        self.walks = 0;
+        self.escapes = 0;
+        self.seed_pattern = pattern;
+        match self.seed_pattern.clone() {
             SeedPattern::Point => self.seed_point(),
             SeedPattern::Line => self.seed_line(),
             SeedPattern::Cross => self.seed_cross(),
@@ -556,8 +1955,46 @@ impl DlaSimulation {
             SeedPattern::Scatter => self.seed_scatter(),
             SeedPattern::MultiPoint => self.seed_multi_point(),
             SeedPattern::Starburst => self.seed_starburst(),
+            SeedPattern::Image(path) => self.seed_image(&path),
+            SeedPattern::Composite(seed) => self.seed_composite(&seed),
+            SeedPattern::Text { string, scale } => self.seed_text(&string, scale),
+        }
+
+        self.tree_extent = TreeExtent::of(&self.grid);
+
+        // Off-lattice runs start from one disc per seed cell
+        self.discs = DiscCluster::new(self.settings.disc_radius);
+        if self.settings.off_lattice {
+            for (x, y, data) in self.grid.iter() {
+                self.discs.insert(Disc {
+                    x: x as f32 + 0.5,
+                    y: y as f32 + 0.5,
+                    data,
+                });
+            }
+        }
+
+        // 3D runs place the seed pattern in the middle plane of the volume
+        self.volume = VoxelGrid::new(self.grid_width, self.grid_height, self.voxel_depth());
+        self.view_key = None;
+        if self.settings.voxels {
+            let z = (self.voxel_depth() / 2) as i32;
+            for (x, y, data) in self.grid.iter() {
+                self.volume.insert(Voxel { x, y, z, data });
+            }
+            self.refresh_view();
         }
 
+        self.potential = PotentialField::default();
+        self.stalled = false;
+        self.perimeter = None;
+        // Cluster-cluster runs scatter their particles around the seed right away
+        self.aggregation = ClusterAggregation::default();
+        if self.settings.active_growth_model() == GrowthModel::Dlca {
+            self.start_aggregation();
+        }
+        self.grow_domain();
+        self.rebuild_distance_map();
         self.paused = false;
     }
 
@@ -568,143 +2005,203 @@ impl DlaSimulation {
             distance: 0.0,
             direction: 0.0,
             neighbor_count: 0,
+            species: 0,
+            strahler: 1,
+            ..ParticleData::default()
         }
     }
 
-    /// Single center point seed
-    fn seed_point(&mut self) {
-        let center_idx = self.grid_height / 2 * self.grid_width + self.grid_width / 2;
-        self.grid[center_idx] = Some(self.seed_particle());
-        self.particles_stuck = 1;
-        self.max_radius = 1.0;
+    /// Place a seed particle in the lattice cell covering square cell (x, y)
+    /// unless it is taken; returns true if placed. Seeds clear the walls they cover.
+    fn place_seed(&mut self, x: usize, y: usize, data: ParticleData) -> bool {
+        let lattice = self.settings.active_lattice();
+        let (col, row) = lattice.cell_at(x as f32 + 0.5, y as f32 + 0.5);
+        if self.grid.is_occupied(col, row) {
+            return false;
+        }
+        if self.is_obstacle(col, row) {
+            let (cx, cy) = lattice.cell_center(col, row);
+            self.obstacles.unset(cx.floor() as i32, cy.floor() as i32);
+        }
+        self.grid.set(col, row, data);
+        let (gx, gy) = self.center();
+        self.particles_stuck += 1;
+        self.max_radius = self.max_radius.max((x as f32 + 0.5 - gx).hypot(y as f32 + 0.5 - gy));
+        true
     }
 
-    /// Horizontal line seed
-    fn seed_line(&mut self) {
-        let cy = self.grid_height / 2;
-        let half_len = 20.min(self.grid_width / 4);
-        let start_x = self.grid_width / 2 - half_len;
-        let end_x = self.grid_width / 2 + half_len;
-        let seed_data = self.seed_particle();
-        for x in start_x..end_x {
-            self.grid[cy * self.grid_width + x] = Some(seed_data);
-        }
-        self.particles_stuck = end_x - start_x;
-        self.max_radius = half_len as f32;
+    /// Place a seed particle in the square cell containing point (x, y), if on the grid
+    fn seed_at(&mut self, x: f32, y: f32, data: ParticleData) -> bool {
+        x >= 0.0
+            && y >= 0.0
+            && x < self.grid_width as f32
+            && y < self.grid_height as f32
+            && self.place_seed(x as usize, y as usize, data)
     }
 
-    /// Cross-shaped seed
-    fn seed_cross(&mut self) {
-        let cx = self.grid_width / 2;
-        let cy = self.grid_height / 2;
-        let arm_len = 10.min(self.grid_width / 8).min(self.grid_height / 8);
+    /// Seed every cell within `reach` of (cx, cy) whose center offset (dx, dy)
+    /// from it, rotated back by the pattern angle, satisfies `inside`
+    fn seed_shape(&mut self, cx: f32, cy: f32, reach: f32, inside: impl Fn(f32, f32) -> bool) {
+        let (sin, cos) = self.seed_value(SeedParam::Angle).to_radians().sin_cos();
         let seed_data = self.seed_particle();
-        let mut count = 0;
-        for i in 0..arm_len {
-            if cx >= i && cy >= i {
-                self.grid[cy * self.grid_width + (cx - i)] = Some(seed_data);
-                self.grid[cy * self.grid_width + (cx + i)] = Some(seed_data);
-                self.grid[(cy - i) * self.grid_width + cx] = Some(seed_data);
-                self.grid[(cy + i) * self.grid_width + cx] = Some(seed_data);
-                count += 4;
+        let x0 = (cx - reach - 1.0).floor().max(0.0) as usize;
+        let y0 = (cy - reach - 1.0).floor().max(0.0) as usize;
+        let x1 = ((cx + reach + 1.0).ceil().max(0.0) as usize).min(self.grid_width);
+        let y1 = ((cy + reach + 1.0).ceil().max(0.0) as usize).min(self.grid_height);
+        for y in y0..y1 {
+            for x in x0..x1 {
+                let (dx, dy) = (x as f32 + 0.5 - cx, y as f32 + 0.5 - cy);
+                if inside(dx * cos + dy * sin, dy * cos - dx * sin) {
+                    self.place_seed(x, y, seed_data);
+                }
             }
         }
-        self.particles_stuck = count;
-        self.max_radius = arm_len as f32;
     }
 
-    /// Circle outline seed
-    fn seed_circle(&mut self) {
-        let (cx, cy) = self.center();
-        let radius = 15.0_f32.min((self.grid_width / 8) as f32).min((self.grid_height / 8) as f32);
+    /// Center of the seed pattern: the middle cell of the grid, shifted by the offset
+    fn seed_center(&self) -> (f32, f32) {
+        (
+            (self.grid_width / 2) as f32 + 0.5 + self.seed_value(SeedParam::OffsetX),
+            (self.grid_height / 2) as f32 + 0.5 + self.seed_value(SeedParam::OffsetY),
+        )
+    }
+
+    /// Value of a seed parameter for the current pattern (the override, or the default)
+    pub fn seed_value(&self, param: SeedParam) -> f32 {
+        self.seed_params.get(param).unwrap_or_else(|| self.seed_default(param))
+    }
+
+    /// Default value of a seed parameter for the current pattern, scaled to the grid
+    pub fn seed_default(&self, param: SeedParam) -> f32 {
+        let (w, h) = (self.grid_width, self.grid_height);
+        let min_dim = w.min(h) as f32;
+        match (&self.seed_pattern, param) {
+            (SeedPattern::Line, SeedParam::Length) => (2 * 20.min(w / 4)) as f32,
+            (SeedPattern::Cross, SeedParam::Length) => 10.min(w / 8).min(h / 8) as f32,
+            (SeedPattern::Block, SeedParam::Length) => (2 * (w.min(h) / 8).max(4) + 1) as f32,
+            (SeedPattern::Starburst, SeedParam::Length) => (min_dim * 0.35).clamp(8.0, 40.0),
+            (SeedPattern::Circle, SeedParam::Radius) => 15.min(w / 8).min(h / 8) as f32,
+            (SeedPattern::Ring, SeedParam::Radius) => (min_dim * 0.30).clamp(6.0, min_dim * 0.45),
+            (SeedPattern::NoisePatch, SeedParam::Radius) => (min_dim * 0.22).clamp(6.0, 30.0),
+            (SeedPattern::Scatter, SeedParam::Radius) => 20.min(w / 6).min(h / 6) as f32,
+            (SeedPattern::MultiPoint, SeedParam::Radius) => 25.min(w / 5).min(h / 5) as f32,
+            (SeedPattern::Ring, SeedParam::Thickness) => 5.0,
+            (SeedPattern::Scatter, SeedParam::Count) => 15.0,
+            (SeedPattern::MultiPoint, SeedParam::Count) => 5.0,
+            (SeedPattern::Starburst, SeedParam::Spokes) => 8.0,
+            (_, SeedParam::Thickness) => 1.0,
+            _ => 0.0,
+        }
+    }
+
+    /// Single point seed
+    fn seed_point(&mut self) {
+        let (cx, cy) = self.seed_center();
         let seed_data = self.seed_particle();
-        let mut count = 0;
-        for angle_deg in 0..360 {
-            let angle = (angle_deg as f32).to_radians();
-            let x = (cx + radius * angle.cos()) as usize;
-            let y = (cy + radius * angle.sin()) as usize;
-            if x < self.grid_width && y < self.grid_height {
-                let idx = y * self.grid_width + x;
-                if self.grid[idx].is_none() {
-                    self.grid[idx] = Some(seed_data);
-                    count += 1;
-                }
-            }
+        if !self.seed_at(cx, cy, seed_data) {
+            // Offset off the grid: fall back to the middle so the run can grow
+            self.place_seed(self.grid_width / 2, self.grid_height / 2, seed_data);
         }
-        self.particles_stuck = count;
-        self.max_radius = radius;
     }
 
-    /// Thick ring seed (hollow core)
-    fn seed_ring(&mut self) {
-        let (cx, cy) = self.center();
-        let min_dim = self.grid_width.min(self.grid_height) as f32;
-        let radius = (min_dim * 0.30).clamp(6.0, min_dim * 0.45);
-        let thickness = 2.5_f32;
+    /// Seed shaped like an image, scaled to fit the middle of the grid
+    /// (a point seed if the image wasn't loaded or has no ink)
+    fn seed_image(&mut self, path: &Path) {
+        if !self.seed_image.is_loaded(path) {
+            return self.seed_point();
+        }
+        let width = (self.grid_width as f32 * IMAGE_SEED_FILL) as usize;
+        let height = (self.grid_height as f32 * IMAGE_SEED_FILL) as usize;
+        let mask = self.seed_image.rasterize(width, height, Fit::Contain);
+        let (x0, y0) = ((self.grid_width - width) / 2, (self.grid_height - height) / 2);
         let seed_data = self.seed_particle();
-        let mut count = 0;
+        for (x, y) in mask.iter() {
+            self.place_seed(x0 + x as usize, y0 + y as usize, seed_data);
+        }
+        if self.particles_stuck == 0 {
+            self.seed_point();
+        }
+    }
 
-        for y in 0..self.grid_height {
-            for x in 0..self.grid_width {
-                let dx = x as f32 - cx;
-                let dy = y as f32 - cy;
-                let dist = (dx * dx + dy * dy).sqrt();
-                if (dist >= radius - thickness) && (dist <= radius + thickness) {
-                    let idx = y * self.grid_width + x;
-                    if self.grid[idx].is_none() {
-                        self.grid[idx] = Some(seed_data);
-                        count += 1;
-                    }
-                }
-            }
+    /// Seed made of the shapes of a seed file, around the seed center
+    /// (a point seed if none of them lands on the grid)
+    fn seed_composite(&mut self, seed: &CompositeSeed) {
+        let (cx, cy) = self.seed_center();
+        self.seed_shape(cx, cy, seed.extent(), |u, v| seed.contains(u, v));
+        if self.particles_stuck == 0 {
+            self.seed_point();
+        }
+    }
+
+    /// Text seed centered on the seed center (a point seed if no ink lands on the grid)
+    fn seed_text(&mut self, text: &str, scale: u32) {
+        let (cx, cy) = self.seed_center();
+        let scale = scale.max(1) as f32;
+        self.seed_shape(cx, cy, font::text_reach(text, scale), |u, v| font::text_contains(text, scale, u, v));
+        if self.particles_stuck == 0 {
+            self.seed_point();
         }
+    }
+
+    /// Straight line seed
+    fn seed_line(&mut self) {
+        let (cx, cy) = self.seed_center();
+        let half_len = self.seed_value(SeedParam::Length) / 2.0;
+        let half_width = self.seed_value(SeedParam::Thickness) / 2.0;
+        self.seed_shape(cx, cy, half_len + half_width, |u, v| {
+            u >= -half_len && u < half_len && v.abs() <= half_width
+        });
+    }
+
+    /// Cross-shaped seed
+    fn seed_cross(&mut self) {
+        let (cx, cy) = self.seed_center();
+        let arm_len = self.seed_value(SeedParam::Length);
+        let half_width = self.seed_value(SeedParam::Thickness) / 2.0;
+        self.seed_shape(cx, cy, arm_len + half_width, |u, v| {
+            (u.abs() < arm_len && v.abs() <= half_width) || (v.abs() < arm_len && u.abs() <= half_width)
+        });
+    }
+
+    /// Circle outline seed
+    fn seed_circle(&mut self) {
+        let (cx, cy) = self.seed_center();
+        let radius = self.seed_value(SeedParam::Radius);
+        self.seed_shape(cx, cy, radius + 1.0, |u, v| (u.hypot(v) - radius).abs() <= 0.5);
+    }
 
-        self.particles_stuck = count;
-        self.max_radius = radius + thickness;
+    /// Thick ring seed (hollow core)
+    fn seed_ring(&mut self) {
+        let (cx, cy) = self.seed_center();
+        let radius = self.seed_value(SeedParam::Radius);
+        let half_width = self.seed_value(SeedParam::Thickness) / 2.0;
+        self.seed_shape(cx, cy, radius + half_width, |u, v| {
+            (u.hypot(v) - radius).abs() <= half_width
+        });
     }
 
     /// Solid block seed (forces surface roughening)
     fn seed_block(&mut self) {
-        let cx = self.grid_width / 2;
-        let cy = self.grid_height / 2;
-        let min_dim = self.grid_width.min(self.grid_height);
-        let half_size = (min_dim / 8).max(4);
-        let start_x = cx.saturating_sub(half_size);
-        let end_x = (cx + half_size).min(self.grid_width.saturating_sub(1));
-        let start_y = cy.saturating_sub(half_size);
-        let end_y = (cy + half_size).min(self.grid_height.saturating_sub(1));
-        let seed_data = self.seed_particle();
-        let mut count = 0;
-
-        for y in start_y..=end_y {
-            for x in start_x..=end_x {
-                let idx = y * self.grid_width + x;
-                if self.grid[idx].is_none() {
-                    self.grid[idx] = Some(seed_data);
-                    count += 1;
-                }
-            }
-        }
-
-        self.particles_stuck = count;
-        self.max_radius = (half_size as f32) * 1.414;
+        let (cx, cy) = self.seed_center();
+        let half_size = self.seed_value(SeedParam::Length) / 2.0;
+        self.seed_shape(cx, cy, half_size * std::f32::consts::SQRT_2, |u, v| {
+            u.abs() <= half_size && v.abs() <= half_size
+        });
     }
 
     /// Dense noisy blob offset from center for asymmetric growth
     fn seed_noise_patch(&mut self) {
-        let (grid_cx, grid_cy) = self.center();
-        let min_dim = self.grid_width.min(self.grid_height) as f32;
-        let radius = (min_dim * 0.22).clamp(6.0, 30.0);
+        let radius = self.seed_value(SeedParam::Radius);
         let radius_i = radius as i32;
         let jitter = (radius_i / 3).max(1);
-        let mut patch_cx = (self.grid_width as i32 / 3) + self.rng.gen_range(-jitter..=jitter);
-        let mut patch_cy = (self.grid_height as i32 / 3) + self.rng.gen_range(-jitter..=jitter);
+        let offset_x = self.seed_value(SeedParam::OffsetX).round() as i32;
+        let offset_y = self.seed_value(SeedParam::OffsetY).round() as i32;
+        let mut patch_cx = (self.grid_width as i32 / 3) + offset_x + self.rng.gen_range(-jitter..=jitter);
+        let mut patch_cy = (self.grid_height as i32 / 3) + offset_y + self.rng.gen_range(-jitter..=jitter);
         patch_cx = patch_cx.clamp(1, self.grid_width as i32 - 2);
         patch_cy = patch_cy.clamp(1, self.grid_height as i32 - 2);
 
         let seed_data = self.seed_particle();
-        let mut count = 0;
-        let mut max_dist: f32 = 1.0;
 
         for y in (patch_cy - radius_i).max(1)..=(patch_cy + radius_i).min(self.grid_height as i32 - 2) {
             for x in (patch_cx - radius_i).max(1)..=(patch_cx + radius_i).min(self.grid_width as i32 - 2) {
@@ -715,123 +2212,70 @@ impl DlaSimulation {
                     let falloff = 1.0 - dist / radius;
                     let stick_prob = 0.35 + falloff * 0.65; // Dense core, noisy edges
                     if self.rng.gen::<f32>() < stick_prob {
-                        let idx = (y as usize) * self.grid_width + (x as usize);
-                        if self.grid[idx].is_none() {
-                            self.grid[idx] = Some(seed_data);
-                            count += 1;
-
-                            let gdx = x as f32 - grid_cx;
-                            let gdy = y as f32 - grid_cy;
-                            let gdist = (gdx * gdx + gdy * gdy).sqrt();
-                            max_dist = max_dist.max(gdist);
-                        }
+                        self.place_seed(x as usize, y as usize, seed_data);
                     }
                 }
             }
         }
 
-        if count == 0 {
+        if self.particles_stuck == 0 {
             // Guarantee at least one seed
-            let idx = (patch_cy as usize) * self.grid_width + (patch_cx as usize);
-            self.grid[idx] = Some(seed_data);
-            count = 1;
-            let gdx = patch_cx as f32 - grid_cx;
-            let gdy = patch_cy as f32 - grid_cy;
-            max_dist = (gdx * gdx + gdy * gdy).sqrt();
+            self.place_seed(patch_cx as usize, patch_cy as usize, seed_data);
         }
-
-        self.particles_stuck = count;
-        self.max_radius = max_dist;
     }
 
     /// Random scattered points in center region
     fn seed_scatter(&mut self) {
-        let cx = self.grid_width / 2;
-        let cy = self.grid_height / 2;
-        let scatter_radius = 20.min(self.grid_width / 6).min(self.grid_height / 6);
-        let num_seeds = 15;
+        let (cx, cy) = self.seed_center();
+        let scatter_radius = self.seed_value(SeedParam::Radius).max(1.0);
+        let num_seeds = self.seed_value(SeedParam::Count) as usize;
         let seed_data = self.seed_particle();
-        let mut count = 0;
 
         for _ in 0..num_seeds {
             let angle = self.rng.gen_range(0.0..std::f32::consts::TAU);
-            let r = self.rng.gen_range(0.0..scatter_radius as f32);
-            let x = (cx as f32 + r * angle.cos()) as usize;
-            let y = (cy as f32 + r * angle.sin()) as usize;
-            if x < self.grid_width && y < self.grid_height {
-                let idx = y * self.grid_width + x;
-                if self.grid[idx].is_none() {
-                    self.grid[idx] = Some(seed_data);
-                    count += 1;
-                }
-            }
+            let r = self.rng.gen_range(0.0..scatter_radius);
+            self.seed_at(cx + r * angle.cos(), cy + r * angle.sin(), seed_data);
+        }
+        if self.particles_stuck == 0 {
+            self.seed_point();
         }
-        self.particles_stuck = count;
-        self.max_radius = scatter_radius as f32;
     }
 
-    /// Multiple seed points spread across the grid (creates competing growth)
+    /// Multiple seed points spread across the grid (creates competing growth):
+    /// one in the middle and the rest evenly around it
     fn seed_multi_point(&mut self) {
-        let cx = self.grid_width / 2;
-        let cy = self.grid_height / 2;
-        let spread = 25.min(self.grid_width / 5).min(self.grid_height / 5);
+        let (cx, cy) = self.seed_center();
+        let spread = self.seed_value(SeedParam::Radius);
+        let count = self.seed_value(SeedParam::Count) as usize;
+        let start = self.seed_value(SeedParam::Angle).to_radians();
         let seed_data = self.seed_particle();
-        let mut count = 0;
 
-        // Place 5 seed points: center and 4 around it
-        let points = [
-            (cx, cy),
-            (cx - spread, cy),
-            (cx + spread, cy),
-            (cx, cy - spread),
-            (cx, cy + spread),
-        ];
-
-        for (px, py) in points {
-            if px < self.grid_width && py < self.grid_height {
-                let idx = py * self.grid_width + px;
-                if self.grid[idx].is_none() {
-                    self.grid[idx] = Some(seed_data);
-                    count += 1;
-                }
-            }
+        self.seed_at(cx, cy, seed_data);
+        let around = count.saturating_sub(1);
+        for i in 0..around {
+            let angle = start + i as f32 * std::f32::consts::TAU / around as f32;
+            self.seed_at(cx + spread * angle.cos(), cy + spread * angle.sin(), seed_data);
+        }
+        if self.particles_stuck == 0 {
+            self.seed_point();
         }
-        self.particles_stuck = count;
-        self.max_radius = spread as f32;
     }
 
     /// Radial spokes with a thin rim for strong anisotropy
     fn seed_starburst(&mut self) {
-        let (cx, cy) = self.center();
-        let min_dim = self.grid_width.min(self.grid_height) as f32;
-        let spoke_len = (min_dim * 0.35).clamp(8.0, 40.0);
-        let spokes = 8;
+        let (cx, cy) = self.seed_center();
+        let spoke_len = self.seed_value(SeedParam::Length);
+        let spokes = self.seed_value(SeedParam::Spokes) as usize;
+        let start = self.seed_value(SeedParam::Angle).to_radians();
         let seed_data = self.seed_particle();
-        let mut count = 0;
 
         // Central hub
-        let hub_x = cx as usize;
-        let hub_y = cy as usize;
-        let hub_idx = hub_y * self.grid_width + hub_x;
-        if self.grid[hub_idx].is_none() {
-            self.grid[hub_idx] = Some(seed_data);
-            count += 1;
-        }
+        self.seed_at(cx, cy, seed_data);
 
         for s in 0..spokes {
-            let angle = (s as f32) * (std::f32::consts::TAU / spokes as f32);
+            let angle = start + (s as f32) * (std::f32::consts::TAU / spokes as f32);
             for step in 1..=(spoke_len as usize) {
-                let fx = cx + (step as f32) * angle.cos();
-                let fy = cy + (step as f32) * angle.sin();
-                let x = fx.round() as isize;
-                let y = fy.round() as isize;
-                if x > 0 && x < self.grid_width as isize - 1 && y > 0 && y < self.grid_height as isize - 1 {
-                    let idx = (y as usize) * self.grid_width + (x as usize);
-                    if self.grid[idx].is_none() {
-                        self.grid[idx] = Some(seed_data);
-                        count += 1;
-                    }
-                }
+                self.seed_at(cx + (step as f32) * angle.cos(), cy + (step as f32) * angle.sin(), seed_data);
             }
         }
 
@@ -839,28 +2283,117 @@ impl DlaSimulation {
         let rim_radius = spoke_len;
         for angle_deg in (0..360).step_by(4) {
             let angle = (angle_deg as f32).to_radians();
-            let x = (cx + rim_radius * angle.cos()) as isize;
-            let y = (cy + rim_radius * angle.sin()) as isize;
-            if x > 0 && x < self.grid_width as isize - 1 && y > 0 && y < self.grid_height as isize - 1 {
-                let idx = (y as usize) * self.grid_width + (x as usize);
-                if self.grid[idx].is_none() {
-                    self.grid[idx] = Some(seed_data);
-                    count += 1;
-                }
-            }
+            self.seed_at(cx + rim_radius * angle.cos(), cy + rim_radius * angle.sin(), seed_data);
+        }
+        if self.particles_stuck == 0 {
+            self.seed_point();
         }
+    }
 
-        self.particles_stuck = count;
-        self.max_radius = rim_radius;
+    /// Capture the grid, counters and RNG state for a snapshot
+    pub fn state(&self) -> SimulationState {
+        // Particles always lie inside the walk domain, so index them within it
+        let (x0, y0, x1, _) = self.bounds();
+        let domain_width = (x1 - x0) as usize;
+        SimulationState {
+            grid_width: self.grid_width,
+            grid_height: self.grid_height,
+            cells: self
+                .grid
+                .iter()
+                .map(|(x, y, p)| ((y - y0) as usize * domain_width + (x - x0) as usize, p))
+                .collect(),
+            expansion: self.expansion,
+            particles_stuck: self.particles_stuck,
+            max_radius: self.max_radius,
+            walks: self.walks,
+            escapes: self.escapes,
+            seed_pattern: self.seed_pattern.clone(),
+            rng: self.rng.clone(),
+            walkers: self.walkers.clone(),
+            discs: self.discs.discs().to_vec(),
+            voxels: self.volume.voxels().to_vec(),
+            potential: (self.settings.active_growth_model() == GrowthModel::Dbm).then(|| self.potential.clone()),
+            aggregation: (!self.aggregation.is_empty()).then(|| self.aggregation.clone()),
+            obstacles: self.obstacles.clone(),
+            seed_params: self.seed_params,
+        }
     }
 
-    /// Get full particle data at (x, y)
-    pub fn get_particle(&self, x: usize, y: usize) -> Option<ParticleData> {
-        if x < self.grid_width && y < self.grid_height {
-            self.grid[y * self.grid_width + x]
-        } else {
-            None
+    /// Restore a previously captured state (grid size follows the snapshot)
+    pub fn restore_state(&mut self, state: SimulationState) -> Result<(), String> {
+        state.validate()?;
+
+        let domain_width = state.grid_width + 2 * state.expansion;
+        self.grid_width = state.grid_width;
+        self.grid_height = state.grid_height;
+        self.expansion = state.expansion;
+        let (x0, y0, _, _) = self.bounds();
+        self.grid.clear();
+        for (idx, particle) in state.cells {
+            self.grid
+                .set(x0 + (idx % domain_width) as i32, y0 + (idx / domain_width) as i32, particle);
         }
+        self.particles_stuck = state.particles_stuck;
+        self.max_radius = state.max_radius;
+        self.walks = state.walks;
+        self.escapes = state.escapes;
+        self.seed_pattern = state.seed_pattern;
+        self.rng = state.rng;
+        self.walkers = state.walkers;
+        self.discs = DiscCluster::from_discs(self.settings.disc_radius, state.discs);
+        self.volume = VoxelGrid::from_voxels(self.grid_width, self.grid_height, self.voxel_depth(), state.voxels);
+        self.view_key = None;
+        self.refresh_view();
+        self.potential = state.potential.unwrap_or_default();
+        self.potential.classify(&self.grid);
+        self.stalled = false;
+        self.perimeter = None;
+        self.aggregation = state.aggregation.unwrap_or_default();
+        self.aggregation.rebuild_index();
+        self.obstacles = state.obstacles;
+        self.seed_params = state.seed_params;
+        self.tree_extent = TreeExtent::of(&self.grid);
+        self.rebuild_distance_map();
+        Ok(())
+    }
+
+    /// Largest generation, path length and Strahler order of the growth tree
+    pub fn tree_extent(&self) -> TreeExtent {
+        self.tree_extent
+    }
+
+    /// Get full particle data at (x, y)
+    pub fn get_particle(&self, x: i32, y: i32) -> Option<ParticleData> {
+        self.grid.get(x, y)
+    }
+
+    /// Particle whose lattice cell covers the point (x, y) of the walk domain
+    #[inline]
+    pub fn particle_at(&self, x: f32, y: f32) -> Option<ParticleData> {
+        let (col, row) = self.settings.active_lattice().cell_at(x, y);
+        self.grid.get(col, row)
+    }
+
+    /// Whether the point (x, y) of the walk domain lies in a wall
+    #[inline]
+    pub fn obstacle_at(&self, x: f32, y: f32) -> bool {
+        self.obstacles.contains_point(x, y)
+    }
+
+    /// Obstacle walls over the base grid (empty without an obstacle mask)
+    pub fn obstacles(&self) -> &Mask {
+        &self.obstacles
+    }
+
+    /// Every stuck particle as (x, y, data) of its lattice cell, tile by tile
+    pub fn particles(&self) -> impl Iterator<Item = (i32, i32, ParticleData)> + '_ {
+        self.grid.iter()
+    }
+
+    /// Number of grid tiles currently allocated
+    pub fn tile_count(&self) -> usize {
+        self.grid.tile_count()
     }
 
     /// Get simulation progress as a ratio (0.0 to 1.0)
@@ -868,9 +2401,16 @@ impl DlaSimulation {
         self.particles_stuck as f32 / self.num_particles as f32
     }
 
-    /// Check if simulation is complete
+    /// Check if simulation is complete (a dielectric breakdown also ends
+    /// once the discharge reaches the outer electrode, ballistic deposition
+    /// once the deposit grows up to where particles are launched, Eden
+    /// growth once no free cell touches the cluster, and cluster-cluster
+    /// aggregation once a single cluster is left)
     pub fn is_complete(&self) -> bool {
         self.particles_stuck >= self.num_particles
+            || self.potential.reached_electrode()
+            || self.stalled
+            || self.aggregation.is_done()
     }
 
     /// Toggle pause state
@@ -878,23 +2418,17 @@ impl DlaSimulation {
         self.paused = !self.paused;
     }
 
-    /// Resize the simulation grid
-    pub fn resize(&mut self, new_width: usize, new_height: usize) {
-        if new_width != self.grid_width || new_height != self.grid_height {
-            self.grid_width = new_width;
-            self.grid_height = new_height;
-            // Cap particles to new grid's max
-            let max = self.max_particles();
-            if self.num_particles > max {
-                self.num_particles = max;
-            }
-            self.reset();
-        }
-    }
-
     /// Get the maximum sensible particle count for this grid size
-    /// Allows up to 75% of grid area for dense patterns
+    /// Allows up to 75% of grid area for dense patterns (the grid grows when unbounded)
     pub fn max_particles(&self) -> usize {
+        if self.settings.voxels {
+            // A 3D cluster is far sparser than its bounding box
+            let volume = self.grid_width * self.grid_height * self.voxel_depth();
+            return (volume / 16).max(100);
+        }
+        if self.settings.boundary_behavior.grows() {
+            return UNBOUNDED_MAX_PARTICLES;
+        }
         let grid_area = self.grid_width * self.grid_height;
         (grid_area * 3 / 4).max(100) // 75% of grid, minimum 100
     }
@@ -911,21 +2445,103 @@ impl DlaSimulation {
         self.stickiness = (self.stickiness + delta).clamp(0.1, 1.0);
     }
 
-    /// Calculate fractal dimension using box-counting method
-    /// Returns (dimension, r_squared) where r_squared indicates fit quality
+    /// Whether growth probes can be fired at this run (2D lattice DLA and RLA)
+    pub fn check_probes(&self) -> Result<(), String> {
+        let model = self.settings.active_growth_model();
+        if self.settings.voxels || self.settings.off_lattice || !matches!(model, GrowthModel::Dla | GrowthModel::Rla) {
+            return Err("Growth probes need a 2D lattice DLA or RLA run".to_string());
+        }
+        Ok(())
+    }
+
+    /// Fire `probes` walkers at the frozen cluster with the walk and spawn
+    /// rules of `step()` and tally the perimeter cell each would stick at.
+    ///
+    /// Nothing sticks, and the run's RNG is put back afterwards, so the run
+    /// continues as if no probe had been fired. A probe that escapes or runs
+    /// out of iterations is relaunched from the spawn source, as the next
+    /// walker would be, and only counts as lost after `PROBE_LAUNCHES`
+    /// tries. Probes are seeded from the RNG seed and particle count when a
+    /// seed is set, so probing again after more growth fires fresh probes.
+    /// Only 2D lattice DLA and RLA runs can be probed.
+    pub fn probe_growth(&mut self, probes: usize) -> Result<HarmonicMeasure, String> {
+        self.check_probes()?;
+
+        let probe_rng = match self.rng_seed {
+            Some(seed) => ChaCha8Rng::seed_from_u64(seed ^ (self.particles_stuck as u64).wrapping_mul(PROBE_SEED_MIX)),
+            None => ChaCha8Rng::from_entropy(),
+        };
+        // Probes are launched in order, then walk on their own RNG streams
+        // (in parallel with the `parallel` feature) like the multi-walker engine
+        let run_rng = std::mem::replace(&mut self.rng, probe_rng);
+        let params = self.walk_params();
+        let mut walking: Vec<(WalkerPosition, ChaCha8Rng)> = (0..probes)
+            .map(|_| {
+                let position = self.launch_walker(&params);
+                (position, ChaCha8Rng::seed_from_u64(self.rng.gen()))
+            })
+            .collect();
+
+        let mut measure = HarmonicMeasure::new();
+        for launch in 1..=PROBE_LAUNCHES {
+            let walk = |(position, rng): &mut (WalkerPosition, ChaCha8Rng)| {
+                for _ in 0..self.settings.max_walk_iterations {
+                    match self.advance_walker(&params, position, rng) {
+                        WalkOutcome::Continue => {}
+                        outcome => return outcome,
+                    }
+                }
+                WalkOutcome::Continue
+            };
+
+            #[cfg(feature = "parallel")]
+            let outcomes: Vec<WalkOutcome> = {
+                use rayon::prelude::*;
+                walking.par_iter_mut().map(walk).collect()
+            };
+            #[cfg(not(feature = "parallel"))]
+            let outcomes: Vec<WalkOutcome> = walking.iter_mut().map(walk).collect();
+
+            let mut relaunched = Vec::new();
+            for (mut probe, outcome) in walking.into_iter().zip(outcomes) {
+                match outcome {
+                    WalkOutcome::Stick(site) => measure.record_hit(site.x, site.y),
+                    _ if launch < PROBE_LAUNCHES => {
+                        probe.0 = self.launch_walker(&params);
+                        relaunched.push(probe);
+                    }
+                    _ => measure.record_lost(),
+                }
+            }
+            walking = relaunched;
+            if walking.is_empty() {
+                break;
+            }
+        }
+        self.rng = run_rng;
+        Ok(measure)
+    }
+
+    /// Box-counting dimension of the cluster as (D, R²); the `analysis`
+    /// module has more estimators, with confidence intervals
     pub fn calculate_fractal_dimension(&self) -> (f32, f32) {
         if self.particles_stuck < 50 {
             return (0.0, 0.0); // Not enough data
         }
+        if self.settings.voxels {
+            return self.voxel_fractal_dimension();
+        }
 
-        let (cx, cy) = self.center();
-        let cx = cx as usize;
-        let cy = cy as usize;
+        let (min_x, min_y, max_x, max_y) = self.bounds();
+        // Boxes tile the cluster's own bounding box, wherever its seed sits
+        let Some((x0, y0, x1, y1)) = self.grid.bounds() else {
+            return (0.0, 0.0);
+        };
 
         // Box sizes to sample (powers of 2)
         let box_sizes: Vec<usize> = vec![2, 4, 8, 16, 32, 64]
             .into_iter()
-            .filter(|&s| s < self.grid_width.min(self.grid_height) / 2)
+            .filter(|&s| (s as i32) < (max_x - min_x).min(max_y - min_y) / 2)
             .collect();
 
         if box_sizes.len() < 3 {
@@ -937,24 +2553,13 @@ impl DlaSimulation {
 
         for box_size in &box_sizes {
             let mut count = 0;
-            let half = self.max_radius as usize + *box_size;
-
-            let x_start = cx.saturating_sub(half);
-            let x_end = (cx + half).min(self.grid_width);
-            let y_start = cy.saturating_sub(half);
-            let y_end = (cy + half).min(self.grid_height);
 
-            for by in (y_start..y_end).step_by(*box_size) {
-                for bx in (x_start..x_end).step_by(*box_size) {
+            for by in (y0..=y1).step_by(*box_size) {
+                for bx in (x0..=x1).step_by(*box_size) {
                     // Check if any particle in this box
-                    'box_check: for dy in 0..*box_size {
-                        for dx in 0..*box_size {
-                            let x = bx + dx;
-                            let y = by + dy;
-                            if x < self.grid_width
-                                && y < self.grid_height
-                                && self.grid[y * self.grid_width + x].is_some()
-                            {
+                    'box_check: for dy in 0..*box_size as i32 {
+                        for dx in 0..*box_size as i32 {
+                            if self.grid.is_occupied(bx + dx, by + dy) {
                                 count += 1;
                                 break 'box_check;
                             }
@@ -969,43 +2574,945 @@ impl DlaSimulation {
             }
         }
 
-        // Linear regression to find slope (fractal dimension)
-        if log_n.len() < 3 {
-            return (0.0, 0.0);
+        fit_power_law(&log_r, &log_n)
+    }
+
+    /// Mass-radius dimension of the 3D cluster from its growth history:
+    /// N ~ Rg^D over the first 32, 64, 128, ... voxels (3D DLA gives about 2.5).
+    ///
+    /// Box counting badly underestimates D at the cluster sizes a terminal run
+    /// reaches in 3D, while the radius of gyration converges much sooner.
+    fn voxel_fractal_dimension(&self) -> (f32, f32) {
+        let voxels = self.volume.voxels();
+        let mut log_n: Vec<f32> = Vec::new();
+        let mut log_r: Vec<f32> = Vec::new();
+        let (mut sum, mut sum_sq) = ([0.0f64; 3], 0.0f64);
+        let mut next_sample = 32;
+        for (i, v) in voxels.iter().enumerate() {
+            let (x, y, z) = (v.x as f64, v.y as f64, v.z as f64);
+            sum = [sum[0] + x, sum[1] + y, sum[2] + z];
+            sum_sq += x * x + y * y + z * z;
+            let n = i + 1;
+            if n == next_sample || n == voxels.len() {
+                let mean_sq = sum.iter().map(|s| (s / n as f64).powi(2)).sum::<f64>();
+                let radius_of_gyration = (sum_sq / n as f64 - mean_sq).max(0.0).sqrt();
+                if radius_of_gyration > 0.0 && n >= 32 {
+                    log_n.push((n as f32).ln());
+                    log_r.push((radius_of_gyration as f32).ln());
+                }
+                next_sample *= 2;
+            }
         }
+        fit_power_law(&log_r, &log_n)
+    }
+}
+
+/// Least-squares slope of log N against log r (or log 1/r), as (|slope|, R²)
+fn fit_power_law(log_r: &[f32], log_n: &[f32]) -> (f32, f32) {
+    // Linear regression to find slope (fractal dimension)
+    if log_n.len() < 3 {
+        return (0.0, 0.0);
+    }
 
-        let n = log_n.len() as f32;
-        let sum_x: f32 = log_r.iter().sum();
-        let sum_y: f32 = log_n.iter().sum();
-        let sum_xy: f32 = log_r.iter().zip(log_n.iter()).map(|(x, y)| x * y).sum();
-        let sum_x2: f32 = log_r.iter().map(|x| x * x).sum();
+    let n = log_n.len() as f32;
+    let sum_x: f32 = log_r.iter().sum();
+    let sum_y: f32 = log_n.iter().sum();
+    let sum_xy: f32 = log_r.iter().zip(log_n.iter()).map(|(x, y)| x * y).sum();
+    let sum_x2: f32 = log_r.iter().map(|x| x * x).sum();
 
-        let denom = n * sum_x2 - sum_x * sum_x;
-        if denom.abs() < 1e-10 {
-            return (0.0, 0.0);
+    let denom = n * sum_x2 - sum_x * sum_x;
+    if denom.abs() < 1e-10 {
+        return (0.0, 0.0);
+    }
+
+    let slope = (n * sum_xy - sum_x * sum_y) / denom;
+
+    // Calculate R-squared
+    let mean_y = sum_y / n;
+    let ss_tot: f32 = log_n.iter().map(|y| (y - mean_y).powi(2)).sum();
+    if ss_tot.abs() < 1e-10 {
+        return (slope.abs(), 1.0);
+    }
+
+    let intercept = (sum_y - slope * sum_x) / n;
+    let ss_res: f32 = log_r
+        .iter()
+        .zip(log_n.iter())
+        .map(|(x, y)| {
+            let predicted = slope * x + intercept;
+            (y - predicted).powi(2)
+        })
+        .sum();
+    let r_squared = (1.0 - ss_res / ss_tot).max(0.0);
+
+    (slope.abs(), r_squared)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::hash_map::DefaultHasher;
+    use std::collections::HashSet;
+    use std::hash::{Hash, Hasher};
+
+    /// Hash every occupied cell (position + particle data bits)
+    fn grid_hash(sim: &DlaSimulation) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        for (x, y, p) in sim.particles() {
+            (x, y).hash(&mut hasher);
+            p.age.hash(&mut hasher);
+            p.distance.to_bits().hash(&mut hasher);
+            p.direction.to_bits().hash(&mut hasher);
+            p.neighbor_count.hash(&mut hasher);
         }
+        sim.particles_stuck.hash(&mut hasher);
+        hasher.finish()
+    }
 
-        let slope = (n * sum_xy - sum_x * sum_y) / denom;
+    /// Grow a seeded `size` x `size` run set up by `setup` until it holds
+    /// `particles` particles or ends
+    fn run(size: usize, seed: u64, particles: usize, setup: impl Fn(&mut DlaSimulation)) -> DlaSimulation {
+        let mut sim = DlaSimulation::new(size, size);
+        sim.num_particles = particles;
+        setup(&mut sim);
+        sim.set_rng_seed(Some(seed));
+        sim.reset();
+        while !sim.is_complete() {
+            sim.step();
+        }
+        sim
+    }
 
-        // Calculate R-squared
-        let mean_y = sum_y / n;
-        let ss_tot: f32 = log_n.iter().map(|y| (y - mean_y).powi(2)).sum();
-        if ss_tot.abs() < 1e-10 {
-            return (slope.abs(), 1.0);
+    /// Assert that every particle past the seed touches an older one through
+    /// the neighbor offsets of its cell
+    fn assert_connected(sim: &DlaSimulation, offsets: impl Fn(i32, i32) -> &'static [(i32, i32)]) {
+        for (x, y, p) in sim.particles().filter(|(_, _, p)| p.age > 0) {
+            let touches_older = offsets(x, y)
+                .iter()
+                .any(|&(dx, dy)| sim.get_particle(x + dx, y + dy).is_some_and(|n| n.age < p.age));
+            assert!(touches_older, "particle {} at ({}, {}) floats", p.age, x, y);
         }
+    }
 
-        let intercept = (sum_y - slope * sum_x) / n;
-        let ss_res: f32 = log_r
-            .iter()
-            .zip(log_n.iter())
-            .map(|(x, y)| {
-                let predicted = slope * x + intercept;
-                (y - predicted).powi(2)
-            })
-            .sum();
-        let r_squared = (1.0 - ss_res / ss_tot).max(0.0);
+    fn run_seeded(seed: u64, pattern: SeedPattern) -> DlaSimulation {
+        run(96, seed, 300, |sim| sim.seed_pattern = pattern.clone())
+    }
+
+    #[test]
+    fn test_same_seed_produces_identical_cluster() {
+        let a = run_seeded(42, SeedPattern::Point);
+        let b = run_seeded(42, SeedPattern::Point);
+        assert_eq!(grid_hash(&a), grid_hash(&b));
+    }
+
+    #[test]
+    fn test_different_seeds_produce_different_clusters() {
+        let a = run_seeded(1, SeedPattern::Point);
+        let b = run_seeded(2, SeedPattern::Point);
+        assert_ne!(grid_hash(&a), grid_hash(&b));
+    }
+
+    #[test]
+    fn test_random_seed_patterns_are_reproducible() {
+        for pattern in [SeedPattern::NoisePatch, SeedPattern::Scatter] {
+            let a = run_seeded(7, pattern.clone());
+            let b = run_seeded(7, pattern.clone());
+            assert_eq!(grid_hash(&a), grid_hash(&b), "{:?}", pattern);
+        }
+    }
+
+    /// Grow a seeded `size` x `size` run set up by `setup` to `saved`
+    /// particles and save its state, then check that the run and a smaller
+    /// simulation restored from that state grow identical clusters of
+    /// `target` particles. Returns the state and both finished runs
+    fn assert_restore_continues(
+        size: usize,
+        seed: u64,
+        saved: usize,
+        target: usize,
+        setup: impl Fn(&mut DlaSimulation),
+    ) -> (SimulationState, DlaSimulation, DlaSimulation) {
+        let mut original = DlaSimulation::new(size, size);
+        setup(&mut original);
+        original.num_particles = target;
+        original.set_rng_seed(Some(seed));
+        original.reset();
+        while original.particles_stuck < saved && !original.is_complete() {
+            original.step();
+        }
+        assert!(!original.is_complete(), "run ended before its state was saved");
+        let state = original.state();
+        while !original.is_complete() {
+            original.step();
+        }
+
+        let mut restored = DlaSimulation::new(32, 32);
+        setup(&mut restored);
+        restored.num_particles = target;
+        restored.restore_state(state.clone()).unwrap();
+        assert_eq!((restored.grid_width, restored.grid_height), (size, size));
+        while !restored.is_complete() {
+            restored.step();
+        }
+        assert_eq!(grid_hash(&restored), grid_hash(&original));
+        (state, original, restored)
+    }
+
+    #[test]
+    fn test_restored_state_continues_identically() {
+        assert_restore_continues(96, 11, 300, 600, |_| {});
+    }
+
+    fn run_multi_walker(seed: u64, particles: usize) -> DlaSimulation {
+        run(96, seed, particles, |sim| sim.settings.walker_density = 0.02)
+    }
+
+    #[test]
+    fn test_multi_walker_run_is_deterministic() {
+        let a = run_multi_walker(3, 400);
+        let b = run_multi_walker(3, 400);
+        assert_eq!(grid_hash(&a), grid_hash(&b));
+        assert_eq!(a.walkers().len(), a.walker_count());
+        assert_eq!(a.walker_count(), 184);
+    }
+
+    #[test]
+    fn test_multi_walker_stops_at_target() {
+        let sim = run_multi_walker(4, 400);
+        let occupied = sim.particles().count();
+        assert_eq!(sim.particles_stuck, 400);
+        assert_eq!(occupied, 400);
+    }
+
+    #[test]
+    fn test_multi_walker_state_restores_walkers() {
+        let (state, original, _) = assert_restore_continues(96, 21, 300, 500, |sim| sim.settings.walker_density = 0.02);
+        assert_eq!(state.walkers.len(), original.walker_count());
+    }
 
-        (slope.abs(), r_squared)
+    fn run_off_lattice(seed: u64, particles: usize) -> DlaSimulation {
+        run(96, seed, particles, |sim| {
+            sim.settings.off_lattice = true;
+            sim.settings.lattice_walk = false;
+        })
     }
 
+    #[test]
+    fn test_off_lattice_discs_never_overlap() {
+        let sim = run_off_lattice(8, 300);
+        let discs = sim.discs();
+        // The seed disc counts toward particles_stuck
+        assert_eq!(discs.len(), sim.particles_stuck);
+        let min_sq = (2.0 * sim.settings.disc_radius - 1e-3).powi(2);
+        for (i, a) in discs.iter().enumerate() {
+            for b in &discs[i + 1..] {
+                assert!((a.x - b.x).powi(2) + (a.y - b.y).powi(2) >= min_sq);
+            }
+        }
+    }
+
+    #[test]
+    fn test_off_lattice_run_is_deterministic_and_restores() {
+        let a = run_off_lattice(12, 200);
+        let b = run_off_lattice(12, 200);
+        assert_eq!(a.discs(), b.discs());
+
+        let mut restored = DlaSimulation::new(32, 32);
+        restored.restore_state(a.state()).unwrap();
+        assert_eq!(restored.discs(), a.discs());
+        assert_eq!(grid_hash(&restored), grid_hash(&a));
+    }
+
+    #[test]
+    fn test_off_lattice_cluster_touching_the_edge_restores() {
+        // Big discs reach the edge of a small grid, where they hang over it
+        let sim = run(48, 5, 150, |sim| {
+            sim.settings.off_lattice = true;
+            sim.settings.disc_radius = 2.0;
+            sim.settings.boundary_behavior = BoundaryBehavior::Clamp;
+        });
+        let (x0, y0, x1, y1) = sim.bounds();
+        assert!(sim.particles().all(|(x, y, _)| x >= x0 && y >= y0 && x < x1 && y < y1));
+        assert!(sim.particles().any(|(x, y, _)| x == x0 || y == y0 || x == x1 - 1 || y == y1 - 1));
+
+        let mut restored = DlaSimulation::new(32, 32);
+        restored.settings.disc_radius = 2.0;
+        restored.restore_state(sim.state()).unwrap();
+        assert_eq!(restored.discs(), sim.discs());
+        assert_eq!(grid_hash(&restored), grid_hash(&sim));
+    }
+
+    #[test]
+    #[ignore = "statistical, run with `cargo test --release -- --ignored`"]
+    fn test_off_lattice_dla_has_the_continuum_dimension() {
+        let sim = run(320, 21, 4000, |sim| {
+            sim.settings.off_lattice = true;
+            sim.settings.boundary_behavior = BoundaryBehavior::Clamp;
+            // Jumps leave the walk statistics unchanged and keep the run short
+            sim.settings.circle_jump = true;
+        });
+        // Sandbox and correlation estimates are the least biased at this size
+        let morphology = crate::analysis::Morphology::from_simulation(&sim, &Default::default());
+        for estimate in [&morphology.sandbox, &morphology.correlation] {
+            let dimension = estimate.dimension().unwrap();
+            assert!((dimension - 1.71).abs() < 0.06, "D = {}", dimension);
+        }
+    }
+
+    fn run_circle_jump(seed: u64, neighborhood: crate::settings::NeighborhoodType) -> DlaSimulation {
+        run(128, seed, 400, |sim| {
+            sim.settings.circle_jump = true;
+            sim.settings.neighborhood = neighborhood;
+        })
+    }
+
+    #[test]
+    fn test_circle_jump_never_skips_the_cluster() {
+        use crate::settings::NeighborhoodType;
+        for neighborhood in [NeighborhoodType::VonNeumann, NeighborhoodType::Moore, NeighborhoodType::Extended] {
+            let sim = run_circle_jump(5, neighborhood);
+            // Every stuck particle must touch an earlier one through its neighborhood
+            assert_connected(&sim, |_, _| neighborhood.offsets());
+        }
+    }
+
+    #[test]
+    #[ignore = "statistical, run with `cargo test --release -- --ignored`"]
+    fn test_circle_jump_keeps_the_fractal_dimension() {
+        // Jumps must sample the same process as stepping: compare the sandbox
+        // dimension of runs with and without them, averaged over three seeds
+        let mean_dimension = |jump: bool| {
+            let dimensions: Vec<f32> = (1..=3)
+                .map(|seed| {
+                    let sim = run(160, seed, 1000, |sim| {
+                        sim.settings.circle_jump = jump;
+                        sim.settings.boundary_behavior = BoundaryBehavior::Clamp;
+                    });
+                    let morphology = crate::analysis::Morphology::from_simulation(&sim, &Default::default());
+                    morphology.sandbox.dimension().unwrap()
+                })
+                .collect();
+            dimensions.iter().sum::<f32>() / dimensions.len() as f32
+        };
+        let (stepped, jumped) = (mean_dimension(false), mean_dimension(true));
+        assert!((stepped - jumped).abs() < 0.05, "D {} stepping vs {} jumping", stepped, jumped);
+    }
+
+    #[test]
+    fn test_circle_jump_is_deterministic() {
+        use crate::settings::NeighborhoodType;
+        let a = run_circle_jump(9, NeighborhoodType::VonNeumann);
+        let b = run_circle_jump(9, NeighborhoodType::VonNeumann);
+        assert_eq!(grid_hash(&a), grid_hash(&b));
+    }
+
+    #[test]
+    fn test_hex_and_triangular_clusters_are_connected() {
+        use crate::settings::NeighborhoodType;
+        for lattice in [LatticeType::Hexagonal, LatticeType::Triangular] {
+            for lattice_walk in [true, false] {
+                let sim = run(96, 4, 300, |sim| {
+                    sim.settings.lattice = lattice;
+                    sim.settings.lattice_walk = lattice_walk;
+                    sim.settings.walk_step_size = 2.0;
+                });
+                assert_eq!(sim.particles().count(), 300);
+                for (col, row, p) in sim.particles() {
+                    // Rendering finds every particle at its cell center
+                    let (x, y) = lattice.cell_center(col, row);
+                    assert_eq!(sim.particle_at(x, y).map(|q| q.age), Some(p.age));
+                }
+                assert_connected(&sim, |col, row| lattice.neighbor_offsets(col, row, NeighborhoodType::Moore));
+            }
+        }
+    }
+
+    #[test]
+    fn test_growth_models_grow_connected_clusters() {
+        use crate::settings::NeighborhoodType;
+        let dla = run(96, 6, 200, |sim| sim.settings.growth_model = GrowthModel::Dla);
+        for model in [GrowthModel::Rla, GrowthModel::Ballistic, GrowthModel::Eden] {
+            let sim = run(96, 6, 200, |sim| sim.settings.growth_model = model);
+            assert_eq!(sim.particles().count(), 200, "{:?}", model);
+            assert_connected(&sim, |_, _| NeighborhoodType::VonNeumann.offsets());
+            if model == GrowthModel::Eden {
+                assert!(sim.max_radius < 0.7 * dla.max_radius, "Eden {} vs DLA {}", sim.max_radius, dla.max_radius);
+            }
+        }
+    }
+
+    #[test]
+    fn test_eden_ends_when_the_grid_is_full() {
+        let sim = run(20, 4, 1000, |sim| {
+            sim.settings.boundary_behavior = BoundaryBehavior::Clamp;
+            sim.settings.growth_model = GrowthModel::Eden;
+        });
+        assert_eq!(sim.particles_stuck, 20 * 20);
+    }
+
+    #[test]
+    fn test_ballistic_deposition_rains_from_the_spawn_edge() {
+        let sim = run(96, 9, 150, |sim| {
+            sim.settings.growth_model = GrowthModel::Ballistic;
+            sim.settings.spawn_mode = SpawnMode::Top;
+            sim.seed_pattern = SeedPattern::Line;
+        });
+        // Particles fall straight down, so everything grows on top of the line
+        let line_y = 48;
+        assert!(sim.particles().all(|(_, y, _)| y <= line_y));
+    }
+
+    fn run_dbm(size: usize, seed: u64, eta: f32, particles: usize) -> DlaSimulation {
+        run(size, seed, particles, |sim| {
+            sim.settings.growth_model = GrowthModel::Dbm;
+            sim.settings.dbm_eta = eta;
+        })
+    }
+
+    #[test]
+    fn test_dbm_eta_controls_compactness() {
+        // eta = 0 fills in like Eden growth, large eta grows thin lightning-like branches
+        use crate::settings::NeighborhoodType;
+        let compact = run_dbm(64, 5, 0.0, 100);
+        let branched = run_dbm(64, 5, 3.0, 100);
+        for sim in [&compact, &branched] {
+            assert_eq!(sim.particles().count(), 100);
+            assert_connected(sim, |_, _| NeighborhoodType::VonNeumann.offsets());
+        }
+        assert!(compact.max_radius < 9.0, "eta 0 radius {}", compact.max_radius);
+        assert!(branched.max_radius > 2.0 * compact.max_radius, "eta 3 radius {}", branched.max_radius);
+    }
+
+    #[test]
+    #[ignore = "statistical, run with `cargo test --release -- --ignored`"]
+    fn test_dbm_eta_one_reproduces_the_dla_dimension() {
+        let sim = run_dbm(112, 7, 1.0, 400);
+        // The sandbox estimate is the steadiest at this size (1.68-1.74 over seeds)
+        let morphology = crate::analysis::Morphology::from_simulation(&sim, &Default::default());
+        let dimension = morphology.sandbox.dimension().unwrap();
+        assert!((dimension - 1.71).abs() < 0.06, "D = {}", dimension);
+    }
+
+    #[test]
+    fn test_dbm_ends_when_the_discharge_reaches_the_edge() {
+        let mut sim = DlaSimulation::new(40, 40);
+        sim.num_particles = 2000;
+        sim.settings.growth_model = GrowthModel::Dbm;
+        sim.settings.dbm_eta = 3.0;
+        sim.set_rng_seed(Some(2));
+        sim.reset();
+        while sim.step() {}
+        assert!(sim.is_complete());
+        assert!(sim.particles_stuck < 2000);
+        let (x0, y0, x1, y1) = sim.bounds();
+        assert!(sim
+            .particles()
+            .any(|(x, y, _)| x == x0 || y == y0 || x == x1 - 1 || y == y1 - 1));
+    }
+
+    #[test]
+    fn test_dbm_restored_state_continues_identically() {
+        let (state, _, _) = assert_restore_continues(96, 8, 100, 160, |sim| {
+            sim.settings.growth_model = GrowthModel::Dbm;
+            sim.settings.dbm_eta = 1.0;
+        });
+        assert!(state.potential.is_some());
+    }
+
+    #[test]
+    fn test_dlca_merges_scattered_particles_into_one_cluster() {
+        let sim = run(48, 3, 150, |sim| sim.settings.growth_model = GrowthModel::Dlca);
+        assert!(sim.aggregation().is_done());
+        assert_eq!(sim.particles_stuck, 150);
+        assert_eq!(sim.particles().count(), 150);
+
+        // Every particle is reachable from every other through touching cells
+        let cells: HashSet<(i32, i32)> = sim.particles().map(|(x, y, _)| (x, y)).collect();
+        let start = *cells.iter().next().unwrap();
+        let mut seen = HashSet::from([start]);
+        let mut stack = vec![start];
+        while let Some((x, y)) = stack.pop() {
+            for &(dx, dy) in sim.settings.neighborhood.offsets() {
+                let next = (x + dx, y + dy);
+                if cells.contains(&next) && seen.insert(next) {
+                    stack.push(next);
+                }
+            }
+        }
+        assert_eq!(seen.len(), 150);
+    }
+
+    #[test]
+    fn test_switching_from_dlca_rebuilds_the_distance_map() {
+        let mut sim = DlaSimulation::new(48, 48);
+        sim.num_particles = 150;
+        sim.settings.growth_model = GrowthModel::Dlca;
+        sim.set_rng_seed(Some(5));
+        sim.reset();
+        for _ in 0..40 {
+            sim.step();
+        }
+        sim.settings.growth_model = GrowthModel::Dla;
+        sim.settings.circle_jump = true;
+        sim.num_particles = sim.grid.len() + 1;
+        sim.step();
+
+        let mut fresh = DistanceMap::default();
+        fresh.rebuild(&sim.grid, std::iter::empty());
+        for y in -20..70 {
+            for x in -20..70 {
+                assert_eq!(sim.distance_map.distance_at(x, y), fresh.distance_at(x, y), "({}, {})", x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn test_dlca_restored_state_continues_identically() {
+        // Saved part-way through the merging, before the cluster count drops to one
+        let (state, _, _) = assert_restore_continues(48, 6, 75, 150, |sim| sim.settings.growth_model = GrowthModel::Dlca);
+        assert!(state.aggregation.is_some());
+    }
+
+    fn run_species(seed: u64, second_fraction: f32, matrix: Vec<Vec<f32>>, setup: impl Fn(&mut DlaSimulation)) -> DlaSimulation {
+        use crate::settings::Species;
+        run(96, seed, 300, |sim| {
+            sim.settings.circle_jump = true;
+            sim.settings.species = vec![
+                Species::default(),
+                Species {
+                    fraction: second_fraction,
+                    ..Species::default()
+                },
+            ];
+            sim.settings.stickiness_matrix = matrix.clone();
+            setup(sim);
+        })
+    }
+
+    /// Species of every deposited particle past the seed, one per particle
+    fn deposited_species(sim: &DlaSimulation) -> Vec<u8> {
+        if sim.settings.voxels {
+            sim.voxels().iter().filter(|v| v.data.age > 0).map(|v| v.data.species).collect()
+        } else if sim.settings.off_lattice {
+            sim.discs().iter().filter(|d| d.data.age > 0).map(|d| d.data.species).collect()
+        } else {
+            sim.particles().filter(|(_, _, p)| p.age > 0).map(|(_, _, p)| p.species).collect()
+        }
+    }
+
+    /// Single and multi-walker lattice DLA, off-lattice discs, 3D voxels and ballistic deposition
+    fn species_modes() -> [fn(&mut DlaSimulation); 5] {
+        [
+            |_| {},
+            |sim| sim.settings.walker_density = 0.005,
+            |sim| sim.settings.off_lattice = true,
+            |sim| sim.settings.voxels = true,
+            |sim| sim.settings.growth_model = GrowthModel::Ballistic,
+        ]
+    }
+
+    #[test]
+    fn test_species_deposit_by_fraction() {
+        for setup in species_modes() {
+            let sim = run_species(4, 3.0, Vec::new(), setup);
+            let species = deposited_species(&sim);
+            let second = species.iter().filter(|&&s| s == 1).count();
+            // Three quarters of the walkers belong to the second species
+            assert!(second * 10 >= species.len() * 6 && second * 10 <= species.len() * 9, "{} of {}", second, species.len());
+        }
+    }
+
+    #[test]
+    fn test_stickiness_matrix_blocks_cross_species_contacts() {
+        // The second species only sticks to its own kind, which never gets a foothold
+        for setup in species_modes() {
+            let sim = run_species(6, 1.0, vec![vec![1.0, 1.0], vec![0.0, 1.0]], setup);
+            assert_eq!(sim.particles_stuck, 300);
+            assert!(deposited_species(&sim).iter().all(|&s| s == 0));
+        }
+    }
+
+    #[test]
+    fn test_seed_params_shape_the_pattern() {
+        let mut sim = DlaSimulation::new(100, 100);
+        sim.seed_params.set(SeedParam::Radius, Some(30.0));
+        sim.seed_params.set(SeedParam::Thickness, Some(3.0));
+        sim.reset_with_seed(SeedPattern::Ring);
+        let (cx, cy) = (50.5, 50.5);
+        assert!(sim.particles_stuck > 0);
+        assert!(sim.particles().all(|(x, y, _)| {
+            let r = (x as f32 + 0.5 - cx).hypot(y as f32 + 0.5 - cy);
+            (28.5..=31.5).contains(&r)
+        }));
+
+        // A vertical 21-cell line, shifted 10 cells right
+        sim.seed_params = SeedParams::default();
+        sim.seed_params.set(SeedParam::Length, Some(21.0));
+        sim.seed_params.set(SeedParam::Angle, Some(90.0));
+        sim.seed_params.set(SeedParam::OffsetX, Some(10.0));
+        sim.reset_with_seed(SeedPattern::Line);
+        assert_eq!(sim.particles_stuck, 21);
+        assert!(sim.particles().all(|(x, _, _)| x == 60));
+
+        // Seven points: the middle one and six around it
+        sim.seed_params = SeedParams::default();
+        sim.seed_params.set(SeedParam::Count, Some(7.0));
+        sim.reset_with_seed(SeedPattern::MultiPoint);
+        assert_eq!(sim.particles_stuck, 7);
+
+        // Unset parameters keep the grid-scaled defaults; angles wrap
+        sim.seed_params.set(SeedParam::Angle, Some(-90.0));
+        assert_eq!(sim.seed_value(SeedParam::Angle), 270.0);
+        assert_eq!(sim.seed_value(SeedParam::Radius), 20.0);
+    }
+
+    #[test]
+    fn test_image_seed_takes_the_image_shape() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bar.png");
+        // Black horizontal bar on white, 4:1
+        image::GrayImage::from_fn(40, 10, |_, _| image::Luma([0])).save(&path).unwrap();
+
+        let mut sim = DlaSimulation::new(100, 100);
+        sim.seed_pattern = SeedPattern::Image(path.clone());
+        sim.load_images().unwrap();
+        // Resets reuse the decoded image
+        std::fs::remove_file(&path).unwrap();
+        sim.reset_with_seed(SeedPattern::Image(path));
+        // Fitted into the middle 80x80: an 80x20 bar from (10, 40)
+        assert_eq!(sim.particles_stuck, 80 * 20);
+        assert!(sim.particles().all(|(x, y, _)| (10..90).contains(&x) && (40..60).contains(&y)));
+
+        // An unreadable image is reported and falls back to a point seed
+        sim.seed_pattern = SeedPattern::Image(dir.path().join("missing.png"));
+        let error = sim.load_images().unwrap_err();
+        assert!(error.contains("missing.png"), "{}", error);
+        assert_eq!(sim.seed_pattern, SeedPattern::Point);
+        sim.reset_with_seed(SeedPattern::Image(dir.path().join("missing.png")));
+        assert_eq!(sim.particles_stuck, 1);
+    }
+
+    #[test]
+    fn test_composite_seed_places_every_shape() {
+        use crate::composite::SeedShape;
+
+        // Two vertical electrodes with a point between them
+        let seed = CompositeSeed {
+            shapes: vec![
+                SeedShape::Line {
+                    from: [-20.0, -10.0],
+                    to: [-20.0, 10.0],
+                    thickness: 1.0,
+                },
+                SeedShape::Line {
+                    from: [20.0, -10.0],
+                    to: [20.0, 10.0],
+                    thickness: 1.0,
+                },
+                SeedShape::Point { at: [0.0, 0.0] },
+            ],
+        };
+        let mut sim = DlaSimulation::new(100, 100);
+        sim.reset_with_seed(SeedPattern::Composite(seed.clone()));
+        assert_eq!(sim.particles_stuck, 2 * 21 + 1);
+        assert!(sim.particles().all(|(x, _, _)| x == 30 || x == 50 || x == 70));
+
+        // The offset moves the whole seed
+        sim.seed_params.set(SeedParam::OffsetY, Some(-20.0));
+        sim.reset_with_seed(SeedPattern::Composite(seed));
+        assert!(sim.particles().all(|(_, y, _)| (20..=40).contains(&y)));
+
+        // Nothing on the grid falls back to a point seed
+        let off_grid = CompositeSeed {
+            shapes: vec![SeedShape::Point { at: [500.0, 0.0] }],
+        };
+        sim.seed_params = SeedParams::default();
+        sim.reset_with_seed(SeedPattern::Composite(off_grid));
+        assert_eq!(sim.particles_stuck, 1);
+    }
+
+    #[test]
+    fn test_text_seed_grows_from_every_spawn_mode() {
+        let text = SeedPattern::Text {
+            string: "HI".to_string(),
+            scale: 2,
+        };
+        let mut sim = DlaSimulation::new(96, 96);
+        sim.reset_with_seed(text.clone());
+        // 'H' has 17 ink pixels and 'I' 11, each 2x2 cells
+        assert_eq!(sim.particles_stuck, (17 + 11) * 4);
+        // The 22x14 block is centered on the middle cell
+        assert!(sim.particles().all(|(x, y, _)| (37..59).contains(&x) && (41..55).contains(&y)));
+
+        let mut mode = SpawnMode::Circle;
+        loop {
+            let mut sim = DlaSimulation::new(96, 96);
+            sim.num_particles = 140;
+            sim.settings.spawn_mode = mode;
+            sim.settings.boundary_behavior = BoundaryBehavior::Clamp;
+            sim.settings.walker_density = 0.02;
+            sim.set_rng_seed(Some(3));
+            sim.reset_with_seed(text.clone());
+            while !sim.is_complete() {
+                sim.step();
+            }
+            assert_eq!(sim.particles_stuck, 140, "{:?}", mode);
+            mode = mode.next();
+            if mode == SpawnMode::Circle {
+                break;
+            }
+        }
+    }
+
+    #[test]
+    fn test_particles_form_a_growth_tree() {
+        for (lattice, model) in [
+            (LatticeType::Square, GrowthModel::Dla),
+            (LatticeType::Hexagonal, GrowthModel::Dla),
+            (LatticeType::Square, GrowthModel::Eden),
+        ] {
+            let mut sim = DlaSimulation::new(96, 96);
+            sim.num_particles = 400;
+            sim.settings.lattice = lattice;
+            sim.settings.growth_model = model;
+            sim.set_rng_seed(Some(5));
+            sim.reset_with_seed(SeedPattern::Point);
+            while !sim.is_complete() {
+                sim.step();
+            }
+
+            for (x, y, particle) in sim.particles() {
+                let Some((px, py)) = particle.parent else {
+                    assert_eq!((particle.generation, particle.age), (0, 0));
+                    continue;
+                };
+                // Parents are older neighbors, one generation up
+                let parent = sim.get_particle(px, py).unwrap();
+                assert!(parent.age < particle.age || parent.generation == 0);
+                assert_eq!(particle.generation, parent.generation + 1);
+                assert!(particle.path_length > parent.path_length);
+                assert!((px - x).abs() <= 1 && (py - y).abs() <= 1);
+                // Orders stay in sync with the children as the tree grows
+                assert_eq!(particle.strahler, tree::strahler_order(&sim.grid, lattice, x, y), "{:?}", lattice);
+            }
+            let (_, _, seed) = sim.particles().find(|(_, _, p)| p.parent.is_none()).unwrap();
+            let extent = sim.tree_extent();
+            assert_eq!(seed.strahler, extent.strahler);
+            assert!(extent.strahler >= 3 && extent.generation > 10, "{:?}", extent);
+
+            // The extent is rebuilt from restored particles
+            let mut restored = DlaSimulation::new(32, 32);
+            restored.restore_state(sim.state()).unwrap();
+            assert_eq!(restored.tree_extent(), extent);
+        }
+    }
+
+    #[test]
+    fn test_growth_probes_hit_the_perimeter_without_growing() {
+        let grow = |probe: bool| {
+            let mut sim = DlaSimulation::new(128, 128);
+            sim.num_particles = 600;
+            sim.settings.boundary_behavior = BoundaryBehavior::Clamp;
+            sim.settings.circle_jump = true;
+            sim.set_rng_seed(Some(8));
+            sim.reset_with_seed(SeedPattern::Point);
+            while !sim.is_complete() {
+                sim.step();
+            }
+            let measure = probe.then(|| sim.probe_growth(2000).unwrap());
+            sim.num_particles = 700;
+            let before = sim.grid.len();
+            while !sim.is_complete() {
+                sim.step();
+            }
+            (sim, measure, before)
+        };
+
+        let (sim, measure, before) = grow(true);
+        let measure = measure.unwrap();
+        assert_eq!(before, 600);
+        assert_eq!(measure.probes, 2000);
+        // Escaped probes are relaunched, so nearly all of them land
+        assert!(measure.lost < 20, "{} probes lost", measure.lost);
+        for site in measure.sites() {
+            // Hit cells are empty perimeter cells of the 600-particle cluster
+            let cell = sim.get_particle(site.x, site.y);
+            assert!(cell.is_none_or(|p| p.age >= 600));
+            assert!(sim.count_neighbors(site.x, site.y).1);
+        }
+        // Tips screen the fjords: the measure is far from uniform
+        assert!(measure.max_hits() as usize * measure.site_count() > 5 * measure.total_hits());
+        let d1 = measure.spectrum(&[1.0]).dimension(1.0).unwrap();
+        assert!((0.7..1.3).contains(&d1), "D_1 = {}", d1);
+
+        // Probing leaves the run's RNG alone
+        let (unprobed, _, _) = grow(false);
+        assert_eq!(grid_hash(&sim), grid_hash(&unprobed));
+
+        // Probes after more growth are seeded afresh; at the same size they repeat
+        let mut sim = sim;
+        let again = sim.probe_growth(2000).unwrap();
+        assert_eq!(sim.probe_growth(2000).unwrap().sites(), again.sites());
+        sim.num_particles = 701;
+        while !sim.is_complete() {
+            sim.step();
+        }
+        assert_ne!(sim.probe_growth(2000).unwrap().sites(), again.sites());
+
+        let mut sim = DlaSimulation::new(64, 64);
+        sim.settings.growth_model = GrowthModel::Eden;
+        assert!(sim.probe_growth(10).is_err());
+    }
+
+    #[test]
+    fn test_walled_in_dbm_and_eden_runs_end() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("box.png");
+        // A closed square wall around the seed
+        image::GrayImage::from_fn(64, 64, |x, y| {
+            let ring = |v: u32| (22..42).contains(&v);
+            let wall = ring(x) && ring(y) && !((24..40).contains(&x) && (24..40).contains(&y));
+            image::Luma([if wall { 0 } else { 255 }])
+        })
+        .save(&path)
+        .unwrap();
+
+        for model in [GrowthModel::Dbm, GrowthModel::Eden] {
+            let mut sim = DlaSimulation::new(64, 64);
+            sim.num_particles = 1000;
+            sim.settings.growth_model = model;
+            sim.settings.obstacle_mask = Some(path.clone());
+            sim.load_images().unwrap();
+            sim.set_rng_seed(Some(3));
+            sim.reset();
+            let mut steps = 0;
+            while sim.step() {
+                steps += 1;
+                assert!(steps < 2000, "{:?} run never ended", model);
+            }
+            assert!(sim.is_complete());
+            assert!(sim.particles_stuck < sim.num_particles);
+            // Eden fills the box; no field reaches inside a closed wall, so DBM
+            // stops once the relaxation has drained it
+            if model == GrowthModel::Eden {
+                assert_eq!(sim.particles().count(), 16 * 16);
+            }
+        }
+    }
+
+    #[test]
+    fn test_walkers_never_enter_obstacles() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("walls.png");
+        // Two black walls left and right of the seed, gaps top and bottom
+        image::GrayImage::from_fn(96, 96, |x, y| {
+            let wall = ((36..40).contains(&x) || (56..60).contains(&x)) && (16..80).contains(&y);
+            image::Luma([if wall { 0 } else { 255 }])
+        })
+        .save(&path)
+        .unwrap();
+
+        for circle_jump in [false, true] {
+            let mut sim = DlaSimulation::new(96, 96);
+            sim.num_particles = 300;
+            sim.settings.circle_jump = circle_jump;
+            sim.settings.obstacle_mask = Some(path.clone());
+            sim.load_images().unwrap();
+            sim.set_rng_seed(Some(12));
+            sim.reset();
+            assert_eq!(sim.obstacles().count(), 2 * 4 * 64);
+            while !sim.is_complete() {
+                sim.step();
+            }
+            assert!(sim.particles().all(|(x, y, _)| !sim.obstacles().contains(x, y)));
+
+            // Walls travel with the saved state
+            let mut restored = DlaSimulation::new(96, 96);
+            restored.restore_state(sim.state()).unwrap();
+            assert_eq!(restored.obstacles(), sim.obstacles());
+        }
+    }
+
+    #[test]
+    fn test_unbounded_cluster_outgrows_base_grid() {
+        let sim = run(32, 3, 500, |sim| {
+            sim.settings.boundary_behavior = BoundaryBehavior::Unbounded;
+            sim.settings.circle_jump = true;
+        });
+        assert_eq!(sim.particles().count(), 500);
+        let (x0, y0, x1, y1) = sim.bounds();
+        assert!(x0 < 0 && y0 < 0 && x1 > 32 && y1 > 32, "domain did not grow: {:?}", sim.bounds());
+        let outside = sim
+            .particles()
+            .filter(|&(x, y, _)| !(0..32).contains(&x) || !(0..32).contains(&y))
+            .count();
+        assert!(outside > 0, "cluster stayed inside the base grid");
+        assert!(sim.particles().all(|(x, y, _)| x >= x0 && x < x1 && y >= y0 && y < y1));
+    }
+
+    #[test]
+    fn test_unbounded_state_restores_grown_domain() {
+        let (_, original, restored) = assert_restore_continues(32, 4, 300, 500, |sim| {
+            sim.settings.boundary_behavior = BoundaryBehavior::Unbounded;
+            sim.settings.circle_jump = true;
+        });
+        assert_eq!(restored.bounds(), original.bounds());
+    }
+
+    #[test]
+    fn test_voxel_cluster_is_connected_and_3d() {
+        let sim = run(96, 6, 2000, |sim| sim.settings.voxels = true);
+        let voxels = sim.voxels();
+        assert_eq!(voxels.len(), 2000);
+        let offsets = sim.settings.neighborhood_3d.offsets();
+        for (i, v) in voxels.iter().enumerate().skip(1) {
+            let touches_older = voxels[..i]
+                .iter()
+                .any(|o| offsets.contains(&(o.x - v.x, o.y - v.y, o.z - v.z)));
+            assert!(touches_older, "voxel {} at ({}, {}, {}) floats", i, v.x, v.y, v.z);
+        }
+        // Grows out of the seed plane, and the dimension lands near 3D DLA's 2.5
+        assert!(voxels.iter().any(|v| v.z != sim.slice_z()));
+        let (dimension, r_squared) = sim.calculate_fractal_dimension();
+        assert!((2.2..2.8).contains(&dimension), "D = {}", dimension);
+        assert!(r_squared > 0.95);
+    }
+
+    #[test]
+    fn test_voxel_views_draw_the_volume() {
+        let mut sim = run(96, 8, 600, |sim| sim.settings.voxels = true);
+        let slice_z = sim.slice_z();
+        let in_slice = sim.voxels().iter().filter(|v| v.z == slice_z).count();
+        assert_eq!(sim.particles().count(), in_slice);
+
+        // Every voxel column shows up once in the projection
+        sim.settings.voxel_view = VoxelView::Projection;
+        sim.refresh_view();
+        let columns: std::collections::HashSet<(i32, i32)> = sim.voxels().iter().map(|v| (v.x, v.y)).collect();
+        assert_eq!(sim.particles().count(), columns.len());
+
+        // A quarter turn looks along x, so each (z, y) column shows up once
+        sim.settings.voxel_view = VoxelView::Rotate;
+        sim.rotate_view(std::f32::consts::FRAC_PI_2);
+        sim.refresh_view();
+        let side: std::collections::HashSet<(i32, i32)> = sim.voxels().iter().map(|v| (v.z, v.y)).collect();
+        assert_eq!(sim.particles().count(), side.len());
+    }
+
+    #[test]
+    fn test_voxel_state_continues_identically() {
+        let (_, original, restored) = assert_restore_continues(96, 12, 300, 600, |sim| sim.settings.voxels = true);
+        assert_eq!(restored.voxels(), original.voxels());
+    }
+
+    #[test]
+    fn test_reset_replays_seeded_run() {
+        let mut sim = run_seeded(99, SeedPattern::Point);
+        let first = grid_hash(&sim);
+        sim.reset();
+        while !sim.is_complete() {
+            sim.step();
+        }
+        assert_eq!(grid_hash(&sim), first);
+    }
 }
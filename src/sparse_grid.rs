@@ -0,0 +1,234 @@
+//! Chunked sparse particle grid.
+//!
+//! Cells live in 64x64 tiles that are only allocated once a particle lands in
+//! them, so an empty region costs one directory slot per tile. The tile
+//! directory covers the bounding box of allocated tiles and grows in any
+//! direction (including negative coordinates) as particles are added.
+
+use crate::simulation::ParticleData;
+
+/// Side length of a tile in cells
+pub const TILE_SIZE: i32 = 64;
+const TILE_AREA: usize = (TILE_SIZE * TILE_SIZE) as usize;
+
+type Tile = [Option<ParticleData>; TILE_AREA];
+
+/// Sparse grid of particles addressed by signed cell coordinates
+#[derive(Debug, Clone, Default)]
+pub struct SparseGrid {
+    /// Tile coordinates of the directory's top-left slot
+    tile_x0: i32,
+    tile_y0: i32,
+    /// Directory size in tiles
+    tiles_w: i32,
+    tiles_h: i32,
+    tiles: Vec<Option<Box<Tile>>>,
+    /// Bounding box of the cells occupied since the last clear as
+    /// (min_x, min_y, max_x, max_y), inclusive
+    bounds: Option<(i32, i32, i32, i32)>,
+    len: usize,
+}
+
+/// Split a cell coordinate into (tile, offset within tile)
+fn split(v: i32) -> (i32, i32) {
+    (v.div_euclid(TILE_SIZE), v.rem_euclid(TILE_SIZE))
+}
+
+impl SparseGrid {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Remove every particle and free all tiles
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Number of occupied cells
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Number of allocated tiles
+    pub fn tile_count(&self) -> usize {
+        self.tiles.iter().filter(|t| t.is_some()).count()
+    }
+
+    /// Inclusive bounding box of occupied cells as (min_x, min_y, max_x, max_y).
+    ///
+    /// Removing cells does not shrink it, so after removals it may be larger
+    /// than the cells that are left.
+    pub fn bounds(&self) -> Option<(i32, i32, i32, i32)> {
+        self.bounds
+    }
+
+    fn slot(&self, tx: i32, ty: i32) -> Option<usize> {
+        let (dx, dy) = (tx - self.tile_x0, ty - self.tile_y0);
+        (dx >= 0 && dx < self.tiles_w && dy >= 0 && dy < self.tiles_h).then(|| (dy * self.tiles_w + dx) as usize)
+    }
+
+    /// Particle at (x, y), if any
+    #[inline]
+    pub fn get(&self, x: i32, y: i32) -> Option<ParticleData> {
+        let (tx, ox) = split(x);
+        let (ty, oy) = split(y);
+        let tile = self.tiles[self.slot(tx, ty)?].as_ref()?;
+        tile[(oy * TILE_SIZE + ox) as usize]
+    }
+
+    #[inline]
+    pub fn is_occupied(&self, x: i32, y: i32) -> bool {
+        self.get(x, y).is_some()
+    }
+
+    /// Place a particle at (x, y), replacing any existing one
+    pub fn set(&mut self, x: i32, y: i32, data: ParticleData) {
+        let (tx, ox) = split(x);
+        let (ty, oy) = split(y);
+        let slot = match self.slot(tx, ty) {
+            Some(slot) => slot,
+            None => {
+                self.grow_directory(tx, ty);
+                self.slot(tx, ty).expect("directory covers the new tile")
+            }
+        };
+        let tile = self.tiles[slot].get_or_insert_with(|| Box::new([None; TILE_AREA]));
+        let cell = &mut tile[(oy * TILE_SIZE + ox) as usize];
+        if cell.is_none() {
+            self.len += 1;
+        }
+        *cell = Some(data);
+
+        self.bounds = Some(match self.bounds {
+            Some((x0, y0, x1, y1)) => (x0.min(x), y0.min(y), x1.max(x), y1.max(y)),
+            None => (x, y, x, y),
+        });
+    }
+
+    /// Remove the particle at (x, y), returning it if there was one
+    pub fn remove(&mut self, x: i32, y: i32) -> Option<ParticleData> {
+        let (tx, ox) = split(x);
+        let (ty, oy) = split(y);
+        let slot = self.slot(tx, ty)?;
+        let removed = self.tiles[slot].as_mut()?[(oy * TILE_SIZE + ox) as usize].take();
+        if removed.is_some() {
+            self.len -= 1;
+            if self.len == 0 {
+                self.bounds = None;
+            }
+        }
+        removed
+    }
+
+    /// Re-lay the tile directory so it also covers tile (tx, ty)
+    fn grow_directory(&mut self, tx: i32, ty: i32) {
+        let (x0, y0, x1, y1) = if self.tiles.is_empty() {
+            (tx, ty, tx + 1, ty + 1)
+        } else {
+            (
+                self.tile_x0.min(tx),
+                self.tile_y0.min(ty),
+                (self.tile_x0 + self.tiles_w).max(tx + 1),
+                (self.tile_y0 + self.tiles_h).max(ty + 1),
+            )
+        };
+        let (w, h) = (x1 - x0, y1 - y0);
+        let mut tiles: Vec<Option<Box<Tile>>> = (0..w * h).map(|_| None).collect();
+        for (i, tile) in std::mem::take(&mut self.tiles).into_iter().enumerate() {
+            if tile.is_some() {
+                let (ox, oy) = (i as i32 % self.tiles_w, i as i32 / self.tiles_w);
+                let (nx, ny) = (self.tile_x0 + ox - x0, self.tile_y0 + oy - y0);
+                tiles[(ny * w + nx) as usize] = tile;
+            }
+        }
+        self.tile_x0 = x0;
+        self.tile_y0 = y0;
+        self.tiles_w = w;
+        self.tiles_h = h;
+        self.tiles = tiles;
+    }
+
+    /// Occupied cells as (x, y, particle), tile by tile and row-major within
+    /// each tile. Only allocated tiles are visited.
+    pub fn iter(&self) -> impl Iterator<Item = (i32, i32, ParticleData)> + '_ {
+        self.tiles.iter().enumerate().flat_map(move |(i, tile)| {
+            let base_x = (self.tile_x0 + i as i32 % self.tiles_w) * TILE_SIZE;
+            let base_y = (self.tile_y0 + i as i32 / self.tiles_w) * TILE_SIZE;
+            tile.iter().flat_map(move |cells| {
+                cells.iter().enumerate().filter_map(move |(j, cell)| {
+                    cell.map(|p| (base_x + j as i32 % TILE_SIZE, base_y + j as i32 / TILE_SIZE, p))
+                })
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn particle(age: usize) -> ParticleData {
+        ParticleData {
+            age,
+            ..ParticleData::default()
+        }
+    }
+
+    #[test]
+    fn test_set_and_get_across_tiles() {
+        let mut grid = SparseGrid::new();
+        let points = [(0, 0), (63, 63), (64, 0), (-1, -1), (-200, 150), (1000, -700)];
+        for (i, &(x, y)) in points.iter().enumerate() {
+            grid.set(x, y, particle(i));
+        }
+        for (i, &(x, y)) in points.iter().enumerate() {
+            assert_eq!(grid.get(x, y).map(|p| p.age), Some(i), "({}, {})", x, y);
+        }
+        assert!(grid.get(1, 0).is_none());
+        assert!(grid.get(5000, 5000).is_none());
+        assert_eq!(grid.len(), points.len());
+        assert_eq!(grid.bounds(), Some((-200, -700, 1000, 150)));
+        // Only tiles that hold particles are allocated
+        assert_eq!(grid.tile_count(), 5);
+    }
+
+    #[test]
+    fn test_overwrite_keeps_count() {
+        let mut grid = SparseGrid::new();
+        grid.set(3, 4, particle(1));
+        grid.set(3, 4, particle(2));
+        assert_eq!(grid.len(), 1);
+        assert_eq!(grid.get(3, 4).unwrap().age, 2);
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut grid = SparseGrid::new();
+        grid.set(3, 4, particle(1));
+        grid.set(-70, 4, particle(2));
+        assert_eq!(grid.remove(3, 4).map(|p| p.age), Some(1));
+        assert_eq!(grid.remove(3, 4), None);
+        assert_eq!(grid.remove(900, 900), None);
+        assert_eq!(grid.len(), 1);
+        assert!(!grid.is_occupied(3, 4));
+        grid.remove(-70, 4);
+        assert!(grid.is_empty());
+        assert_eq!(grid.bounds(), None);
+    }
+
+    #[test]
+    fn test_iter_walks_tiles_in_order() {
+        let mut grid = SparseGrid::new();
+        for &(x, y) in &[(70, 5), (-3, 5), (2, -9), (5, 2), (1, 2)] {
+            grid.set(x, y, particle(0));
+        }
+        let cells: Vec<(i32, i32)> = grid.iter().map(|(x, y, _)| (x, y)).collect();
+        assert_eq!(cells, vec![(2, -9), (-3, 5), (1, 2), (5, 2), (70, 5)]);
+        grid.remove(2, -9);
+        assert_eq!(grid.iter().count(), 4);
+    }
+}
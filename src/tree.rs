@@ -0,0 +1,187 @@
+//! Growth tree: which particle each lattice particle attached to.
+//!
+//! A particle that sticks on a 2D lattice is linked to its parent, the
+//! nearest occupied neighbor it touched (the oldest one on ties), so the
+//! cluster is a forest rooted at the seed cells. Each particle records its
+//! generation (links to the root), the length of that path, and the Strahler
+//! order of the branch it roots. Strahler orders depend on everything grown
+//! below a particle, so they are updated up the tree as leaves attach.
+
+use crate::lattice::LatticeType;
+use crate::settings::NeighborhoodType;
+use crate::simulation::ParticleData;
+use crate::sparse_grid::SparseGrid;
+
+/// Largest tree values on the grid, for normalizing the tree color modes
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TreeExtent {
+    pub generation: u32,
+    pub path_length: f32,
+    pub strahler: u8,
+}
+
+impl TreeExtent {
+    /// Extent of every particle on the grid
+    pub fn of(grid: &SparseGrid) -> Self {
+        let mut extent = Self::default();
+        for (_, _, particle) in grid.iter() {
+            extent.include(&particle);
+        }
+        extent
+    }
+
+    pub fn include(&mut self, particle: &ParticleData) {
+        self.generation = self.generation.max(particle.generation);
+        self.path_length = self.path_length.max(particle.path_length);
+        self.strahler = self.strahler.max(particle.strahler);
+    }
+}
+
+/// Cells a child of (col, row) can lie in: on the square lattice every
+/// neighborhood is within the extended one, so changing it mid-run is fine
+fn link_offsets(lattice: LatticeType, col: i32, row: i32) -> &'static [(i32, i32)] {
+    lattice.neighbor_offsets(col, row, NeighborhoodType::Extended)
+}
+
+/// Fill in the tree fields of a particle about to stick at (col, row): its
+/// parent among the occupied cells of `neighborhood`, generation and path length
+pub fn link(
+    grid: &SparseGrid,
+    lattice: LatticeType,
+    neighborhood: NeighborhoodType,
+    col: i32,
+    row: i32,
+    data: &mut ParticleData,
+) {
+    let (x, y) = lattice.cell_center(col, row);
+    let mut best: Option<(f32, usize, (i32, i32), ParticleData)> = None;
+    for &(dx, dy) in lattice.neighbor_offsets(col, row, neighborhood) {
+        let (px, py) = (col + dx, row + dy);
+        let Some(parent) = grid.get(px, py) else {
+            continue;
+        };
+        let (cx, cy) = lattice.cell_center(px, py);
+        let link_length = (cx - x).hypot(cy - y);
+        let closer = best
+            .as_ref()
+            .is_none_or(|&(length, age, _, _)| (link_length, parent.age) < (length, age));
+        if closer {
+            best = Some((link_length, parent.age, (px, py), parent));
+        }
+    }
+
+    data.strahler = 1;
+    match best {
+        Some((link_length, _, cell, parent)) => {
+            data.parent = Some(cell);
+            data.generation = parent.generation + 1;
+            data.path_length = parent.path_length + link_length;
+        }
+        None => {
+            data.parent = None;
+            data.generation = 0;
+            data.path_length = 0.0;
+        }
+    }
+}
+
+/// Strahler order of (col, row) from its children: 1 for a leaf, otherwise
+/// the highest child order, plus one if two or more children share it
+pub fn strahler_order(grid: &SparseGrid, lattice: LatticeType, col: i32, row: i32) -> u8 {
+    let (mut highest, mut count) = (0, 0);
+    for &(dx, dy) in link_offsets(lattice, col, row) {
+        let Some(child) = grid.get(col + dx, row + dy) else {
+            continue;
+        };
+        if child.parent != Some((col, row)) {
+            continue;
+        }
+        if child.strahler > highest {
+            (highest, count) = (child.strahler, 1);
+        } else if child.strahler == highest {
+            count += 1;
+        }
+    }
+    match count {
+        0 => 1,
+        1 => highest,
+        _ => highest.saturating_add(1),
+    }
+}
+
+/// Update Strahler orders from (col, row) up to the root after a leaf
+/// attached to it; returns the highest order set
+pub fn update_strahler(grid: &mut SparseGrid, lattice: LatticeType, col: i32, row: i32) -> u8 {
+    let mut cell = Some((col, row));
+    let mut highest = 0;
+    while let Some((x, y)) = cell {
+        let Some(mut particle) = grid.get(x, y) else {
+            break;
+        };
+        let order = strahler_order(grid, lattice, x, y);
+        if order == particle.strahler {
+            break;
+        }
+        particle.strahler = order;
+        grid.set(x, y, particle);
+        highest = highest.max(order);
+        cell = particle.parent;
+    }
+    highest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Stick a particle at (x, y) the way the simulation does
+    fn stick(grid: &mut SparseGrid, x: i32, y: i32) {
+        let mut data = ParticleData {
+            age: grid.len(),
+            ..ParticleData::default()
+        };
+        link(grid, LatticeType::Square, NeighborhoodType::Moore, x, y, &mut data);
+        let parent = data.parent;
+        grid.set(x, y, data);
+        if let Some((px, py)) = parent {
+            update_strahler(grid, LatticeType::Square, px, py);
+        }
+    }
+
+    #[test]
+    fn test_links_and_strahler_orders() {
+        let mut grid = SparseGrid::new();
+        // Root at the origin with a stem going up
+        for y in 0..3 {
+            stick(&mut grid, 0, -y);
+        }
+        let tip = grid.get(0, -2).unwrap();
+        assert_eq!(tip.parent, Some((0, -1)));
+        assert_eq!((tip.generation, tip.path_length), (2, 2.0));
+        assert_eq!(grid.get(0, 0).unwrap().strahler, 1);
+
+        // A diagonal twig off the tip
+        stick(&mut grid, 1, -3);
+        assert_eq!(grid.get(1, -3).unwrap().parent, Some((0, -2)));
+        assert!((grid.get(1, -3).unwrap().path_length - (2.0 + 2.0_f32.sqrt())).abs() < 1e-5);
+        assert_eq!(grid.get(0, 0).unwrap().strahler, 1);
+
+        // A second twig of order 1 at the tip makes it order 2, all the way down
+        stick(&mut grid, -1, -3);
+        assert_eq!(grid.get(0, -2).unwrap().strahler, 2);
+        assert_eq!(grid.get(0, 0).unwrap().strahler, 2);
+
+        // Another order-2 branch from the root needs its own two twigs
+        stick(&mut grid, 1, 1);
+        stick(&mut grid, 2, 2);
+        assert_eq!(grid.get(0, 0).unwrap().strahler, 2);
+        stick(&mut grid, 3, 2);
+        // Touching (2, 2) and (3, 2), the nearer orthogonal neighbor is the parent
+        stick(&mut grid, 2, 3);
+        assert_eq!(grid.get(2, 3).unwrap().parent, Some((2, 2)));
+        assert_eq!(grid.get(2, 2).unwrap().strahler, 2);
+        assert_eq!(grid.get(0, 0).unwrap().strahler, 3);
+        assert_eq!(TreeExtent::of(&grid).strahler, 3);
+        assert_eq!(TreeExtent::of(&grid).generation, 3);
+    }
+}
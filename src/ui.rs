@@ -1,5 +1,11 @@
-use crate::app::{App, Focus, ParamPopup, TextInputPopup, ViewMode};
+use crate::app::{App, Focus, ParamPopup, PresetPopup, SeedPopup, TextInputPopup, ViewMode};
 use crate::braille;
+use dla_sim_tui::analysis::Morphology;
+use dla_sim_tui::color::ColorScheme;
+use dla_sim_tui::dlca::ClusterAggregation;
+use dla_sim_tui::harmonic::{HarmonicMeasure, MultifractalSpectrum};
+use dla_sim_tui::lattice::LatticeType;
+use dla_sim_tui::settings::SimulationSettings;
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
@@ -10,15 +16,19 @@ use ratatui::{
 
 const SIDEBAR_WIDTH: u16 = 22;
 const STATES_PANEL_WIDTH: u16 = 48;
+/// Width of the cluster statistics panel drawn over the canvas
+const CLUSTER_STATS_WIDTH: u16 = 26;
+/// Width of the morphology panel overlaid on the canvas
+const MORPHOLOGY_WIDTH: u16 = 30;
 
 /// Max scroll for help content (generous to account for text wrapping on small screens)
-pub const HELP_CONTENT_LINES: u16 = 73;
+pub const HELP_CONTENT_LINES: u16 = 94;
 
-/// Number of lines in controls content (5 main + 18 Shift+letter hints + 1 record)
-pub const CONTROLS_CONTENT_LINES: u16 = 25;
+/// Number of lines in controls content (5 main + 19 Shift+letter hints + record, png, snapshot, presets, probes, metrics, zoom, pan)
+pub const CONTROLS_CONTENT_LINES: u16 = 36;
 
-/// Number of lines in parameters content
-pub const PARAMS_CONTENT_LINES: u16 = 24;
+/// Number of lines in parameters content (through the last parameter)
+pub const PARAMS_CONTENT_LINES: u16 = Focus::VoxelView.line_index() + 1;
 
 // UI color scheme
 const BORDER_COLOR: Color = Color::Cyan;
@@ -68,7 +78,35 @@ pub fn render(frame: &mut Frame, app: &App) {
 
     // Render export popup if open (overlays everything)
     if let Some(popup) = &app.export_popup {
-        render_export_popup(frame, area, popup);
+        render_export_popup(frame, area, popup, Some(".json config | .graphml .dot .graph.json | .ply .xyz | .csv .analysis.json"));
+    }
+
+    // Render PNG export popup if open
+    if let Some(popup) = &app.png_popup {
+        render_export_popup(frame, area, popup, None);
+    }
+
+    // Render snapshot popup if open
+    if let Some((_, popup)) = &app.snapshot_popup {
+        render_export_popup(frame, area, popup, None);
+    }
+
+    // Render metrics log popup if open
+    if let Some(popup) = &app.metrics_popup {
+        render_export_popup(frame, area, popup, Some(".csv | .ndjson .jsonl .json"));
+    }
+
+    // Render preset browser and preset save popup if open
+    if let Some(popup) = &app.preset_popup {
+        render_preset_popup(frame, area, app, popup);
+    }
+    if let Some(popup) = &app.preset_save_popup {
+        render_export_popup(frame, area, popup, None);
+    }
+
+    // Render seed panel if open
+    if let Some(popup) = &app.seed_popup {
+        render_seed_popup(frame, area, app, popup);
     }
 
     // Render export result toast if present
@@ -85,6 +123,26 @@ pub fn render(frame: &mut Frame, app: &App) {
     if let Some(result) = &app.recording_result {
         render_recording_result(frame, area, result);
     }
+
+    // Render snapshot result toast if present (same style as recording messages)
+    if let Some(result) = &app.snapshot_result {
+        render_recording_result(frame, area, result);
+    }
+
+    // Render preset result toast if present
+    if let Some(result) = &app.preset_result {
+        render_recording_result(frame, area, result);
+    }
+
+    // Render growth probe error toast if present
+    if let Some(result) = &app.probe_result {
+        render_recording_result(frame, area, result);
+    }
+
+    // Render metrics log result toast if present
+    if let Some(result) = &app.metrics_result {
+        render_recording_result(frame, area, result);
+    }
 }
 
 /// Calculate the canvas size (excluding borders)
@@ -124,9 +182,9 @@ pub fn get_controls_visible_lines(terminal_height: u16) -> u16 {
     let fixed_height = STATUS_HEIGHT + NAV_HEIGHT;
     let available = terminal_height.saturating_sub(fixed_height);
 
-    let params_ideal = PARAMS_CONTENT_LINES + BORDERS; // 14
-    let controls_min = MIN_CONTROLS_VISIBLE + BORDERS; // 5
-    let controls_max = CONTROLS_CONTENT_LINES + BORDERS; // 10
+    let params_ideal = PARAMS_CONTENT_LINES + BORDERS;
+    let controls_min = MIN_CONTROLS_VISIBLE + BORDERS;
+    let controls_max = CONTROLS_CONTENT_LINES + BORDERS;
 
     let controls_height = if available < params_ideal + controls_min {
         controls_min.min(available)
@@ -151,13 +209,13 @@ fn render_sidebar(frame: &mut Frame, area: Rect, app: &App) {
     let available = area.height.saturating_sub(fixed_height);
 
     // Calculate ideal heights (content + borders)
-    let params_ideal = PARAMS_CONTENT_LINES + BORDERS; // 14
-    let controls_min = MIN_CONTROLS_VISIBLE + BORDERS; // 5
-    let controls_max = CONTROLS_CONTENT_LINES + BORDERS; // 10
+    let params_ideal = PARAMS_CONTENT_LINES + BORDERS;
+    let controls_min = MIN_CONTROLS_VISIBLE + BORDERS;
+    let controls_max = CONTROLS_CONTENT_LINES + BORDERS;
 
     // Allocate space with priority:
-    // 1. Parameters needs its content (no whitespace) - up to 14
-    // 2. Controls expands from 3 to 8 visible lines
+    // 1. Parameters needs its content (no whitespace) - PARAMS_CONTENT_LINES
+    // 2. Controls expands from MIN_CONTROLS_VISIBLE to all its lines
     // 3. Remaining whitespace goes to Parameters
     let (params_height, controls_height) = if available < params_ideal + controls_min {
         // Not enough space - give controls its minimum, params gets the rest
@@ -167,7 +225,7 @@ fn render_sidebar(frame: &mut Frame, area: Rect, app: &App) {
     } else {
         // Enough for params ideal + controls min, see how much extra we have
         let extra = available - params_ideal - controls_min;
-        // Controls gets extra up to its max (8 visible lines)
+        // Controls gets extra up to its max (all of its lines)
         let controls_extra = extra.min(controls_max - controls_min);
         // Any remainder goes to params as whitespace
         let params_extra = extra.saturating_sub(controls_extra);
@@ -179,7 +237,7 @@ fn render_sidebar(frame: &mut Frame, area: Rect, app: &App) {
         .constraints([
             Constraint::Length(STATUS_HEIGHT),   // Status - fixed
             Constraint::Length(params_height),   // Parameters - dynamic
-            Constraint::Length(controls_height), // Controls - dynamic (3-8 visible lines)
+            Constraint::Length(controls_height), // Controls - dynamic
             Constraint::Length(NAV_HEIGHT),      // Nav - fixed
         ])
         .split(area);
@@ -225,6 +283,11 @@ fn render_status_box(frame: &mut Frame, area: Rect, app: &App) {
                 format!("N: {} / {}", app.simulation.particles_stuck, app.simulation.num_particles),
                 Style::default().fg(TEXT_COLOR),
             ),
+            // Metric samples logged so far
+            Span::styled(
+                app.metrics.as_ref().map_or(String::new(), |(_, log)| format!("  LOG {}", log.samples())),
+                Style::default().fg(DIM_TEXT_COLOR),
+            ),
         ]),
         Line::from(vec![
             Span::styled("█".repeat(filled), Style::default().fg(Color::Green)),
@@ -272,7 +335,7 @@ fn render_params_box(frame: &mut Frame, area: Rect, app: &App) {
 
     // Parameters grouped by type, alphabetical within each group
     let content = vec![
-        // === Movement (alphabetical: adaptfactor, adaptive, direction, force, lattice, radial, walk) ===
+        // === Movement (alphabetical: adaptfactor, adaptive, direction, eta, force, growth, jump, lat type, lattice, mobility, off-lattice, radial, voxels, walk) ===
         make_header("Movement"),
         make_line(
             "adaptive",
@@ -289,21 +352,56 @@ fn render_params_box(frame: &mut Frame, area: Rect, app: &App) {
             format!("{:.0}°", settings.walk_bias_angle),
             app.focus == Focus::Direction,
         ),
+        make_line(
+            "eta",
+            format!("{:.2}", settings.dbm_eta),
+            app.focus == Focus::Eta,
+        ),
         make_line(
             "force",
             format!("{:.2}", settings.walk_bias_strength),
             app.focus == Focus::Force,
         ),
+        make_line(
+            "growth",
+            settings.growth_model.name().to_lowercase(),
+            app.focus == Focus::GrowthModel,
+        ),
+        make_line(
+            "jump",
+            if settings.circle_jump { "on" } else { "off" }.to_string(),
+            app.focus == Focus::Jump,
+        ),
+        make_line(
+            "lat type",
+            settings.lattice.name().to_lowercase(),
+            app.focus == Focus::LatticeType,
+        ),
         make_line(
             "lattice",
             if settings.lattice_walk { "on" } else { "off" }.to_string(),
             app.focus == Focus::LatticeWalk,
         ),
+        make_line(
+            "mobility",
+            format!("{:.2}", settings.cluster_mobility),
+            app.focus == Focus::Mobility,
+        ),
+        make_line(
+            "off-lattice",
+            if settings.off_lattice { "on" } else { "off" }.to_string(),
+            app.focus == Focus::OffLattice,
+        ),
         make_line(
             "radial",
             format!("{:.2}", settings.radial_bias),
             app.focus == Focus::RadialBias,
         ),
+        make_line(
+            "voxels",
+            if settings.voxels { "on" } else { "off" }.to_string(),
+            app.focus == Focus::Voxels,
+        ),
         make_line(
             "walk",
             format!("{:.1}", settings.walk_step_size),
@@ -323,7 +421,7 @@ fn render_params_box(frame: &mut Frame, area: Rect, app: &App) {
         ),
         make_line(
             "neighbors",
-            settings.neighborhood.short_name().to_lowercase(),
+            neighbors_label(settings),
             app.focus == Focus::Neighborhood,
         ),
         make_line(
@@ -341,7 +439,7 @@ fn render_params_box(frame: &mut Frame, area: Rect, app: &App) {
             format!("{:.1}", settings.tip_stickiness),
             app.focus == Focus::TipSticky,
         ),
-        // === Spawn (alphabetical: bound, escape, maxsteps, minradius, spawn, spawnoff) ===
+        // === Spawn (alphabetical: bound, escape, maxsteps, minradius, spawn, spawnoff, walkers) ===
         make_header("Spawn"),
         make_line(
             "bound",
@@ -373,7 +471,12 @@ fn render_params_box(frame: &mut Frame, area: Rect, app: &App) {
             format!("{:.0}", settings.spawn_radius_offset),
             app.focus == Focus::SpawnOffset,
         ),
-        // === Visual (alphabetical: age, color, highlight, invert, mode, particles, seed, speed) ===
+        make_line(
+            "walkers",
+            walkers_label(settings.walker_density),
+            app.focus == Focus::Walkers,
+        ),
+        // === Visual (alphabetical: age, color, highlight, invert, mode, particles, seed, slice, speed, view) ===
         make_header("Visual"),
         make_line(
             "age",
@@ -410,13 +513,25 @@ fn render_params_box(frame: &mut Frame, area: Rect, app: &App) {
             app.simulation.seed_pattern.name().to_lowercase(),
             app.focus == Focus::Seed,
         ),
+        make_line(
+            "slice",
+            format!("z {}", app.simulation.slice_z()),
+            app.focus == Focus::Slice,
+        ),
         make_line(
             "speed",
             format!("{}", app.steps_per_frame),
             app.focus == Focus::Speed,
         ),
+        make_line(
+            "view",
+            settings.voxel_view.name().to_lowercase(),
+            app.focus == Focus::VoxelView,
+        ),
     ];
 
+    debug_assert_eq!(content.len() as u16, PARAMS_CONTENT_LINES, "Focus::line_index is out of date");
+
     // Calculate scroll to keep focused item visible based on actual area
     let focus_line = app.focus.line_index();
     let visible_height = area.height.saturating_sub(2); // minus borders
@@ -485,6 +600,46 @@ fn render_controls_box(frame: &mut Frame, area: Rect, app: &App) {
             Span::styled("Shift+X:", key_style),
             Span::styled(" export", desc_style),
         ]),
+        Line::from(vec![
+            Span::raw(" "),
+            Span::styled("P:", key_style),
+            Span::styled(" save png", desc_style),
+        ]),
+        Line::from(vec![
+            Span::raw(" "),
+            Span::styled("O/L:", key_style),
+            Span::styled(" save/load", desc_style),
+        ]),
+        Line::from(vec![
+            Span::raw(" "),
+            Span::styled("T:", key_style),
+            Span::styled(" presets", desc_style),
+        ]),
+        Line::from(vec![
+            Span::raw(" "),
+            Span::styled("G:", key_style),
+            Span::styled(" growth probes", desc_style),
+        ]),
+        Line::from(vec![
+            Span::raw(" "),
+            Span::styled("D:", key_style),
+            Span::styled(" morphology", desc_style),
+        ]),
+        Line::from(vec![
+            Span::raw(" "),
+            Span::styled("E:", key_style),
+            Span::styled(" log metrics", desc_style),
+        ]),
+        Line::from(vec![
+            Span::raw(" "),
+            Span::styled(",/. F:", key_style),
+            Span::styled(" zoom/fit", desc_style),
+        ]),
+        Line::from(vec![
+            Span::raw(" "),
+            Span::styled("Ctrl+↑↓←→:", key_style),
+            Span::styled(" pan", desc_style),
+        ]),
         // Shift+letter hints (alphabetical)
         Line::from(vec![
             Span::raw(" "),
@@ -509,7 +664,7 @@ fn render_controls_box(frame: &mut Frame, area: Rect, app: &App) {
         Line::from(vec![
             Span::raw(" "),
             Span::styled("Shift+E:", key_style),
-            Span::styled(" escape", desc_style),
+            Span::styled(" escape/eta", desc_style),
         ]),
         Line::from(vec![
             Span::raw(" "),
@@ -519,7 +674,7 @@ fn render_controls_box(frame: &mut Frame, area: Rect, app: &App) {
         Line::from(vec![
             Span::raw(" "),
             Span::styled("Shift+G:", key_style),
-            Span::styled(" gradient", desc_style),
+            Span::styled(" grad/growth", desc_style),
         ]),
         Line::from(vec![
             Span::raw(" "),
@@ -531,10 +686,15 @@ fn render_controls_box(frame: &mut Frame, area: Rect, app: &App) {
             Span::styled("Shift+I:", key_style),
             Span::styled(" invert", desc_style),
         ]),
+        Line::from(vec![
+            Span::raw(" "),
+            Span::styled("Shift+L:", key_style),
+            Span::styled(" lattice", desc_style),
+        ]),
         Line::from(vec![
             Span::raw(" "),
             Span::styled("Shift+M:", key_style),
-            Span::styled(" 4 options", desc_style),
+            Span::styled(" 5 options", desc_style),
         ]),
         Line::from(vec![
             Span::raw(" "),
@@ -566,11 +726,21 @@ fn render_controls_box(frame: &mut Frame, area: Rect, app: &App) {
             Span::styled("Shift+T:", key_style),
             Span::styled(" tip", desc_style),
         ]),
+        Line::from(vec![
+            Span::raw(" "),
+            Span::styled("Shift+V:", key_style),
+            Span::styled(" 3D view", desc_style),
+        ]),
         Line::from(vec![
             Span::raw(" "),
             Span::styled("Shift+W:", key_style),
             Span::styled(" walk", desc_style),
         ]),
+        Line::from(vec![
+            Span::raw(" "),
+            Span::styled("Shift+Z:", key_style),
+            Span::styled(" voxels", desc_style),
+        ]),
         Line::from(vec![
             Span::raw(" "),
             Span::styled("`:", key_style),
@@ -698,26 +868,33 @@ fn render_two_column_params(frame: &mut Frame, area: Rect, app: &App) {
         ))
     };
 
-    // Left column content: Movement + Sticking (15 lines)
+    // Left column content: Movement + Sticking (22 lines)
     let left_content: Vec<Line<'_>> = vec![
         make_header("movement"),
         make_line("adaptive", if settings.adaptive_step { "on" } else { "off" }.to_string(), app.focus == Focus::AdaptiveStep),
         make_line("adapt fact", format!("{:.2}", settings.adaptive_step_factor), app.focus == Focus::AdaptiveFactor),
         make_line("direction", format!("{:.0}°", settings.walk_bias_angle), app.focus == Focus::Direction),
+        make_line("eta", format!("{:.2}", settings.dbm_eta), app.focus == Focus::Eta),
         make_line("force", format!("{:.2}", settings.walk_bias_strength), app.focus == Focus::Force),
+        make_line("growth", settings.growth_model.name().to_lowercase(), app.focus == Focus::GrowthModel),
+        make_line("jump", if settings.circle_jump { "on" } else { "off" }.to_string(), app.focus == Focus::Jump),
+        make_line("lat type", settings.lattice.name().to_lowercase(), app.focus == Focus::LatticeType),
         make_line("lattice", if settings.lattice_walk { "on" } else { "off" }.to_string(), app.focus == Focus::LatticeWalk),
+        make_line("mobility", format!("{:.2}", settings.cluster_mobility), app.focus == Focus::Mobility),
+        make_line("off-latt", if settings.off_lattice { "on" } else { "off" }.to_string(), app.focus == Focus::OffLattice),
         make_line("radial", format!("{:.2}", settings.radial_bias), app.focus == Focus::RadialBias),
+        make_line("voxels", if settings.voxels { "on" } else { "off" }.to_string(), app.focus == Focus::Voxels),
         make_line("walk", format!("{:.1}", settings.walk_step_size), app.focus == Focus::WalkStep),
         make_header("sticking"),
         make_line("contacts", format!("{}", settings.multi_contact_min), app.focus == Focus::MultiContact),
         make_line("gradient", format!("{:.1}", settings.stickiness_gradient), app.focus == Focus::StickyGradient),
-        make_line("neighbors", settings.neighborhood.short_name().to_lowercase(), app.focus == Focus::Neighborhood),
+        make_line("neighbors", neighbors_label(settings), app.focus == Focus::Neighborhood),
         make_line("sticky", format!("{:.2}", app.simulation.stickiness), app.focus == Focus::Stickiness),
         make_line("side", format!("{:.1}", settings.side_stickiness), app.focus == Focus::SideSticky),
         make_line("tip", format!("{:.1}", settings.tip_stickiness), app.focus == Focus::TipSticky),
     ];
 
-    // Right column content: Spawn + Visual (16 lines)
+    // Right column content: Spawn + Visual (19 lines)
     let right_content: Vec<Line<'_>> = vec![
         make_header("spawn"),
         make_line("bound", settings.boundary_behavior.name().to_lowercase(), app.focus == Focus::Boundary),
@@ -726,6 +903,7 @@ fn render_two_column_params(frame: &mut Frame, area: Rect, app: &App) {
         make_line("min radius", format!("{:.0}", settings.min_spawn_radius), app.focus == Focus::MinRadius),
         make_line("spawn", settings.spawn_mode.name().to_lowercase(), app.focus == Focus::Spawn),
         make_line("spawn off", format!("{:.0}", settings.spawn_radius_offset), app.focus == Focus::SpawnOffset),
+        make_line("walkers", walkers_label(settings.walker_density), app.focus == Focus::Walkers),
         make_header("visual"),
         make_line("age", if app.color_by_age { "on" } else { "off" }.to_string(), app.focus == Focus::Age),
         make_line("color", app.color_scheme.name().to_lowercase(), app.focus == Focus::ColorScheme),
@@ -734,7 +912,9 @@ fn render_two_column_params(frame: &mut Frame, area: Rect, app: &App) {
         make_line("mode", settings.color_mode.name().to_lowercase(), app.focus == Focus::Mode),
         make_line("particles", format!("{}", app.simulation.num_particles), app.focus == Focus::Particles),
         make_line("seed", app.simulation.seed_pattern.name().to_lowercase(), app.focus == Focus::Seed),
+        make_line("slice", format!("z {}", app.simulation.slice_z()), app.focus == Focus::Slice),
         make_line("speed", format!("{}", app.steps_per_frame), app.focus == Focus::Speed),
+        make_line("view", settings.voxel_view.name().to_lowercase(), app.focus == Focus::VoxelView),
     ];
 
     // Calculate scroll for left column based on focused line (Movement + Sticking params)
@@ -742,16 +922,23 @@ fn render_two_column_params(frame: &mut Frame, area: Rect, app: &App) {
         Focus::AdaptiveStep => Some(1),
         Focus::AdaptiveFactor => Some(2),
         Focus::Direction => Some(3),
-        Focus::Force => Some(4),
-        Focus::LatticeWalk => Some(5),
-        Focus::RadialBias => Some(6),
-        Focus::WalkStep => Some(7),
-        Focus::MultiContact => Some(9),
-        Focus::StickyGradient => Some(10),
-        Focus::Neighborhood => Some(11),
-        Focus::Stickiness => Some(12),
-        Focus::SideSticky => Some(13),
-        Focus::TipSticky => Some(14),
+        Focus::Eta => Some(4),
+        Focus::Force => Some(5),
+        Focus::GrowthModel => Some(6),
+        Focus::Jump => Some(7),
+        Focus::LatticeType => Some(8),
+        Focus::LatticeWalk => Some(9),
+        Focus::Mobility => Some(10),
+        Focus::OffLattice => Some(11),
+        Focus::RadialBias => Some(12),
+        Focus::Voxels => Some(13),
+        Focus::WalkStep => Some(14),
+        Focus::MultiContact => Some(16),
+        Focus::StickyGradient => Some(17),
+        Focus::Neighborhood => Some(18),
+        Focus::Stickiness => Some(19),
+        Focus::SideSticky => Some(20),
+        Focus::TipSticky => Some(21),
         _ => None,
     };
 
@@ -763,14 +950,17 @@ fn render_two_column_params(frame: &mut Frame, area: Rect, app: &App) {
         Focus::MinRadius => Some(4),
         Focus::Spawn => Some(5),
         Focus::SpawnOffset => Some(6),
-        Focus::Age => Some(8),
-        Focus::ColorScheme => Some(9),
-        Focus::Highlight => Some(10),
-        Focus::Invert => Some(11),
-        Focus::Mode => Some(12),
-        Focus::Particles => Some(13),
-        Focus::Seed => Some(14),
-        Focus::Speed => Some(15),
+        Focus::Walkers => Some(7),
+        Focus::Age => Some(9),
+        Focus::ColorScheme => Some(10),
+        Focus::Highlight => Some(11),
+        Focus::Invert => Some(12),
+        Focus::Mode => Some(13),
+        Focus::Particles => Some(14),
+        Focus::Seed => Some(15),
+        Focus::Slice => Some(16),
+        Focus::Speed => Some(17),
+        Focus::VoxelView => Some(18),
         _ => None,
     };
 
@@ -811,6 +1001,26 @@ fn render_two_column_params(frame: &mut Frame, area: Rect, app: &App) {
     frame.render_widget(right_paragraph, columns[1]);
 }
 
+/// Walker density as shown in the params box ("1" = classic single walker)
+fn walkers_label(walker_density: f32) -> String {
+    if walker_density > 0.0 {
+        format!("{:.1}%", walker_density * 100.0)
+    } else {
+        "1".to_string()
+    }
+}
+
+/// Neighborhood as shown in the params box (3D and hex/tri lattices have their own)
+fn neighbors_label(settings: &SimulationSettings) -> String {
+    if settings.voxels {
+        return settings.neighborhood_3d.short_name().to_string();
+    }
+    match settings.active_lattice() {
+        LatticeType::Square => settings.neighborhood.short_name().to_lowercase(),
+        lattice => format!("{}-{}", lattice.neighbor_count(settings.neighborhood), lattice.name().to_lowercase()),
+    }
+}
+
 fn render_canvas(frame: &mut Frame, area: Rect, app: &App) {
     let block = styled_block("");
 
@@ -820,17 +1030,28 @@ fn render_canvas(frame: &mut Frame, area: Rect, app: &App) {
     // Get settings for rendering
     let settings = &app.simulation.settings;
 
-    // Render Braille pattern (uses LUT for fast color lookup)
-    let cells = braille::render_to_braille(
-        &app.simulation,
-        inner.width,
-        inner.height,
-        &app.color_lut,
-        app.color_by_age,
-        settings.color_mode,
-        settings.highlight_recent,
-        settings.invert_colors,
-    );
+    // Render Braille pattern (uses LUT for fast color lookup), or the probe heatmap
+    let cells = match &app.harmonic {
+        Some((measure, _)) => braille::render_harmonic_to_braille(
+            &app.simulation,
+            &app.current_viewport(),
+            inner.width,
+            inner.height,
+            measure,
+            &ColorScheme::Fire.build_lut(),
+        ),
+        None => braille::render_to_braille(
+            &app.simulation,
+            &app.current_viewport(),
+            inner.width,
+            inner.height,
+            &app.color_lut,
+            app.color_by_age,
+            settings.color_mode,
+            settings.highlight_recent,
+            settings.invert_colors,
+        ),
+    };
 
     for cell in cells {
         let x = inner.x + cell.x;
@@ -848,6 +1069,192 @@ fn render_canvas(frame: &mut Frame, area: Rect, app: &App) {
             frame.render_widget(paragraph, cell_rect);
         }
     }
+
+    if let Some((measure, spectrum)) = &app.harmonic {
+        render_probe_stats(frame, inner, measure, spectrum);
+    } else if !app.simulation.aggregation().is_empty() {
+        render_cluster_stats(frame, inner, app.simulation.aggregation());
+    }
+    // An analysis export keeps its results even while the panel is hidden
+    if let (true, Some(morphology)) = (app.show_morphology, &app.morphology) {
+        render_morphology_stats(frame, inner, morphology);
+    }
+}
+
+/// Dimension estimates with their confidence intervals, lacunarity and
+/// angular symmetry, in the top-left corner of the canvas
+fn render_morphology_stats(frame: &mut Frame, area: Rect, morphology: &Morphology) {
+    let label_style = Style::default().fg(DIM_TEXT_COLOR);
+    let value_style = Style::default().fg(TEXT_COLOR);
+
+    let mut content = vec![Line::from(Span::styled(
+        format!("D ± {:.0}% CI", morphology.confidence * 100.0),
+        label_style,
+    ))];
+    let names = ["mass-radius", "gyration", "correlation", "sandbox"];
+    for (name, (_, estimate)) in names.iter().zip(morphology.estimates()) {
+        let value = match estimate.map(|e| e.fit) {
+            Some(Some(fit)) => format!("{:.2} ± {:.2}", fit.dimension, fit.margin()),
+            Some(None) => "-".to_string(),
+            None => "n/a".to_string(),
+        };
+        content.push(Line::from(vec![
+            Span::styled(format!("{:<12}", name), label_style),
+            Span::styled(value, value_style),
+        ]));
+    }
+    if let (Some(&(first, low)), Some(&(last, high))) = (morphology.lacunarity.first(), morphology.lacunarity.last()) {
+        content.push(Line::from(vec![
+            Span::styled("lacunarity  ", label_style),
+            Span::styled(format!("{:.2} @{:.0} {:.2} @{:.0}", low, first, high, last), value_style),
+        ]));
+    }
+    // Strongest k-fold symmetry
+    if let Some((k, strength)) = morphology
+        .angular
+        .harmonics
+        .iter()
+        .enumerate()
+        .max_by(|a, b| a.1.total_cmp(b.1))
+    {
+        content.push(Line::from(vec![
+            Span::styled("symmetry    ", label_style),
+            Span::styled(format!("{}-fold {:.2}", k + 1, strength), value_style),
+        ]));
+    }
+    content.push(Line::from(vec![
+        Span::styled("center      ", label_style),
+        Span::styled(format!("{:.0},{:.0}", morphology.center[0], morphology.center[1]), value_style),
+        Span::styled(" r ", label_style),
+        Span::styled(format!("{:.0}", morphology.radius), value_style),
+    ]));
+
+    let width = MORPHOLOGY_WIDTH.min(area.width);
+    let height = (content.len() as u16 + 2).min(area.height);
+    let panel = Rect {
+        x: area.x,
+        y: area.y,
+        width,
+        height,
+    };
+    frame.render_widget(Clear, panel);
+    frame.render_widget(Paragraph::new(content).block(styled_block(" Morphology ")), panel);
+}
+
+/// Probe counts and generalized dimensions of the growth probe heatmap,
+/// in the top-right corner of the canvas
+fn render_probe_stats(frame: &mut Frame, area: Rect, measure: &HarmonicMeasure, spectrum: &MultifractalSpectrum) {
+    let label_style = Style::default().fg(DIM_TEXT_COLOR);
+    let value_style = Style::default().fg(TEXT_COLOR);
+    let dimension = |q| spectrum.dimension(q).map_or("-".to_string(), |d| format!("{:.2}", d));
+
+    let mut content = vec![
+        Line::from(vec![
+            Span::styled("probes ", label_style),
+            Span::styled(format!("{}", measure.probes), value_style),
+            Span::styled("  lost ", label_style),
+            Span::styled(format!("{}", measure.lost), value_style),
+        ]),
+        Line::from(vec![
+            Span::styled("cells hit ", label_style),
+            Span::styled(format!("{}", measure.site_count()), value_style),
+        ]),
+        Line::from(vec![
+            Span::styled("D0 ", label_style),
+            Span::styled(dimension(0.0), value_style),
+            Span::styled(" D1 ", label_style),
+            Span::styled(dimension(1.0), value_style),
+            Span::styled(" D2 ", label_style),
+            Span::styled(dimension(2.0), value_style),
+        ]),
+    ];
+    if let Some((min, max)) = spectrum.alpha_range() {
+        content.push(Line::from(vec![
+            Span::styled("alpha ", label_style),
+            Span::styled(format!("{:.2} - {:.2}", min, max), value_style),
+        ]));
+        // D_q falls off with q; its curve from the lowest to the highest q
+        let dimensions: Vec<u32> = spectrum.points.iter().map(|p| (p.dimension.max(0.0) * 1000.0) as u32).collect();
+        content.push(Line::from(Span::styled("D_q over q", label_style)));
+        content.push(Line::from(Span::styled(
+            sparkline(&dimensions, CLUSTER_STATS_WIDTH as usize - 2),
+            Style::default().fg(Color::Yellow),
+        )));
+    }
+
+    let width = CLUSTER_STATS_WIDTH.min(area.width);
+    let height = (content.len() as u16 + 2).min(area.height);
+    let panel = Rect {
+        x: area.x + area.width - width,
+        y: area.y,
+        width,
+        height,
+    };
+    frame.render_widget(Clear, panel);
+    frame.render_widget(Paragraph::new(content).block(styled_block(" Growth Probes ")), panel);
+}
+
+/// Cluster count over time and mass distribution of a cluster-cluster run,
+/// in the top-right corner of the canvas
+fn render_cluster_stats(frame: &mut Frame, area: Rect, aggregation: &ClusterAggregation) {
+    let label_style = Style::default().fg(DIM_TEXT_COLOR);
+    let value_style = Style::default().fg(TEXT_COLOR);
+
+    let mut content = vec![
+        Line::from(vec![
+            Span::styled("clusters ", label_style),
+            Span::styled(format!("{}", aggregation.cluster_count()), value_style),
+            Span::styled("  t ", label_style),
+            Span::styled(format!("{:.0}", aggregation.time()), value_style),
+        ]),
+        Line::from(vec![
+            Span::styled("largest ", label_style),
+            Span::styled(format!("{}", aggregation.largest()), value_style),
+            Span::styled("  mean ", label_style),
+            Span::styled(format!("{:.1}", aggregation.mean_size()), value_style),
+        ]),
+        Line::from(Span::styled(
+            sparkline(aggregation.history(), CLUSTER_STATS_WIDTH as usize - 2),
+            Style::default().fg(Color::Green),
+        )),
+        Line::from(Span::styled("mass      clusters", label_style)),
+    ];
+    let histogram = aggregation.size_histogram();
+    let most = histogram.iter().map(|&(_, _, count)| count).max().unwrap_or(0).max(1);
+    for (min_mass, max_mass, count) in histogram {
+        let range = if min_mass == max_mass {
+            format!("{}", min_mass)
+        } else {
+            format!("{}-{}", min_mass, max_mass)
+        };
+        let bar = (count * 8).div_ceil(most);
+        content.push(Line::from(vec![
+            Span::styled(format!("{:<10}", range), label_style),
+            Span::styled("█".repeat(bar), Style::default().fg(BORDER_COLOR)),
+            Span::styled(format!(" {}", count), value_style),
+        ]));
+    }
+
+    let width = CLUSTER_STATS_WIDTH.min(area.width);
+    let height = (content.len() as u16 + 2).min(area.height);
+    let panel = Rect {
+        x: area.x + area.width - width,
+        y: area.y,
+        width,
+        height,
+    };
+    frame.render_widget(Clear, panel);
+    frame.render_widget(Paragraph::new(content).block(styled_block(" Clusters ")), panel);
+}
+
+/// Values as a row of block characters scaled to the largest, resampled to at most `width` columns
+fn sparkline(values: &[u32], width: usize) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let max = values.iter().copied().max().unwrap_or(0).max(1) as f32;
+    let columns = width.min(values.len());
+    (0..columns)
+        .map(|i| BARS[(values[i * values.len() / columns] as f32 / max * 7.0).round() as usize])
+        .collect()
 }
 
 fn render_help_overlay(frame: &mut Frame, area: Rect, app: &App) {
@@ -892,7 +1299,14 @@ fn render_help_overlay(frame: &mut Frame, area: Rect, app: &App) {
         Line::from(Span::styled("Esc - Close help / exit focus", Style::default().fg(TEXT_COLOR))),
         Line::from(Span::styled("V - Cycle view (Default/States/Fullscreen)", Style::default().fg(TEXT_COLOR))),
         Line::from(Span::styled("` - Start/stop recording", Style::default().fg(TEXT_COLOR))),
-        Line::from(Span::styled("Shift+X - Export config to file", Style::default().fg(TEXT_COLOR))),
+        Line::from(Span::styled("Shift+X - Export config, graph, points or analysis", Style::default().fg(TEXT_COLOR))),
+        Line::from(Span::styled("P - Save cluster as PNG image", Style::default().fg(TEXT_COLOR))),
+        Line::from(Span::styled("O - Save full simulation snapshot", Style::default().fg(TEXT_COLOR))),
+        Line::from(Span::styled("L - Load simulation snapshot", Style::default().fg(TEXT_COLOR))),
+        Line::from(Span::styled("T - Browse presets (N: save current, D: delete)", Style::default().fg(TEXT_COLOR))),
+        Line::from(Span::styled("G - Probe growth probabilities (heatmap)", Style::default().fg(TEXT_COLOR))),
+        Line::from(Span::styled("D - Toggle morphology analysis panel", Style::default().fg(TEXT_COLOR))),
+        Line::from(Span::styled("E - Start/stop logging growth metrics", Style::default().fg(TEXT_COLOR))),
         Line::from(Span::styled("Q - Quit", Style::default().fg(TEXT_COLOR))),
         Line::from(""),
         Line::from(Span::styled("PARAMETER POPUP:", Style::default().fg(HIGHLIGHT_COLOR))),
@@ -905,8 +1319,12 @@ fn render_help_overlay(frame: &mut Frame, area: Rect, app: &App) {
         Line::from(Span::styled("QUICK KEYS:", Style::default().fg(HIGHLIGHT_COLOR))),
         Line::from(""),
         Line::from(Span::styled("1-0 - Seed patterns (Point to Scatter)", Style::default().fg(TEXT_COLOR))),
+        Line::from(Span::styled("Enter on seed - Seed panel (j/k adjust, X: default)", Style::default().fg(TEXT_COLOR))),
         Line::from(Span::styled("+/- - Adjust speed", Style::default().fg(TEXT_COLOR))),
         Line::from(Span::styled("[/] - Adjust highlight count", Style::default().fg(TEXT_COLOR))),
+        Line::from(Span::styled(",/. - Zoom view out/in", Style::default().fg(TEXT_COLOR))),
+        Line::from(Span::styled("Ctrl+arrows - Pan view", Style::default().fg(TEXT_COLOR))),
+        Line::from(Span::styled("F - Fit view to the whole grid", Style::default().fg(TEXT_COLOR))),
         Line::from(""),
         Line::from(Span::styled("DIRECT PARAM KEYS:", Style::default().fg(HIGHLIGHT_COLOR))),
         Line::from(""),
@@ -925,11 +1343,18 @@ fn render_help_overlay(frame: &mut Frame, area: Rect, app: &App) {
         Line::from("Direction (0-360) - Bias angle"),
         Line::from("Force (0-0.5) - Bias strength"),
         Line::from("Radial (-0.3 to 0.3) - Inward/outward drift"),
+        Line::from("Jump - Circle jumps far from the cluster"),
+        Line::from("Lattice Type - Square/Hex/Tri cells"),
+        Line::from("Off-Lattice - Disc particles, continuous space"),
+        Line::from("Voxels - 3D cluster in a cubic volume"),
+        Line::from("Growth - DLA/RLA/Ballistic/Eden/DBM/DLCA"),
+        Line::from("Eta (0-5) - DBM field exponent: 0 compact, 1 DLA, >1 lightning"),
+        Line::from("Mobility (0-2) - DLCA: clusters of mass s move at rate s^-mobility"),
         Line::from(""),
         Line::from(Span::styled("STICKING PARAMETERS:", Style::default().fg(HIGHLIGHT_COLOR))),
         Line::from(""),
         Line::from("Stickiness (0.1-1.0) - Base stick chance"),
-        Line::from("Neighborhood - VonNeumann/Moore/Extended"),
+        Line::from("Neighborhood - VonNeumann/Moore/Extended (6/18/26 in 3D, fixed on hex/tri)"),
         Line::from("Multi-Contact (1-4) - Min neighbors to stick"),
         Line::from("Tip/Side Sticky - Stickiness by position"),
         Line::from("Gradient - Distance-based stickiness"),
@@ -937,8 +1362,9 @@ fn render_help_overlay(frame: &mut Frame, area: Rect, app: &App) {
         Line::from(Span::styled("SPAWN/BOUNDARY:", Style::default().fg(HIGHLIGHT_COLOR))),
         Line::from(""),
         Line::from("Spawn - Circle/Edges/Corners/Random/Dir"),
-        Line::from("Boundary - Clamp/Wrap/Bounce/Stick/Absorb"),
+        Line::from("Boundary - Clamp/Wrap/Bounce/Stick/Absorb/Unbounded"),
         Line::from("Offset/Escape/MinRadius/MaxIter"),
+        Line::from("Walkers (0-10%) - Concurrent walkers per cell"),
         Line::from(""),
         Line::from(Span::styled("VISUAL PARAMETERS:", Style::default().fg(HIGHLIGHT_COLOR))),
         Line::from(""),
@@ -946,6 +1372,8 @@ fn render_help_overlay(frame: &mut Frame, area: Rect, app: &App) {
         Line::from("Speed (1-100) - Steps per frame"),
         Line::from("Color - 8 schemes, 4 modes"),
         Line::from("Highlight (0-50) - Recent particles in white"),
+        Line::from("Slice - Depth of the 3D slice view"),
+        Line::from("View - 3D slice/projection/rotate"),
         Line::from(""),
     ];
 
@@ -1045,10 +1473,162 @@ fn render_param_popup(frame: &mut Frame, area: Rect, popup: &ParamPopup) {
     frame.render_widget(paragraph, popup_area);
 }
 
-/// Render text input popup for export filename
-fn render_export_popup(frame: &mut Frame, area: Rect, popup: &TextInputPopup) {
+/// Render preset browser with the selected preset's description
+fn render_preset_popup(frame: &mut Frame, area: Rect, app: &App, popup: &PresetPopup) {
+    let builtin_count = app.preset_manager.builtin.len();
+    let preset_count = app.preset_count();
+
+    let popup_width = 48.min(area.width.saturating_sub(4));
+    // List + separator + description + key hints, plus borders
+    let popup_height = (preset_count as u16 + 6).min(area.height.saturating_sub(4)).max(8);
+
+    let popup_x = area.x + (area.width.saturating_sub(popup_width)) / 2;
+    let popup_y = area.y + (area.height.saturating_sub(popup_height)) / 2;
+
+    let popup_area = Rect {
+        x: popup_x,
+        y: popup_y,
+        width: popup_width,
+        height: popup_height,
+    };
+
+    frame.render_widget(Clear, popup_area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Double)
+        .border_style(Style::default().fg(HIGHLIGHT_COLOR))
+        .title(" Presets ");
+    let inner = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(4)])
+        .split(inner);
+
+    // Preset list (user presets marked with *)
+    let content: Vec<Line> = app
+        .preset_manager
+        .all_presets()
+        .enumerate()
+        .map(|(idx, preset)| {
+            let is_selected = idx == popup.selected_idx;
+            let prefix = if is_selected { "> " } else { "  " };
+            let marker = if idx >= builtin_count { " *" } else { "" };
+            let style = if is_selected {
+                Style::default()
+                    .fg(HIGHLIGHT_COLOR)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(TEXT_COLOR)
+            };
+            Line::from(Span::styled(format!("{}{}{}", prefix, preset.name, marker), style))
+        })
+        .collect();
+
+    // Keep selection visible
+    let visible_height = layout[0].height;
+    let selected = popup.selected_idx as u16;
+    let scroll = if visible_height == 0 || selected < visible_height {
+        0
+    } else {
+        selected.saturating_sub(visible_height - 1)
+    };
+    frame.render_widget(Paragraph::new(content).scroll((scroll, 0)), layout[0]);
+
+    // Description of the selected preset + key hints
+    let description = app
+        .selected_preset()
+        .map(|p| p.description.clone())
+        .unwrap_or_default();
+    let footer = vec![
+        Line::from(Span::styled("─".repeat(layout[1].width as usize), Style::default().fg(BORDER_COLOR))),
+        Line::from(Span::styled(description, Style::default().fg(TEXT_COLOR))),
+        Line::from(""),
+        Line::from(Span::styled(
+            "Enter: apply | N: save current | D: delete",
+            Style::default().fg(DIM_TEXT_COLOR),
+        )),
+    ];
+    frame.render_widget(Paragraph::new(footer), layout[1]);
+}
+
+/// Render the seed panel: the current pattern's parameters with their values
+fn render_seed_popup(frame: &mut Frame, area: Rect, app: &App, popup: &SeedPopup) {
+    let sim = &app.simulation;
+    let params = sim.seed_pattern.params();
+
+    let popup_width = 36.min(area.width.saturating_sub(4));
+    // Parameter list + separator + key hints, plus borders
+    let popup_height = (params.len().max(1) as u16 + 4).min(area.height.saturating_sub(4)).max(6);
+
+    let popup_x = area.x + (area.width.saturating_sub(popup_width)) / 2;
+    let popup_y = area.y + (area.height.saturating_sub(popup_height)) / 2;
+
+    let popup_area = Rect {
+        x: popup_x,
+        y: popup_y,
+        width: popup_width,
+        height: popup_height,
+    };
+
+    frame.render_widget(Clear, popup_area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Double)
+        .border_style(Style::default().fg(HIGHLIGHT_COLOR))
+        .title(format!(" Seed: {} ", sim.seed_pattern.name()));
+    let inner = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(2)])
+        .split(inner);
+
+    // Parameters (defaults marked as auto)
+    let content: Vec<Line> = if params.is_empty() {
+        vec![Line::from(Span::styled("  No parameters", Style::default().fg(DIM_TEXT_COLOR)))]
+    } else {
+        params
+            .iter()
+            .enumerate()
+            .map(|(idx, &param)| {
+                let is_selected = idx == popup.selected_idx;
+                let prefix = if is_selected { "> " } else { "  " };
+                let value = sim.seed_value(param);
+                let value = if value.fract() == 0.0 { format!("{:.0}", value) } else { format!("{:.1}", value) };
+                let auto = if sim.seed_params.get(param).is_none() { " (auto)" } else { "" };
+                let style = if is_selected {
+                    Style::default()
+                        .fg(HIGHLIGHT_COLOR)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(TEXT_COLOR)
+                };
+                Line::from(Span::styled(
+                    format!("{}{:<10} {}{}", prefix, param.name(), value, auto),
+                    style,
+                ))
+            })
+            .collect()
+    };
+    frame.render_widget(Paragraph::new(content), layout[0]);
+
+    let footer = vec![
+        Line::from(Span::styled("─".repeat(layout[1].width as usize), Style::default().fg(BORDER_COLOR))),
+        Line::from(Span::styled("j/k: adjust | X: default | Esc", Style::default().fg(DIM_TEXT_COLOR))),
+    ];
+    frame.render_widget(Paragraph::new(footer), layout[1]);
+}
+
+/// Render text input popup for export filename (config, PNG or snapshot),
+/// with an optional hint line about the accepted extensions
+fn render_export_popup(frame: &mut Frame, area: Rect, popup: &TextInputPopup, hint: Option<&str>) {
     let popup_width = 44.min(area.width.saturating_sub(4));
-    let popup_height = 5;
+    let popup_height = if hint.is_some() { 6 } else { 5 };
 
     let popup_x = area.x + (area.width.saturating_sub(popup_width)) / 2;
     let popup_y = area.y + (area.height.saturating_sub(popup_height)) / 2;
@@ -1064,7 +1644,7 @@ fn render_export_popup(frame: &mut Frame, area: Rect, popup: &TextInputPopup) {
 
     // Build input line with cursor
     let (before_cursor, after_cursor) = popup.input.split_at(popup.cursor_pos);
-    let content = vec![
+    let mut content = vec![
         Line::from(vec![
             Span::styled(before_cursor, Style::default().fg(TEXT_COLOR)),
             Span::styled(
@@ -1076,11 +1656,14 @@ fn render_export_popup(frame: &mut Frame, area: Rect, popup: &TextInputPopup) {
             Span::styled(after_cursor, Style::default().fg(TEXT_COLOR)),
         ]),
         Line::from(""),
-        Line::from(Span::styled(
-            "Enter: save | Esc: cancel",
-            Style::default().fg(DIM_TEXT_COLOR),
-        )),
     ];
+    if let Some(hint) = hint {
+        content.push(Line::from(Span::styled(hint, Style::default().fg(DIM_TEXT_COLOR))));
+    }
+    content.push(Line::from(Span::styled(
+        "Enter: save | Esc: cancel",
+        Style::default().fg(DIM_TEXT_COLOR),
+    )));
 
     let block = Block::default()
         .borders(Borders::ALL)
@@ -0,0 +1,112 @@
+//! Viewports map a rectangle of simulation cells onto an output raster.
+//!
+//! The terminal canvas, video frames and PNG exports all sample the sparse
+//! grid through a viewport, so what is shown no longer depends on the grid
+//! size and the cluster can be panned and zoomed independently of it.
+
+/// Rectangle of simulation cells (in cell units, may be fractional)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Viewport {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Viewport {
+    /// Viewport covering the half-open cell bounds (min_x, min_y, max_x, max_y) exactly
+    pub fn from_bounds(bounds: (i32, i32, i32, i32)) -> Self {
+        let (x0, y0, x1, y1) = bounds;
+        Self {
+            x: x0 as f32,
+            y: y0 as f32,
+            width: (x1 - x0).max(1) as f32,
+            height: (y1 - y0).max(1) as f32,
+        }
+    }
+
+    /// Smallest viewport showing all of `bounds` on an `out_w` x `out_h` raster
+    /// with square pixels, centered on the bounds
+    pub fn fit(bounds: (i32, i32, i32, i32), out_w: usize, out_h: usize) -> Self {
+        let full = Self::from_bounds(bounds);
+        let (out_w, out_h) = (out_w.max(1) as f32, out_h.max(1) as f32);
+        let cells_per_pixel = (full.width / out_w).max(full.height / out_h);
+        let (cx, cy) = full.center();
+        Self::centered(cx, cy, out_w * cells_per_pixel, out_h * cells_per_pixel)
+    }
+
+    fn centered(cx: f32, cy: f32, width: f32, height: f32) -> Self {
+        Self {
+            x: cx - width / 2.0,
+            y: cy - height / 2.0,
+            width,
+            height,
+        }
+    }
+
+    pub fn center(&self) -> (f32, f32) {
+        (self.x + self.width / 2.0, self.y + self.height / 2.0)
+    }
+
+    /// Point of the walk domain sampled by pixel (px, py) of an `out_w` x `out_h` raster
+    #[inline]
+    pub fn point_at(&self, px: usize, py: usize, out_w: usize, out_h: usize) -> (f32, f32) {
+        (
+            self.x + px as f32 * self.width / out_w as f32,
+            self.y + py as f32 * self.height / out_h as f32,
+        )
+    }
+
+    /// Square cell sampled by pixel (px, py) of an `out_w` x `out_h` raster
+    #[inline]
+    pub fn cell_at(&self, px: usize, py: usize, out_w: usize, out_h: usize) -> (i32, i32) {
+        let (x, y) = self.point_at(px, py, out_w, out_h);
+        (x.floor() as i32, y.floor() as i32)
+    }
+
+    /// Zoom around the center (factor > 1 zooms in)
+    pub fn zoomed(&self, factor: f32) -> Self {
+        let (cx, cy) = self.center();
+        Self::centered(cx, cy, self.width / factor, self.height / factor)
+    }
+
+    /// Move by a fraction of the viewport size
+    pub fn panned(&self, dx: f32, dy: f32) -> Self {
+        Self {
+            x: self.x + dx * self.width,
+            y: self.y + dy * self.height,
+            ..*self
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_bounds_maps_pixels_to_cells() {
+        let view = Viewport::from_bounds((-10, 0, 30, 20));
+        // 2x scale: two pixels per cell
+        assert_eq!(view.cell_at(0, 0, 80, 40), (-10, 0));
+        assert_eq!(view.cell_at(1, 1, 80, 40), (-10, 0));
+        assert_eq!(view.cell_at(2, 3, 80, 40), (-9, 1));
+        assert_eq!(view.cell_at(79, 39, 80, 40), (29, 19));
+    }
+
+    #[test]
+    fn test_fit_keeps_aspect_and_center() {
+        // Wide bounds on a square raster: width limits, height is padded
+        let view = Viewport::fit((0, 0, 200, 100), 100, 100);
+        assert_eq!((view.width, view.height), (200.0, 200.0));
+        assert_eq!(view.center(), (100.0, 50.0));
+    }
+
+    #[test]
+    fn test_zoom_and_pan() {
+        let view = Viewport::from_bounds((0, 0, 100, 100)).zoomed(2.0);
+        assert_eq!((view.x, view.y, view.width), (25.0, 25.0, 50.0));
+        let moved = view.panned(0.5, -0.1);
+        assert_eq!((moved.x, moved.y), (50.0, 20.0));
+    }
+}
@@ -0,0 +1,239 @@
+//! Voxel storage for 3D DLA.
+//!
+//! Occupancy is a dense bitset over the voxel box (one bit per voxel, so a
+//! 256^3 box costs 2 MiB) and particle data lives in a list kept in sticking
+//! order, which is all the walkers, views and exporters need. The species of
+//! voxels past the first is kept in a sparse map for the stickiness matrix.
+//! Voxels can be saved as a point cloud for 3D viewers.
+
+use crate::simulation::ParticleData;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+/// A stuck voxel and its particle data
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Voxel {
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+    pub data: ParticleData,
+}
+
+/// Occupied voxels inside a `width` x `height` x `depth` box
+#[derive(Debug, Clone, Default)]
+pub struct VoxelGrid {
+    width: usize,
+    height: usize,
+    depth: usize,
+    bits: Vec<u64>,
+    voxels: Vec<Voxel>,
+    /// Species by voxel index, for voxels that aren't species 0
+    species: HashMap<usize, u8>,
+}
+
+impl VoxelGrid {
+    pub fn new(width: usize, height: usize, depth: usize) -> Self {
+        Self {
+            width,
+            height,
+            depth,
+            bits: vec![0; (width * height * depth).div_ceil(64)],
+            voxels: Vec::new(),
+            species: HashMap::new(),
+        }
+    }
+
+    /// Rebuild from saved voxels (voxels outside the box are dropped)
+    pub fn from_voxels(width: usize, height: usize, depth: usize, voxels: Vec<Voxel>) -> Self {
+        let mut grid = Self::new(width, height, depth);
+        for voxel in voxels {
+            grid.insert(voxel);
+        }
+        grid
+    }
+
+    /// Box size as (width, height, depth)
+    pub fn size(&self) -> (usize, usize, usize) {
+        (self.width, self.height, self.depth)
+    }
+
+    /// Remove every voxel, keeping the box size
+    pub fn clear(&mut self) {
+        self.bits.fill(0);
+        self.voxels.clear();
+        self.species.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.voxels.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.voxels.is_empty()
+    }
+
+    /// Stuck voxels in sticking order
+    pub fn voxels(&self) -> &[Voxel] {
+        &self.voxels
+    }
+
+    fn index(&self, x: i32, y: i32, z: i32) -> Option<usize> {
+        let inside = x >= 0
+            && (x as usize) < self.width
+            && y >= 0
+            && (y as usize) < self.height
+            && z >= 0
+            && (z as usize) < self.depth;
+        inside.then(|| (z as usize * self.height + y as usize) * self.width + x as usize)
+    }
+
+    /// True if (x, y, z) lies inside the box
+    pub fn contains(&self, x: i32, y: i32, z: i32) -> bool {
+        self.index(x, y, z).is_some()
+    }
+
+    #[inline]
+    pub fn is_occupied(&self, x: i32, y: i32, z: i32) -> bool {
+        self.index(x, y, z)
+            .is_some_and(|idx| self.bits[idx / 64] & (1 << (idx % 64)) != 0)
+    }
+
+    /// Add a voxel; returns false if it is outside the box or already taken
+    pub fn insert(&mut self, voxel: Voxel) -> bool {
+        let Some(idx) = self.index(voxel.x, voxel.y, voxel.z) else {
+            return false;
+        };
+        let (word, bit) = (idx / 64, 1 << (idx % 64));
+        if self.bits[word] & bit != 0 {
+            return false;
+        }
+        self.bits[word] |= bit;
+        if voxel.data.species != 0 {
+            self.species.insert(idx, voxel.data.species);
+        }
+        self.voxels.push(voxel);
+        true
+    }
+
+    /// Number of occupied voxels among the given neighbor offsets of (x, y, z)
+    pub fn count_neighbors(&self, x: i32, y: i32, z: i32, offsets: &[(i32, i32, i32)]) -> usize {
+        offsets
+            .iter()
+            .filter(|&&(dx, dy, dz)| self.is_occupied(x + dx, y + dy, z + dz))
+            .count()
+    }
+
+    /// Species of the voxel at (x, y, z), or None if it is empty
+    pub fn species_at(&self, x: i32, y: i32, z: i32) -> Option<u8> {
+        let idx = self.index(x, y, z)?;
+        (self.bits[idx / 64] & (1 << (idx % 64)) != 0).then(|| self.species.get(&idx).copied().unwrap_or(0))
+    }
+}
+
+/// Point cloud file format
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PointFormat {
+    /// One "x y z" line per voxel
+    Xyz,
+    /// ASCII PLY with each voxel's age as an extra vertex property
+    Ply,
+}
+
+impl PointFormat {
+    /// Point format named by the file extension: .xyz or .ply (None otherwise)
+    pub fn from_filename(filename: &str) -> Option<Self> {
+        let lower = filename.to_lowercase();
+        if lower.ends_with(".xyz") {
+            Some(PointFormat::Xyz)
+        } else if lower.ends_with(".ply") {
+            Some(PointFormat::Ply)
+        } else {
+            None
+        }
+    }
+}
+
+/// Write voxels as a point cloud of their cell centers' integer coordinates
+pub fn save_points(voxels: &[Voxel], path: &Path, format: PointFormat) -> Result<(), String> {
+    let file = File::create(path).map_err(|e| format!("Failed to create point file: {}", e))?;
+    let mut out = BufWriter::new(file);
+    let write = |out: &mut BufWriter<File>| -> std::io::Result<()> {
+        if format == PointFormat::Ply {
+            writeln!(out, "ply")?;
+            writeln!(out, "format ascii 1.0")?;
+            writeln!(out, "element vertex {}", voxels.len())?;
+            for property in ["int x", "int y", "int z", "uint age"] {
+                writeln!(out, "property {}", property)?;
+            }
+            writeln!(out, "end_header")?;
+        }
+        for v in voxels {
+            match format {
+                PointFormat::Xyz => writeln!(out, "{} {} {}", v.x, v.y, v.z)?,
+                PointFormat::Ply => writeln!(out, "{} {} {} {}", v.x, v.y, v.z, v.data.age)?,
+            }
+        }
+        out.flush()
+    };
+    write(&mut out).map_err(|e| format!("Failed to write point file: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn voxel(x: i32, y: i32, z: i32) -> Voxel {
+        Voxel {
+            x,
+            y,
+            z,
+            data: ParticleData::default(),
+        }
+    }
+
+    #[test]
+    fn test_insert_and_neighbors() {
+        use crate::settings::Neighborhood3d;
+        let mut grid = VoxelGrid::new(10, 10, 10);
+        assert!(grid.insert(voxel(5, 5, 5)));
+        assert!(!grid.insert(voxel(5, 5, 5)));
+        assert!(!grid.insert(voxel(10, 0, 0)));
+        assert_eq!(grid.len(), 1);
+
+        // Face, edge and corner neighbors of the voxel
+        for (pos, faces, edges, corners) in [((5, 5, 6), 1, 1, 1), ((6, 6, 5), 0, 1, 1), ((4, 4, 4), 0, 0, 1)] {
+            let (x, y, z) = pos;
+            assert_eq!(grid.count_neighbors(x, y, z, Neighborhood3d::Faces.offsets()), faces);
+            assert_eq!(grid.count_neighbors(x, y, z, Neighborhood3d::Edges.offsets()), edges);
+            assert_eq!(grid.count_neighbors(x, y, z, Neighborhood3d::Corners.offsets()), corners);
+        }
+    }
+
+    #[test]
+    fn test_point_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let voxels = [voxel(1, 2, 3), voxel(4, 5, 6)];
+
+        let xyz = dir.path().join("cluster.xyz");
+        save_points(&voxels, &xyz, PointFormat::from_filename("cluster.XYZ").unwrap()).unwrap();
+        assert_eq!(std::fs::read_to_string(&xyz).unwrap(), "1 2 3\n4 5 6\n");
+
+        let ply = dir.path().join("cluster.ply");
+        save_points(&voxels, &ply, PointFormat::from_filename("cluster.ply").unwrap()).unwrap();
+        let ply = std::fs::read_to_string(&ply).unwrap();
+        assert!(ply.starts_with("ply\nformat ascii 1.0\nelement vertex 2\n"));
+        assert!(ply.ends_with("end_header\n1 2 3 0\n4 5 6 0\n"));
+        assert_eq!(PointFormat::from_filename("cluster.json"), None);
+    }
+
+    #[test]
+    fn test_neighborhood_sizes() {
+        use crate::settings::Neighborhood3d;
+        assert_eq!(Neighborhood3d::Faces.offsets().len(), 6);
+        assert_eq!(Neighborhood3d::Edges.offsets().len(), 18);
+        assert_eq!(Neighborhood3d::Corners.offsets().len(), 26);
+    }
+}